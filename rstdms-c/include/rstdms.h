@@ -0,0 +1,89 @@
+/*
+ * C interface to the rstdms TDMS reader.
+ *
+ * Functions returning int64_t lengths or counts return -1 on error, and functions
+ * returning int32_t status codes return 0 on success and -1 on error. The message for
+ * the most recent error on the current thread is returned by rstdms_last_error.
+ * Data type ids are written to an output parameter, as DAQmx raw data uses 0xFFFFFFFF.
+ *
+ * Functions that copy strings into a caller provided buffer always nul terminate the
+ * buffer, truncating the string if required, and return the full length of the string
+ * in bytes. Pass a null buffer to query the length.
+ *
+ * When accessing properties, a null channel name refers to a group and a null group
+ * and channel name refers to the root object of the file.
+ */
+
+#ifndef RSTDMS_H
+#define RSTDMS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct RstdmsFile RstdmsFile;
+
+const char *rstdms_last_error(void);
+
+RstdmsFile *rstdms_open(const char *path);
+void rstdms_close(RstdmsFile *file);
+
+int64_t rstdms_group_count(const RstdmsFile *file);
+int64_t rstdms_group_name(const RstdmsFile *file, size_t index, char *buffer, size_t buffer_len);
+
+int64_t rstdms_channel_count(const RstdmsFile *file, const char *group_name);
+int64_t rstdms_channel_name(const RstdmsFile *file, const char *group_name, size_t index,
+                            char *buffer, size_t buffer_len);
+int64_t rstdms_channel_length(const RstdmsFile *file, const char *group_name,
+                              const char *channel_name);
+int32_t rstdms_channel_data_type(const RstdmsFile *file, const char *group_name,
+                                 const char *channel_name, uint32_t *data_type);
+
+int64_t rstdms_read_i8(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                       int8_t *buffer, size_t buffer_len);
+int64_t rstdms_read_i16(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                        int16_t *buffer, size_t buffer_len);
+int64_t rstdms_read_i32(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                        int32_t *buffer, size_t buffer_len);
+int64_t rstdms_read_i64(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                        int64_t *buffer, size_t buffer_len);
+int64_t rstdms_read_u8(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                       uint8_t *buffer, size_t buffer_len);
+int64_t rstdms_read_u16(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                        uint16_t *buffer, size_t buffer_len);
+int64_t rstdms_read_u32(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                        uint32_t *buffer, size_t buffer_len);
+int64_t rstdms_read_u64(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                        uint64_t *buffer, size_t buffer_len);
+int64_t rstdms_read_f32(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                        float *buffer, size_t buffer_len);
+int64_t rstdms_read_f64(const RstdmsFile *file, const char *group_name, const char *channel_name,
+                        double *buffer, size_t buffer_len);
+
+int64_t rstdms_property_count(const RstdmsFile *file, const char *group_name,
+                              const char *channel_name);
+int64_t rstdms_property_name(const RstdmsFile *file, const char *group_name,
+                             const char *channel_name, size_t index, char *buffer,
+                             size_t buffer_len);
+int32_t rstdms_property_type(const RstdmsFile *file, const char *group_name,
+                             const char *channel_name, const char *property_name,
+                             uint32_t *data_type);
+int32_t rstdms_property_f64(const RstdmsFile *file, const char *group_name,
+                            const char *channel_name, const char *property_name, double *value);
+int32_t rstdms_property_i64(const RstdmsFile *file, const char *group_name,
+                            const char *channel_name, const char *property_name, int64_t *value);
+int32_t rstdms_property_timestamp(const RstdmsFile *file, const char *group_name,
+                                  const char *channel_name, const char *property_name,
+                                  int64_t *seconds, uint64_t *second_fractions);
+int64_t rstdms_property_string(const RstdmsFile *file, const char *group_name,
+                               const char *channel_name, const char *property_name,
+                               char *buffer, size_t buffer_len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RSTDMS_H */
@@ -0,0 +1,646 @@
+//! C interface to the rstdms TDMS reader.
+//!
+//! Functions that return an `int64_t` length or count return -1 on error,
+//! and functions that return an `int32_t` status return 0 on success and -1 on error.
+//! Panics are caught and reported as errors rather than unwinding into the caller.
+//! The message for the most recent error on the current thread can be retrieved
+//! with `rstdms_last_error`. Groups and channels are identified by name,
+//! and a null group name refers to the root object when accessing properties.
+
+use rstdms::{Channel, NativeType, TdmsFile, TdmsProperty, TdmsValue};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::fs::File;
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// An open TDMS file
+pub struct RstdmsFile {
+    inner: TdmsFile<File>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some(message));
+}
+
+/// Run an operation, recording any error or panic and returning `error_value` instead
+fn handle_error<T, F: FnOnce() -> Result<T, String>>(error_value: T, operation: F) -> T {
+    match panic::catch_unwind(AssertUnwindSafe(operation)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(message)) => {
+            set_last_error(message);
+            error_value
+        }
+        Err(payload) => {
+            let message = if let Some(message) = payload.downcast_ref::<&str>() {
+                message.to_string()
+            } else if let Some(message) = payload.downcast_ref::<String>() {
+                message.clone()
+            } else {
+                "Unknown error".to_owned()
+            };
+            set_last_error(format!("Panic in rstdms: {}", message));
+            error_value
+        }
+    }
+}
+
+unsafe fn to_str<'a>(string: *const c_char, name: &str) -> Result<&'a str, String> {
+    if string.is_null() {
+        return Err(format!("{} must not be null", name));
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map_err(|_| format!("{} is not valid UTF-8", name))
+}
+
+unsafe fn to_optional_str<'a>(
+    string: *const c_char,
+    name: &str,
+) -> Result<Option<&'a str>, String> {
+    if string.is_null() {
+        Ok(None)
+    } else {
+        to_str(string, name).map(Some)
+    }
+}
+
+unsafe fn to_file<'a>(file: *const RstdmsFile) -> Result<&'a TdmsFile<File>, String> {
+    file.as_ref()
+        .map(|file| &file.inner)
+        .ok_or_else(|| "file must not be null".to_owned())
+}
+
+/// Copy a string into a caller provided buffer, truncating it if necessary and always
+/// writing a nul terminator, and return the full length of the string in bytes
+unsafe fn copy_string(string: &str, buffer: *mut c_char, buffer_len: usize) -> i64 {
+    if !buffer.is_null() && buffer_len > 0 {
+        let copy_len = string.len().min(buffer_len - 1);
+        ptr::copy_nonoverlapping(string.as_ptr() as *const c_char, buffer, copy_len);
+        *buffer.add(copy_len) = 0;
+    }
+    string.len() as i64
+}
+
+/// Run an operation with a channel identified by group and channel name
+fn with_channel<T, F>(
+    file: &TdmsFile<File>,
+    group_name: &str,
+    channel_name: &str,
+    operation: F,
+) -> Result<T, String>
+where
+    F: FnOnce(&Channel<File>) -> Result<T, String>,
+{
+    let group = file
+        .groups()
+        .find(|group| group.name() == group_name)
+        .ok_or_else(|| format!("Invalid group name '{}'", group_name))?;
+    let channel = group.channel(channel_name).ok_or_else(|| {
+        format!(
+            "Invalid channel name '{}' in group '{}'",
+            channel_name, group_name
+        )
+    })?;
+    operation(&channel)
+}
+
+/// Run an operation with the properties of the root object, a group or a channel
+unsafe fn with_properties<T, F>(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    operation: F,
+) -> Result<T, String>
+where
+    F: FnOnce(&[TdmsProperty]) -> Result<T, String>,
+{
+    let file = to_file(file)?;
+    let group_name = to_optional_str(group_name, "group_name")?;
+    let channel_name = to_optional_str(channel_name, "channel_name")?;
+    match (group_name, channel_name) {
+        (None, None) => operation(file.properties()),
+        (Some(group_name), None) => {
+            let group = file
+                .groups()
+                .find(|group| group.name() == group_name)
+                .ok_or_else(|| format!("Invalid group name '{}'", group_name))?;
+            operation(group.properties())
+        }
+        (Some(group_name), Some(channel_name)) => {
+            with_channel(file, group_name, channel_name, |channel| {
+                operation(channel.properties())
+            })
+        }
+        (None, Some(_)) => Err("group_name must not be null when channel_name is set".to_owned()),
+    }
+}
+
+/// Run an operation with the value of a property, using the last value if the
+/// property was set multiple times
+unsafe fn with_property<T, F>(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    property_name: *const c_char,
+    operation: F,
+) -> Result<T, String>
+where
+    F: FnOnce(&TdmsValue) -> Result<T, String>,
+{
+    let property_name = to_str(property_name, "property_name")?;
+    with_properties(file, group_name, channel_name, |properties| {
+        let property = properties
+            .iter()
+            .rev()
+            .find(|property| property.name == property_name)
+            .ok_or_else(|| format!("Property '{}' not found", property_name))?;
+        operation(&property.value)
+    })
+}
+
+/// Get the message for the most recent error on the current thread, or null if there
+/// has been no error. The returned string is valid until the next error on this thread.
+#[no_mangle]
+pub extern "C" fn rstdms_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| match &*last_error.borrow() {
+        Some(message) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Open a TDMS file and read its metadata, returning null on error.
+///
+/// # Safety
+///
+/// `path` must be a valid nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_open(path: *const c_char) -> *mut RstdmsFile {
+    handle_error(ptr::null_mut(), || {
+        let path = to_str(path, "path")?;
+        let file = File::open(path).map_err(|e| format!("Error opening {}: {}", path, e))?;
+        let inner = TdmsFile::new(file).map_err(|e| e.to_string())?;
+        Ok(Box::into_raw(Box::new(RstdmsFile { inner })))
+    })
+}
+
+/// Close a file opened with `rstdms_open`. Passing null has no effect.
+///
+/// # Safety
+///
+/// `file` must be null or a pointer returned by `rstdms_open` that has not been closed.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_close(file: *mut RstdmsFile) {
+    if !file.is_null() {
+        drop(Box::from_raw(file));
+    }
+}
+
+/// Get the number of groups in a file
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_group_count(file: *const RstdmsFile) -> i64 {
+    handle_error(-1, || Ok(to_file(file)?.groups().count() as i64))
+}
+
+/// Copy the name of the group at the given index into `buffer`,
+/// returning the length of the name in bytes
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, and `buffer` must be null
+/// or valid for writes of `buffer_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_group_name(
+    file: *const RstdmsFile,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> i64 {
+    handle_error(-1, || {
+        let group = to_file(file)?
+            .groups()
+            .nth(index)
+            .ok_or_else(|| format!("Group index {} is out of range", index))?;
+        Ok(copy_string(group.name(), buffer, buffer_len))
+    })
+}
+
+/// Get the number of channels in a group
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`,
+/// and `group_name` must be a valid nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_channel_count(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+) -> i64 {
+    handle_error(-1, || {
+        let group_name = to_str(group_name, "group_name")?;
+        let file = to_file(file)?;
+        let group = file
+            .groups()
+            .find(|group| group.name() == group_name)
+            .ok_or_else(|| format!("Invalid group name '{}'", group_name))?;
+        Ok(group.channel_count() as i64)
+    })
+}
+
+/// Copy the name of the channel at the given index within a group into `buffer`,
+/// returning the length of the name in bytes
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, `group_name` must be a valid
+/// nul terminated string, and `buffer` must be null or valid for writes of `buffer_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_channel_name(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> i64 {
+    handle_error(-1, || {
+        let group_name = to_str(group_name, "group_name")?;
+        let file = to_file(file)?;
+        let group = file
+            .groups()
+            .find(|group| group.name() == group_name)
+            .ok_or_else(|| format!("Invalid group name '{}'", group_name))?;
+        let channel_names = group.channel_names();
+        let channel_name = channel_names
+            .get(index)
+            .ok_or_else(|| format!("Channel index {} is out of range", index))?;
+        Ok(copy_string(channel_name, buffer, buffer_len))
+    })
+}
+
+/// Get the number of values in a channel
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, and `group_name` and
+/// `channel_name` must be valid nul terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_channel_length(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+) -> i64 {
+    handle_error(-1, || {
+        with_channel(
+            to_file(file)?,
+            to_str(group_name, "group_name")?,
+            to_str(channel_name, "channel_name")?,
+            |channel| Ok(channel.len() as i64),
+        )
+    })
+}
+
+/// Get the TDMS data type id of a channel, written to `data_type`
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, `group_name` and
+/// `channel_name` must be valid nul terminated strings and `data_type` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_channel_data_type(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    data_type: *mut u32,
+) -> i32 {
+    handle_error(-1, || {
+        if data_type.is_null() {
+            return Err("data_type must not be null".to_owned());
+        }
+        let value = with_channel(
+            to_file(file)?,
+            to_str(group_name, "group_name")?,
+            to_str(channel_name, "channel_name")?,
+            |channel| Ok(channel.data_type() as u32),
+        )?;
+        *data_type = value;
+        Ok(0)
+    })
+}
+
+unsafe fn read_channel<T: NativeType>(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    buffer: *mut T,
+    buffer_len: usize,
+) -> i64 {
+    handle_error(-1, || {
+        if buffer.is_null() {
+            return Err("buffer must not be null".to_owned());
+        }
+        let buffer = std::slice::from_raw_parts_mut(buffer, buffer_len);
+        with_channel(
+            to_file(file)?,
+            to_str(group_name, "group_name")?,
+            to_str(channel_name, "channel_name")?,
+            |channel| {
+                channel.read_all_data(buffer).map_err(|e| e.to_string())?;
+                Ok(channel.len() as i64)
+            },
+        )
+    })
+}
+
+macro_rules! read_channel_fn {
+    ($name:ident, $type:ty) => {
+        /// Read all values of a channel into `buffer`, which must be at least as long as
+        /// the channel, returning the number of values read.
+        ///
+        /// # Safety
+        ///
+        /// `file` must be a valid pointer returned by `rstdms_open`, `group_name` and
+        /// `channel_name` must be valid nul terminated strings, and `buffer` must be valid
+        /// for writes of `buffer_len` values.
+        #[no_mangle]
+        pub unsafe extern "C" fn $name(
+            file: *const RstdmsFile,
+            group_name: *const c_char,
+            channel_name: *const c_char,
+            buffer: *mut $type,
+            buffer_len: usize,
+        ) -> i64 {
+            read_channel(file, group_name, channel_name, buffer, buffer_len)
+        }
+    };
+}
+
+read_channel_fn!(rstdms_read_i8, i8);
+read_channel_fn!(rstdms_read_i16, i16);
+read_channel_fn!(rstdms_read_i32, i32);
+read_channel_fn!(rstdms_read_i64, i64);
+read_channel_fn!(rstdms_read_u8, u8);
+read_channel_fn!(rstdms_read_u16, u16);
+read_channel_fn!(rstdms_read_u32, u32);
+read_channel_fn!(rstdms_read_u64, u64);
+read_channel_fn!(rstdms_read_f32, f32);
+read_channel_fn!(rstdms_read_f64, f64);
+
+/// Get the number of properties of an object. A null `channel_name` refers to the group,
+/// and a null `group_name` and `channel_name` refers to the root object.
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, and `group_name` and
+/// `channel_name` must be null or valid nul terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_property_count(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+) -> i64 {
+    handle_error(-1, || {
+        with_properties(file, group_name, channel_name, |properties| {
+            Ok(properties.len() as i64)
+        })
+    })
+}
+
+/// Copy the name of the property at the given index of an object into `buffer`,
+/// returning the length of the name in bytes
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, `group_name` and
+/// `channel_name` must be null or valid nul terminated strings, and `buffer` must be null
+/// or valid for writes of `buffer_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_property_name(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    index: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> i64 {
+    handle_error(-1, || {
+        with_properties(file, group_name, channel_name, |properties| {
+            let property = properties
+                .get(index)
+                .ok_or_else(|| format!("Property index {} is out of range", index))?;
+            Ok(copy_string(&property.name, buffer, buffer_len))
+        })
+    })
+}
+
+/// Get the TDMS data type id of a property value, written to `data_type`
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, `group_name` and
+/// `channel_name` must be null or valid nul terminated strings, `property_name`
+/// must be a valid nul terminated string and `data_type` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_property_type(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    property_name: *const c_char,
+    data_type: *mut u32,
+) -> i32 {
+    handle_error(-1, || {
+        if data_type.is_null() {
+            return Err("data_type must not be null".to_owned());
+        }
+        let value = with_property(file, group_name, channel_name, property_name, |value| {
+            Ok(value.data_type() as u32)
+        })?;
+        *data_type = value;
+        Ok(0)
+    })
+}
+
+/// Get the value of a numeric property as a double
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, `group_name` and
+/// `channel_name` must be null or valid nul terminated strings, `property_name`
+/// must be a valid nul terminated string and `value` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_property_f64(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    property_name: *const c_char,
+    value: *mut f64,
+) -> i32 {
+    handle_error(-1, || {
+        with_property(
+            file,
+            group_name,
+            channel_name,
+            property_name,
+            |property_value| {
+                let property_value = match property_value {
+                    TdmsValue::Int8(v) => *v as f64,
+                    TdmsValue::Int16(v) => *v as f64,
+                    TdmsValue::Int32(v) => *v as f64,
+                    TdmsValue::Int64(v) => *v as f64,
+                    TdmsValue::Uint8(v) => *v as f64,
+                    TdmsValue::Uint16(v) => *v as f64,
+                    TdmsValue::Uint32(v) => *v as f64,
+                    TdmsValue::Uint64(v) => *v as f64,
+                    TdmsValue::Float32(v) => *v as f64,
+                    TdmsValue::Float64(v) => *v,
+                    _ => return Err("Property is not numeric".to_owned()),
+                };
+                write_value(value, property_value)
+            },
+        )
+    })
+}
+
+/// Get the value of an integer property as a 64 bit signed integer
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, `group_name` and
+/// `channel_name` must be null or valid nul terminated strings, `property_name`
+/// must be a valid nul terminated string and `value` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_property_i64(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    property_name: *const c_char,
+    value: *mut i64,
+) -> i32 {
+    handle_error(-1, || {
+        with_property(
+            file,
+            group_name,
+            channel_name,
+            property_name,
+            |property_value| {
+                let property_value = match property_value {
+                    TdmsValue::Int8(v) => *v as i64,
+                    TdmsValue::Int16(v) => *v as i64,
+                    TdmsValue::Int32(v) => *v as i64,
+                    TdmsValue::Int64(v) => *v,
+                    TdmsValue::Uint8(v) => *v as i64,
+                    TdmsValue::Uint16(v) => *v as i64,
+                    TdmsValue::Uint32(v) => *v as i64,
+                    TdmsValue::Uint64(v) => i64::try_from(*v).map_err(|_| {
+                        "Property value is too large for a signed integer".to_owned()
+                    })?,
+                    _ => return Err("Property is not an integer".to_owned()),
+                };
+                write_value(value, property_value)
+            },
+        )
+    })
+}
+
+/// Get the value of a timestamp property as whole seconds since the TDMS epoch of
+/// 1904-01-01 00:00:00 UTC and positive fractions of a second in units of 2^-64 seconds
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, `group_name` and
+/// `channel_name` must be null or valid nul terminated strings, `property_name`
+/// must be a valid nul terminated string and `seconds` and `second_fractions`
+/// must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_property_timestamp(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    property_name: *const c_char,
+    seconds: *mut i64,
+    second_fractions: *mut u64,
+) -> i32 {
+    handle_error(-1, || {
+        with_property(
+            file,
+            group_name,
+            channel_name,
+            property_name,
+            |value| match value {
+                TdmsValue::Timestamp(timestamp) => {
+                    write_value(seconds, timestamp.seconds)?;
+                    write_value(second_fractions, timestamp.second_fractions)
+                }
+                _ => Err("Property is not a timestamp".to_owned()),
+            },
+        )
+    })
+}
+
+/// Copy the value of a string property into `buffer`,
+/// returning the length of the string in bytes
+///
+/// # Safety
+///
+/// `file` must be a valid pointer returned by `rstdms_open`, `group_name` and
+/// `channel_name` must be null or valid nul terminated strings, `property_name`
+/// must be a valid nul terminated string and `buffer` must be null or valid for writes
+/// of `buffer_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn rstdms_property_string(
+    file: *const RstdmsFile,
+    group_name: *const c_char,
+    channel_name: *const c_char,
+    property_name: *const c_char,
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> i64 {
+    handle_error(-1, || {
+        with_property(
+            file,
+            group_name,
+            channel_name,
+            property_name,
+            |value| match value {
+                TdmsValue::String(value) => Ok(copy_string(value, buffer, buffer_len)),
+                _ => Err("Property is not a string".to_owned()),
+            },
+        )
+    })
+}
+
+unsafe fn write_value<T>(target: *mut T, value: T) -> Result<i32, String> {
+    if target.is_null() {
+        return Err("Output pointer must not be null".to_owned());
+    }
+    *target = value;
+    Ok(0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn panics_are_reported_as_errors() {
+        let result = handle_error(-1, || -> Result<i64, String> {
+            panic!("Unsupported scaler")
+        });
+        assert_eq!(result, -1);
+        let message = unsafe { CStr::from_ptr(rstdms_last_error()) };
+        assert_eq!(
+            message.to_str().unwrap(),
+            "Panic in rstdms: Unsupported scaler"
+        );
+    }
+}
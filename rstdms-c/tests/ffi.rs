@@ -0,0 +1,191 @@
+use rstdms_c::*;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+/// Write a TDMS file with a root property and a single group
+/// with one I32 channel, returning its path
+fn write_test_file(name: &str) -> CString {
+    let mut metadata = Vec::new();
+    metadata.extend(2_u32.to_le_bytes());
+    // Root object with a string property
+    write_string("/", &mut metadata);
+    metadata.extend(0xFFFFFFFF_u32.to_le_bytes());
+    metadata.extend(1_u32.to_le_bytes());
+    write_string("name", &mut metadata);
+    metadata.extend(0x20_u32.to_le_bytes());
+    write_string("test file", &mut metadata);
+    // Channel with an integer property
+    write_string("/'Group'/'Channel1'", &mut metadata);
+    metadata.extend(20_u32.to_le_bytes());
+    metadata.extend(3_u32.to_le_bytes());
+    metadata.extend(1_u32.to_le_bytes());
+    metadata.extend(3_u64.to_le_bytes());
+    metadata.extend(1_u32.to_le_bytes());
+    write_string("count", &mut metadata);
+    metadata.extend(3_u32.to_le_bytes());
+    metadata.extend(42_i32.to_le_bytes());
+
+    let mut data = Vec::new();
+    for value in [1_i32, 2, 3] {
+        data.extend(value.to_le_bytes());
+    }
+
+    let mut bytes = Vec::new();
+    bytes.extend(b"TDSm");
+    bytes.extend(((1_u32 << 1) | (1 << 2) | (1 << 3)).to_le_bytes());
+    bytes.extend(4713_u32.to_le_bytes());
+    bytes.extend(((metadata.len() + data.len()) as u64).to_le_bytes());
+    bytes.extend((metadata.len() as u64).to_le_bytes());
+    bytes.extend(metadata);
+    bytes.extend(data);
+
+    let path = std::env::temp_dir().join(format!("rstdms_c_{}_{}.tdms", name, std::process::id()));
+    std::fs::write(&path, bytes).unwrap();
+    CString::new(path.to_str().unwrap()).unwrap()
+}
+
+fn write_string(string: &str, bytes: &mut Vec<u8>) {
+    bytes.extend((string.len() as u32).to_le_bytes());
+    bytes.extend(string.bytes());
+}
+
+fn c_str(string: &str) -> CString {
+    CString::new(string).unwrap()
+}
+
+#[test]
+fn read_file_through_c_interface() {
+    let path = write_test_file("read");
+    unsafe {
+        let file = rstdms_open(path.as_ptr());
+        assert!(!file.is_null());
+
+        assert_eq!(rstdms_group_count(file), 1);
+        let mut buffer = [0 as c_char; 32];
+        assert_eq!(
+            rstdms_group_name(file, 0, buffer.as_mut_ptr(), buffer.len()),
+            5
+        );
+        assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str().unwrap(), "Group");
+
+        let group = c_str("Group");
+        let channel = c_str("Channel1");
+        assert_eq!(rstdms_channel_count(file, group.as_ptr()), 1);
+        assert_eq!(
+            rstdms_channel_name(file, group.as_ptr(), 0, ptr::null_mut(), 0),
+            8
+        );
+        assert_eq!(
+            rstdms_channel_length(file, group.as_ptr(), channel.as_ptr()),
+            3
+        );
+        let mut data_type = 0;
+        assert_eq!(
+            rstdms_channel_data_type(file, group.as_ptr(), channel.as_ptr(), &mut data_type),
+            0
+        );
+        assert_eq!(data_type, 3);
+
+        let mut values = [0_i32; 3];
+        assert_eq!(
+            rstdms_read_i32(
+                file,
+                group.as_ptr(),
+                channel.as_ptr(),
+                values.as_mut_ptr(),
+                values.len()
+            ),
+            3
+        );
+        assert_eq!(values, [1, 2, 3]);
+
+        assert_eq!(rstdms_property_count(file, ptr::null(), ptr::null()), 1);
+        let name = c_str("name");
+        let mut buffer = [0 as c_char; 5];
+        assert_eq!(
+            rstdms_property_string(
+                file,
+                ptr::null(),
+                ptr::null(),
+                name.as_ptr(),
+                buffer.as_mut_ptr(),
+                buffer.len()
+            ),
+            9
+        );
+        assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str().unwrap(), "test");
+
+        let count = c_str("count");
+        assert_eq!(
+            rstdms_property_type(
+                file,
+                group.as_ptr(),
+                channel.as_ptr(),
+                count.as_ptr(),
+                &mut data_type
+            ),
+            0
+        );
+        assert_eq!(data_type, 3);
+
+        let mut value = 0.0;
+        assert_eq!(
+            rstdms_property_f64(
+                file,
+                group.as_ptr(),
+                channel.as_ptr(),
+                count.as_ptr(),
+                &mut value
+            ),
+            0
+        );
+        assert_eq!(value, 42.0);
+
+        rstdms_close(file);
+    }
+    std::fs::remove_file(path.to_str().unwrap()).unwrap();
+}
+
+#[test]
+fn errors_are_reported() {
+    let path = write_test_file("errors");
+    unsafe {
+        let file = rstdms_open(path.as_ptr());
+        let group = c_str("Group");
+        let channel = c_str("Channel1");
+
+        let mut values = [0.0_f64; 3];
+        assert_eq!(
+            rstdms_read_f64(
+                file,
+                group.as_ptr(),
+                channel.as_ptr(),
+                values.as_mut_ptr(),
+                values.len()
+            ),
+            -1
+        );
+        let message = CStr::from_ptr(rstdms_last_error()).to_str().unwrap();
+        assert!(
+            message.contains("Channel1"),
+            "Unexpected message: {}",
+            message
+        );
+
+        let missing = c_str("Missing");
+        assert_eq!(rstdms_channel_count(file, missing.as_ptr()), -1);
+        let message = CStr::from_ptr(rstdms_last_error()).to_str().unwrap();
+        assert_eq!(message, "Invalid group name 'Missing'");
+
+        assert_eq!(
+            rstdms_channel_data_type(file, group.as_ptr(), channel.as_ptr(), ptr::null_mut()),
+            -1
+        );
+        let message = CStr::from_ptr(rstdms_last_error()).to_str().unwrap();
+        assert_eq!(message, "data_type must not be null");
+
+        rstdms_close(file);
+    }
+    std::fs::remove_file(path.to_str().unwrap()).unwrap();
+}
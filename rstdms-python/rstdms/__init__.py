@@ -1,45 +1,497 @@
-import pyarrow as pa
-from pyarrow.cffi import ffi
+import os
+from datetime import datetime
+from html import escape
 
-from .rstdms import InternalTdmsFile
+import numpy as np
+
+from .rstdms import InternalTdmsFile, InternalTdmsWriter
+
+try:
+    import pyarrow as pa
+except ImportError:
+    pa = None
+
+
+# numpy data types of the TDMS data types that can be read
+_NUMPY_DTYPES = {
+    "I8": "int8",
+    "I16": "int16",
+    "I32": "int32",
+    "I64": "int64",
+    "U8": "uint8",
+    "U16": "uint16",
+    "U32": "uint32",
+    "U64": "uint64",
+    "SingleFloat": "float32",
+    "DoubleFloat": "float64",
+    "SingleFloatWithUnit": "float32",
+    "DoubleFloatWithUnit": "float64",
+    "String": "object",
+}
 
 
 class TdmsFile:
-    def __init__(self, path):
-        self._file = InternalTdmsFile(path)
-        self._groups = set(self._file.groups())
+    def __init__(self, path, index_path=None):
+        """ Open a TDMS file. Only metadata is read when opening the file,
+        and data is read when requested. If index_path is given, metadata is read
+        from that .tdms_index file rather than the data file.
+        """
+        self._file = InternalTdmsFile(
+                os.fspath(path),
+                None if index_path is None else os.fspath(index_path))
+
+    @staticmethod
+    def read_metadata(path):
+        """ Open a TDMS file, reading metadata from its .tdms_index file
+        if one exists, which is much faster than reading metadata spread
+        throughout a large data file
+        """
+        index_path = os.fspath(path) + "_index"
+        if not os.path.exists(index_path):
+            index_path = None
+        return TdmsFile(path, index_path)
 
     def __getitem__(self, group_name):
-        if group_name in self._groups:
-            return TdmsGroup(self._file, group_name)
-        raise KeyError(f"No group named '{group_name}' found")
+        try:
+            return TdmsGroup(self._file.group(group_name))
+        except ValueError:
+            raise KeyError(f"No group named '{group_name}' found") from None
+
+    def __contains__(self, group_name):
+        return group_name in self.group_names()
+
+    def __len__(self):
+        return len(self.group_names())
+
+    def __iter__(self):
+        """ Iterate over the names of groups in the file, like a dict
+        """
+        return iter(self.group_names())
+
+    def keys(self):
+        return self.group_names()
+
+    def values(self):
+        return self.groups()
+
+    def items(self):
+        return [(group.name, group) for group in self.groups()]
+
+    def group_names(self):
+        """ Get a list of the names of all groups in the file
+        """
+        return [group.name for group in self._file.groups()]
+
+    def groups(self):
+        """ Get a list of all groups in the file
+        """
+        return [TdmsGroup(group) for group in self._file.groups()]
+
+    def properties(self, raw_timestamps=False):
+        return self._file.properties(raw_timestamps)
+
+    def describe(self, raw_timestamps=False):
+        """ Get the whole hierarchy of the file as nested dicts, read in a single
+        call rather than one call per object. The result has the file "properties"
+        and a "groups" dict keyed by group name. Each group has "properties" and
+        a "channels" dict, and each channel has its "data_type", "dtype",
+        "length" and "properties".
+        """
+        description = self._file.describe(raw_timestamps)
+        for group in description["groups"].values():
+            for channel in group["channels"].values():
+                dtype = _NUMPY_DTYPES.get(channel["data_type"])
+                channel["dtype"] = None if dtype is None else np.dtype(dtype)
+        return description
+
+    def refresh(self):
+        """ Read any segments appended to the file since it was opened or
+        last refreshed, for monitoring a file that is still being written.
+        Returns the number of new segments read. Groups and channels retrieved
+        before refreshing include any new channels and data.
+        """
+        return self._file.refresh()
+
+    def data_chunks(self, group_name, channel_name, chunk_size, as_numpy=False):
+        """ Iterate over the data of a channel in chunks of at most chunk_size values
+        """
+        return self[group_name][channel_name].data_chunks(chunk_size, as_numpy)
+
+    def group_data(self, group_name, time_column=False):
+        """ Read the data of all channels in a group as an Arrow table
+        """
+        return self[group_name].read_all_data(time_column)
+
+    def _repr_html_(self):
+        description = self.describe()
+        channels = [
+            (group_name, channel_name, channel)
+            for (group_name, group) in description["groups"].items()
+            for (channel_name, channel) in group["channels"].items()
+        ]
+        return _html_summary(
+                "TdmsFile", description["properties"], channels)
+
+    def segments(self):
+        """ Describe each segment in the file, as a list of dicts with the
+        segment position, data position, table of contents flags and number
+        of objects, for debugging the structure of a file
+        """
+        return self._file.segments()
+
+    def layout_stats(self):
+        """ Get statistics describing how data is laid out in the file,
+        including the number of chunks the data of each channel is split into
+        and the fraction of data that is fragmented
+        """
+        return self._file.layout_stats()
 
 
 class TdmsGroup:
-    def __init__(self, file, group_name):
-        self._file = file
-        self._group_name = group_name
-        self._channels = set(self._file.group_channels(self._group_name))
+    def __init__(self, group):
+        self._group = group
+
+    @property
+    def name(self):
+        return self._group.name
 
     def __getitem__(self, channel_name):
-        if channel_name in self._channels:
-            return TdmsChannel(self._file, self._group_name, channel_name)
-        raise KeyError(
-                f"No channel named '{channel_name}' found in group "
-                f"'{self._group_name}'")
+        try:
+            return TdmsChannel(self._group.channel(channel_name))
+        except ValueError:
+            raise KeyError(
+                    f"No channel named '{channel_name}' found in group "
+                    f"'{self.name}'") from None
+
+    def __contains__(self, channel_name):
+        return channel_name in self.channel_names()
+
+    def __len__(self):
+        return len(self.channel_names())
+
+    def __iter__(self):
+        """ Iterate over the names of channels in this group, like a dict
+        """
+        return iter(self.channel_names())
+
+    def keys(self):
+        return self.channel_names()
+
+    def values(self):
+        return self.channels()
+
+    def items(self):
+        return [(channel.name, channel) for channel in self.channels()]
+
+    def channel_names(self):
+        """ Get a list of the names of all channels in this group
+        """
+        return [channel.name for channel in self._group.channels()]
+
+    def channels(self):
+        """ Get a list of all channels in this group
+        """
+        return [TdmsChannel(channel) for channel in self._group.channels()]
+
+    def properties(self, raw_timestamps=False):
+        return self._group.properties(raw_timestamps)
+
+    def read_all_data(self, time_column=False):
+        """ Read the data of all channels in this group as an Arrow table,
+        with one column per channel. The channels must all have the same length.
+        If time_column is true, a "time" column is added using the waveform
+        timing properties of the channels.
+        """
+        return _pyarrow().table(self._group.read_all_data(time_column))
+
+    def iter_batches(self, batch_size=65536, time_column=False):
+        """ Iterate over the data of this group as Arrow record batches of at
+        most batch_size rows, reading only one batch into memory at a time.
+        """
+        if batch_size < 1:
+            raise ValueError("batch_size must be positive")
+        length = self._group.len()
+        for start in range(0, length, batch_size):
+            yield _pyarrow().record_batch(
+                    self._group.read_data(start, batch_size, time_column))
+
+    def batch_reader(self, batch_size=65536, time_column=False):
+        """ Get an Arrow RecordBatchReader that reads the data of this group
+        in batches of at most batch_size rows, for streaming into pandas,
+        polars or duckdb without reading the whole group into memory
+        """
+        schema = _pyarrow().record_batch(
+                self._group.read_data(0, 0, time_column)).schema
+        return _pyarrow().RecordBatchReader.from_batches(
+                schema, self.iter_batches(batch_size, time_column))
+
+    def as_xarray(self, time_coordinate=True):
+        """ Convert this group to an xarray Dataset with one data variable per
+        channel. If time_coordinate is true, the dimension is "time" with
+        coordinates computed from the waveform timing of the channels,
+        otherwise the dimension is "index". Group and channel properties are
+        stored as attrs, with timestamps converted to ISO 8601 strings so the
+        dataset can be written to netCDF.
+        """
+        xr = _import_xarray()
+        table = self.read_all_data(time_column=time_coordinate)
+        dim = "time" if time_coordinate else "index"
+        channels = {channel.name: channel for channel in self.channels()}
+        data_vars = {
+            name: xr.Variable(
+                dim,
+                table.column(name).to_numpy(),
+                attrs=_attrs(channels[name].properties()))
+            for name in channels
+        }
+        coords = {}
+        if time_coordinate:
+            coords["time"] = table.column("time").to_numpy()
+        return xr.Dataset(
+                data_vars, coords=coords, attrs=_attrs(self.properties()))
+
+    def __repr__(self):
+        return f"<TdmsGroup with path /'{self.name}'>"
+
+    def _repr_html_(self):
+        channels = [
+            (self.name, channel.name, {
+                "data_type": channel.data_type,
+                "length": len(channel),
+                "properties": channel.properties(),
+            })
+            for channel in self.channels()
+        ]
+        return _html_summary(
+                f"TdmsGroup /'{self.name}'", self.properties(), channels)
 
 
 class TdmsChannel:
-    def __init__(self, file, group_name, channel_name):
-        self._file = file
-        self._group_name = group_name
-        self._channel_name = channel_name
+    def __init__(self, channel):
+        self._channel = channel
+
+    @property
+    def name(self):
+        return self._channel.name
+
+    @property
+    def group_name(self):
+        return self._channel.group_name
+
+    @property
+    def data_type(self):
+        """ Name of the TDMS data type of this channel, for example "DoubleFloat"
+        """
+        return self._channel.data_type
+
+    @property
+    def dtype(self):
+        """ The numpy dtype of data read from this channel, or None if reading
+        this channel's data type is not supported
+        """
+        dtype = _NUMPY_DTYPES.get(self.data_type)
+        return None if dtype is None else np.dtype(dtype)
+
+    def properties(self, raw_timestamps=False):
+        return self._channel.properties(raw_timestamps)
+
+    def __len__(self):
+        return self._channel.len()
 
     def read_all_data(self):
-        c_schema = ffi.new("struct ArrowSchema*")
-        ptr_schema = int(ffi.cast("uintptr_t", c_schema))
-        c_array = ffi.new("struct ArrowArray*")
-        ptr_array = int(ffi.cast("uintptr_t", c_array))
-        self._file.channel_data(
-                self._group_name, self._channel_name, ptr_schema, ptr_array)
-        return pa.Array._import_from_c(ptr_array, ptr_schema)
+        return _pyarrow().array(self._channel.read_all_data())
+
+    def read_data(self, offset=0, length=None):
+        """ Read up to length values starting at offset as an Arrow array,
+        reading only the data required from the file
+        """
+        if length is None:
+            length = max(len(self) - offset, 0)
+        return _pyarrow().array(self._channel.read_data(offset, length))
+
+    def read_numpy(self, offset=0, length=None):
+        """ Read up to length values starting at offset as a numpy array,
+        without requiring pyarrow. Numeric data is returned as a read only
+        array viewing memory owned by the extension module, without copying.
+        String data is returned as an array of Python objects.
+        """
+        if length is None:
+            length = max(len(self) - offset, 0)
+        data = self._channel.read_buffer(offset, length)
+        if isinstance(data, list):
+            return np.array(data, dtype=object)
+        return np.asarray(data)
+
+    def time_track(self, absolute_time=False):
+        """ Get the time of each value as a numpy array, computed from the
+        wf_start_time and wf_increment properties. Times are in seconds
+        relative to the first value, or datetime64 values in UTC if
+        absolute_time is true.
+        """
+        time_track = np.asarray(self._channel.time_track(absolute_time))
+        if absolute_time:
+            return time_track.view("datetime64[ns]")
+        return time_track
+
+    def as_xarray(self, time_coordinate=True):
+        """ Convert this channel to an xarray DataArray. If time_coordinate is
+        true, the dimension is "time" with coordinates computed from the
+        waveform timing properties, otherwise the dimension is "index".
+        Properties are stored as attrs, with timestamps converted to
+        ISO 8601 strings.
+        """
+        xr = _import_xarray()
+        dim = "time" if time_coordinate else "index"
+        coords = {}
+        if time_coordinate:
+            coords["time"] = self.time_track(absolute_time=True)
+        return xr.DataArray(
+                self.read_numpy(),
+                dims=dim,
+                coords=coords,
+                name=self.name,
+                attrs=_attrs(self.properties()))
+
+    def chunks(self):
+        """ Describe how the data of this channel is laid out in each segment
+        containing it, as a list of dicts with one dict per chunk of data
+        """
+        return self._channel.chunks()
+
+    def data_chunks(self, chunk_size, as_numpy=False):
+        """ Iterate over the channel data in chunks of at most chunk_size values,
+        reading only one chunk into memory at a time.
+        Chunks are Arrow arrays, or numpy arrays if as_numpy is true,
+        which doesn't require pyarrow.
+        """
+        if chunk_size < 1:
+            raise ValueError("chunk_size must be positive")
+        read = self.read_numpy if as_numpy else self.read_data
+        length = len(self)
+        for start in range(0, length, chunk_size):
+            yield read(start, chunk_size)
+
+    def __repr__(self):
+        return (
+                f"<TdmsChannel with path "
+                f"/'{self.group_name}'/'{self.name}'>")
+
+
+# Channel properties shown in the HTML summary of a file or group
+_SUMMARY_PROPERTIES = ["unit_string", "wf_start_time", "wf_increment"]
+
+
+def _html_summary(title, properties, channels):
+    """ Render an HTML summary of an object's properties and a table of
+    channels, given as (group name, channel name, description) tuples
+    """
+    rows = "".join(
+            f"<tr><th>{escape(name)}</th><td>{escape(str(value))}</td></tr>"
+            for name, value in properties.items())
+    headings = ["Group", "Channel", "Data type", "Length"] + _SUMMARY_PROPERTIES
+    channel_rows = "".join(
+            "<tr>" + "".join(
+                f"<td>{escape(str(value))}</td>"
+                for value in [
+                    group_name, channel_name,
+                    channel["data_type"], channel["length"]] + [
+                    channel["properties"].get(name, "")
+                    for name in _SUMMARY_PROPERTIES])
+            + "</tr>"
+            for group_name, channel_name, channel in channels)
+    heading_row = "".join(f"<th>{heading}</th>" for heading in headings)
+    return (
+            f"<div><strong>{escape(title)}</strong>"
+            f"<table>{rows}</table>"
+            f"<table><thead><tr>{heading_row}</tr></thead>"
+            f"<tbody>{channel_rows}</tbody></table></div>")
+
+
+def _pyarrow():
+    if pa is None:
+        raise ImportError(
+                "pyarrow is required for Arrow data, install it with "
+                "'pip install rstdms[arrow]' or use read_numpy to read "
+                "channel data as numpy arrays")
+    return pa
+
+
+def _import_xarray():
+    try:
+        import xarray
+    except ImportError:
+        raise ImportError(
+                "xarray is required to convert TDMS data to xarray objects, "
+                "install it with 'pip install rstdms[xarray]'") from None
+    return xarray
+
+
+def _attrs(properties):
+    """ Convert properties to xarray attrs, storing timestamps as strings
+    """
+    return {
+        name: value.isoformat() if isinstance(value, datetime) else value
+        for name, value in properties.items()
+    }
+
+
+class TdmsWriter:
+    """ Writes a TDMS file one segment at a time.
+    Can be used as a context manager to close the file when done.
+    """
+    def __init__(self, path):
+        self._writer = InternalTdmsWriter(os.fspath(path))
+
+    def write_segment(self, objects):
+        """ Write a segment containing RootObject, GroupObject and ChannelObject
+        instances. Channel data is appended to any data previously written
+        for the same channel.
+        """
+        self._writer.write_segment([obj._to_internal() for obj in objects])
+
+    def close(self):
+        self._writer.close()
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, exc_type, exc_value, traceback):
+        self.close()
+
+
+class RootObject:
+    """ The root object of a TDMS file, to set file properties """
+    def __init__(self, properties=None):
+        self.properties = properties or {}
+
+    def _to_internal(self):
+        return (None, None, self.properties, None)
+
+
+class GroupObject:
+    """ A group within a TDMS file, to set group properties """
+    def __init__(self, group_name, properties=None):
+        self.group_name = group_name
+        self.properties = properties or {}
+
+    def _to_internal(self):
+        return (self.group_name, None, self.properties, None)
+
+
+class ChannelObject:
+    """ A channel within a TDMS file, with optional data to append.
+    Data can be a numpy array, a pyarrow array or anything pyarrow can
+    convert to an array. Strings and timestamps are supported as well as
+    numeric types.
+    """
+    def __init__(self, group_name, channel_name, data=None, properties=None):
+        self.group_name = group_name
+        self.channel_name = channel_name
+        self.data = data
+        self.properties = properties or {}
+
+    def _to_internal(self):
+        data = self.data
+        if data is not None and not hasattr(data, "__arrow_c_array__"):
+            data = _pyarrow().array(data)
+        return (self.group_name, self.channel_name, self.properties, data)
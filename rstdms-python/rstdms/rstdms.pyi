@@ -0,0 +1,136 @@
+"""Type stubs for the rstdms extension module"""
+
+from datetime import datetime
+from typing import Any, Dict, List, Optional, Sequence, Tuple, Union
+
+PropertyValue = Union[int, float, str, datetime, "TdmsTimestamp"]
+WriteObject = Tuple[
+    Optional[str], Optional[str], Dict[str, PropertyValue], Optional[Any]
+]
+
+class ArrowData:
+    """Arrow data that can be imported by pyarrow or any other library
+    supporting the Arrow PyCapsule interface"""
+
+    def __arrow_c_array__(
+        self, requested_schema: Optional[object] = None
+    ) -> Tuple[object, object]:
+        """Export the data as a pair of schema and array capsules"""
+    def __arrow_c_stream__(self, requested_schema: Optional[object] = None) -> object:
+        """Export the data as an array stream capsule"""
+
+class DataBuffer:
+    """Read only channel data owned by Rust, which can be viewed as a numpy array
+    without copying. Views keep a reference to the buffer so the data stays alive."""
+
+    def __buffer__(self, flags: int) -> memoryview: ...
+    def __release_buffer__(self, view: memoryview) -> None: ...
+
+class TdmsTimestamp:
+    """A timestamp with the full precision of a TDMS timestamp"""
+
+    @property
+    def second_fractions(self) -> int:
+        """Positive fractions of a second, in units of 2^-64 seconds"""
+    @property
+    def seconds(self) -> int:
+        """Seconds since the epoch 1904-01-01 00:00:00 UTC"""
+    def to_datetime(self) -> datetime:
+        """Convert to a naive datetime in UTC, with microsecond precision"""
+
+class InternalTdmsFile:
+    """A TDMS file opened for reading"""
+
+    def __init__(self, path: str, index_path: Optional[str] = None) -> None:
+        """Open a TDMS file, reading metadata from the index file at index_path if given"""
+    def refresh(self) -> int:
+        """Read the metadata of segments appended to the file since it was opened
+        or last refreshed, returning the number of new segments"""
+    def groups(self) -> List[InternalTdmsGroup]:
+        """Get all groups in the file"""
+    def group(self, group_name: str) -> InternalTdmsGroup:
+        """Get a group by name"""
+    def properties(self, raw_timestamps: bool = False) -> Dict[str, PropertyValue]:
+        """Get the properties of the file. Timestamps are converted to timezone aware
+        datetimes in UTC, unless raw_timestamps is true."""
+    def describe(self, raw_timestamps: bool = False) -> Dict[str, Any]:
+        """Describe the whole file hierarchy as nested dicts, with the file properties and
+        a dict of groups keyed by name. Each group has properties and a dict of channels,
+        and each channel has a data type, length and properties."""
+    def segments(self) -> List[Dict[str, Any]]:
+        """Describe each segment in the file, as a list of dicts with the segment position,
+        data position, table of contents flags and number of objects"""
+    def layout_stats(self) -> Dict[str, Any]:
+        """Get statistics describing how data is laid out in the file, including the number
+        of chunks each channel's data is split into"""
+
+class InternalTdmsGroup:
+    """A group within a TDMS file"""
+
+    @property
+    def name(self) -> str:
+        """Name of the group"""
+    def channels(self) -> List[InternalTdmsChannel]:
+        """Get all channels in the group"""
+    def channel(self, channel_name: str) -> InternalTdmsChannel:
+        """Get a channel by name"""
+    def properties(self, raw_timestamps: bool = False) -> Dict[str, PropertyValue]:
+        """Get the properties of the group. Timestamps are converted to timezone aware
+        datetimes in UTC, unless raw_timestamps is true."""
+    def len(self) -> int:
+        """Get the number of values in each channel of the group,
+        which must be the same for all channels"""
+    def read_all_data(self, time_column: bool = False) -> ArrowData:
+        """Read the data of all channels in the group as a struct array with one field per
+        channel, which can be imported as a record batch. If time_column is true,
+        a "time" field is first, computed from the waveform timing of the channels."""
+    def read_data(
+        self, start: int, length: int, time_column: bool = False
+    ) -> ArrowData:
+        """Read up to `length` rows of the group starting at index `start`,
+        as a struct array with one field per channel"""
+
+class InternalTdmsChannel:
+    """A channel within a TDMS file"""
+
+    @property
+    def group_name(self) -> str:
+        """Name of the group containing the channel"""
+    @property
+    def name(self) -> str:
+        """Name of the channel"""
+    @property
+    def data_type(self) -> str:
+        """Get the TDMS data type name of the channel, for example "DoubleFloat" for 64 bit floats"""
+    def len(self) -> int:
+        """Get the number of values in the channel"""
+    def properties(self, raw_timestamps: bool = False) -> Dict[str, PropertyValue]:
+        """Get the properties of the channel. Timestamps are converted to timezone aware
+        datetimes in UTC, unless raw_timestamps is true."""
+    def read_all_data(self) -> ArrowData:
+        """Read all data of the channel"""
+    def read_data(self, start: int, length: int) -> ArrowData:
+        """Read up to `length` values starting at index `start`.
+        Only the data required is read from the file."""
+    def read_buffer(self, start: int, length: int) -> Union[DataBuffer, List[str]]:
+        """Read up to `length` values starting at index `start` without using Arrow,
+        as a buffer that numpy can view without copying, or a list of strings"""
+    def time_track(self, absolute_time: bool = False) -> DataBuffer:
+        """Get the time of each value from the waveform timing properties of the channel
+        as a buffer, either of seconds relative to the first value as 64 bit floats
+        or of nanoseconds since the Unix epoch as 64 bit integers"""
+    def chunks(self) -> List[Dict[str, Any]]:
+        """Describe how the data of the channel is laid out in each segment containing it,
+        as a list of dicts with one dict per chunk of data"""
+
+class InternalTdmsWriter:
+    """Writes a TDMS file one segment at a time"""
+
+    def __init__(self, path: str) -> None:
+        """Create a new TDMS file, replacing any existing file"""
+    def write_segment(self, objects: Sequence[WriteObject]) -> None:
+        """Write a segment containing the given objects. Each object is a tuple of
+        group name, channel name, properties dict and channel data, where data
+        implements `__arrow_c_array__`."""
+    def close(self) -> None:
+        """Flush any buffered data and close the file"""
@@ -0,0 +1,209 @@
+//! Export and import of Arrow data to and from Python using the Arrow PyCapsule interface,
+//! see <https://arrow.apache.org/docs/format/CDataInterface/PyCapsuleInterface.html>
+
+use std::os::raw::{c_char, c_int, c_void};
+use std::sync::Arc;
+
+use arrow2::array::Array;
+use arrow2::datatypes::Field;
+use arrow2::ffi::{
+    export_array_to_c, export_field_to_c, import_array_from_c, import_field_from_c, Ffi_ArrowArray,
+    Ffi_ArrowSchema,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::AsPyPointer;
+
+const SCHEMA_CAPSULE_NAME: &[u8] = b"arrow_schema\0";
+const ARRAY_CAPSULE_NAME: &[u8] = b"arrow_array\0";
+const STREAM_CAPSULE_NAME: &[u8] = b"arrow_array_stream\0";
+
+/// Arrow data that can be imported by pyarrow or any other library supporting the
+/// Arrow PyCapsule interface, either as an array or as a stream containing one array.
+/// Struct arrays can be imported as record batches or tables.
+#[pyclass]
+pub struct ArrowData {
+    field: Field,
+    array: Arc<dyn Array>,
+}
+
+impl ArrowData {
+    pub fn new(array: Arc<dyn Array>) -> ArrowData {
+        let field = Field::new("data", array.data_type().clone(), false);
+        ArrowData { field, array }
+    }
+}
+
+#[pymethods]
+impl ArrowData {
+    /// Export the data as a pair of schema and array capsules
+    #[pyo3(text_signature = "($self, requested_schema=None)")]
+    #[args(requested_schema = "None")]
+    fn __arrow_c_array__(
+        &self,
+        py: Python,
+        requested_schema: Option<PyObject>,
+    ) -> PyResult<(PyObject, PyObject)> {
+        // The requested schema is only a hint and data is always exported with its own type
+        let _ = requested_schema;
+        let mut schema = Box::new(Ffi_ArrowSchema::empty());
+        let mut array = Box::new(Ffi_ArrowArray::empty());
+        unsafe {
+            export_field_to_c(&self.field, &mut *schema);
+            export_array_to_c(self.array.clone(), &mut *array);
+            Ok((
+                into_capsule(py, schema, SCHEMA_CAPSULE_NAME, release_schema_capsule)?,
+                into_capsule(py, array, ARRAY_CAPSULE_NAME, release_array_capsule)?,
+            ))
+        }
+    }
+
+    /// Export the data as an array stream capsule
+    #[pyo3(text_signature = "($self, requested_schema=None)")]
+    #[args(requested_schema = "None")]
+    fn __arrow_c_stream__(
+        &self,
+        py: Python,
+        requested_schema: Option<PyObject>,
+    ) -> PyResult<PyObject> {
+        let _ = requested_schema;
+        let private_data = Box::new(StreamPrivateData {
+            field: self.field.clone(),
+            arrays: vec![self.array.clone()].into_iter(),
+        });
+        let stream = Box::new(Ffi_ArrowArrayStream {
+            get_schema: Some(get_stream_schema),
+            get_next: Some(get_stream_next),
+            get_last_error: Some(get_stream_last_error),
+            release: Some(release_stream),
+            private_data: Box::into_raw(private_data) as *mut c_void,
+        });
+        unsafe { into_capsule(py, stream, STREAM_CAPSULE_NAME, release_stream_capsule) }
+    }
+}
+
+/// Import an array from a Python object implementing `__arrow_c_array__`,
+/// such as a pyarrow array
+pub fn import_array(data: &PyAny) -> PyResult<Box<dyn Array>> {
+    let (schema, array): (&PyAny, &PyAny) = data.call_method0("__arrow_c_array__")?.extract()?;
+    unsafe {
+        let schema: *mut Ffi_ArrowSchema = capsule_pointer(schema, SCHEMA_CAPSULE_NAME)?;
+        let array: *mut Ffi_ArrowArray = capsule_pointer(array, ARRAY_CAPSULE_NAME)?;
+        let field =
+            import_field_from_c(&*schema).map_err(|err| PyValueError::new_err(err.to_string()))?;
+        // Move the array out of the capsule, leaving a released array behind
+        let array = Box::new(std::ptr::replace(array, Ffi_ArrowArray::empty()));
+        import_array_from_c(array, &field).map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+}
+
+unsafe fn capsule_pointer<T>(capsule: &PyAny, name: &'static [u8]) -> PyResult<*mut T> {
+    let pointer = ffi::PyCapsule_GetPointer(capsule.as_ptr(), name.as_ptr() as *const c_char);
+    if pointer.is_null() {
+        Err(PyErr::fetch(capsule.py()))
+    } else {
+        Ok(pointer as *mut T)
+    }
+}
+
+/// ABI compatible struct for ArrowArrayStream from the C Stream Interface,
+/// see <https://arrow.apache.org/docs/format/CStreamInterface.html>
+#[repr(C)]
+struct Ffi_ArrowArrayStream {
+    get_schema:
+        Option<unsafe extern "C" fn(*mut Ffi_ArrowArrayStream, *mut Ffi_ArrowSchema) -> c_int>,
+    get_next: Option<unsafe extern "C" fn(*mut Ffi_ArrowArrayStream, *mut Ffi_ArrowArray) -> c_int>,
+    get_last_error: Option<unsafe extern "C" fn(*mut Ffi_ArrowArrayStream) -> *const c_char>,
+    release: Option<unsafe extern "C" fn(*mut Ffi_ArrowArrayStream)>,
+    private_data: *mut c_void,
+}
+
+impl Drop for Ffi_ArrowArrayStream {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self) }
+        }
+    }
+}
+
+struct StreamPrivateData {
+    field: Field,
+    arrays: std::vec::IntoIter<Arc<dyn Array>>,
+}
+
+unsafe extern "C" fn get_stream_schema(
+    stream: *mut Ffi_ArrowArrayStream,
+    out: *mut Ffi_ArrowSchema,
+) -> c_int {
+    let private_data = &*((*stream).private_data as *const StreamPrivateData);
+    std::ptr::write(out, Ffi_ArrowSchema::empty());
+    export_field_to_c(&private_data.field, out);
+    0
+}
+
+unsafe extern "C" fn get_stream_next(
+    stream: *mut Ffi_ArrowArrayStream,
+    out: *mut Ffi_ArrowArray,
+) -> c_int {
+    let private_data = &mut *((*stream).private_data as *mut StreamPrivateData);
+    // The end of the stream is indicated by an array without a release callback
+    std::ptr::write(out, Ffi_ArrowArray::empty());
+    if let Some(array) = private_data.arrays.next() {
+        export_array_to_c(array, out);
+    }
+    0
+}
+
+unsafe extern "C" fn get_stream_last_error(_stream: *mut Ffi_ArrowArrayStream) -> *const c_char {
+    // Streams are created from data already in memory so can't fail
+    std::ptr::null()
+}
+
+unsafe extern "C" fn release_stream(stream: *mut Ffi_ArrowArrayStream) {
+    drop(Box::from_raw(
+        (*stream).private_data as *mut StreamPrivateData,
+    ));
+    (*stream).release = None;
+}
+
+/// Create a capsule owning a boxed value, which is dropped by the destructor
+/// when the capsule is garbage collected
+unsafe fn into_capsule<T>(
+    py: Python,
+    value: Box<T>,
+    name: &'static [u8],
+    destructor: ffi::PyCapsule_Destructor,
+) -> PyResult<PyObject> {
+    let pointer = Box::into_raw(value);
+    let capsule = ffi::PyCapsule_New(
+        pointer as *mut c_void,
+        name.as_ptr() as *const c_char,
+        Some(destructor),
+    );
+    if capsule.is_null() {
+        drop(Box::from_raw(pointer));
+    }
+    PyObject::from_owned_ptr_or_err(py, capsule)
+}
+
+/// Drop the value owned by a capsule. Arrow structures that have been moved
+/// out of by a consumer no longer have a release callback so are not released.
+unsafe fn drop_capsule_value<T>(capsule: *mut ffi::PyObject, name: &'static [u8]) {
+    let pointer = ffi::PyCapsule_GetPointer(capsule, name.as_ptr() as *const c_char) as *mut T;
+    if !pointer.is_null() {
+        drop(Box::from_raw(pointer));
+    }
+}
+
+unsafe extern "C" fn release_schema_capsule(capsule: *mut ffi::PyObject) {
+    drop_capsule_value::<Ffi_ArrowSchema>(capsule, SCHEMA_CAPSULE_NAME)
+}
+
+unsafe extern "C" fn release_array_capsule(capsule: *mut ffi::PyObject) {
+    drop_capsule_value::<Ffi_ArrowArray>(capsule, ARRAY_CAPSULE_NAME)
+}
+
+unsafe extern "C" fn release_stream_capsule(capsule: *mut ffi::PyObject) {
+    drop_capsule_value::<Ffi_ArrowArrayStream>(capsule, STREAM_CAPSULE_NAME)
+}
@@ -0,0 +1,99 @@
+//! Export of channel data to numpy without Arrow, using the Python buffer protocol,
+//! see <https://docs.python.org/3/c-api/buffer.html>
+
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+
+use pyo3::class::PyBufferProtocol;
+use pyo3::exceptions::{PyBufferError, PyNotImplementedError};
+use pyo3::ffi;
+use pyo3::prelude::*;
+use pyo3::AsPyPointer;
+use rstdms::ChannelData;
+
+/// Read only channel data owned by Rust, which can be viewed as a numpy array
+/// without copying. Views keep a reference to the buffer so the data stays alive.
+#[pyclass]
+pub struct DataBuffer {
+    data: ChannelData,
+    shape: [isize; 1],
+    strides: [isize; 1],
+}
+
+#[pyproto]
+impl PyBufferProtocol for DataBuffer {
+    fn bf_getbuffer(slf: PyRefMut<Self>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("View is null"));
+        }
+        if (flags & ffi::PyBUF_WRITABLE) == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("Channel data is read only"));
+        }
+        let (buf, format) = buffer_parts(&slf.data).expect("Buffers only hold numeric data");
+        unsafe {
+            (*view).obj = ffi::_Py_NewRef(slf.as_ptr());
+            (*view).buf = buf as *mut c_void;
+            (*view).len = slf.shape[0] * slf.strides[0];
+            (*view).readonly = 1;
+            (*view).itemsize = slf.strides[0];
+            (*view).format = ptr::null_mut();
+            if (flags & ffi::PyBUF_FORMAT) == ffi::PyBUF_FORMAT {
+                (*view).format = format.as_ptr() as *mut _;
+            }
+            (*view).ndim = 1;
+            (*view).shape = ptr::null_mut();
+            if (flags & ffi::PyBUF_ND) == ffi::PyBUF_ND {
+                (*view).shape = slf.shape.as_ptr() as *mut _;
+            }
+            (*view).strides = ptr::null_mut();
+            if (flags & ffi::PyBUF_STRIDES) == ffi::PyBUF_STRIDES {
+                (*view).strides = slf.strides.as_ptr() as *mut _;
+            }
+            (*view).suboffsets = ptr::null_mut();
+            (*view).internal = ptr::null_mut();
+        }
+        Ok(())
+    }
+
+    fn bf_releasebuffer(_slf: PyRefMut<Self>, _view: *mut ffi::Py_buffer) {}
+}
+
+/// Convert channel data to a Python object, as a buffer for numeric data
+/// or a list for string data
+pub fn into_py_data(py: Python, data: ChannelData) -> PyResult<PyObject> {
+    let itemsize = match &data {
+        ChannelData::String(values) => return Ok(values.to_object(py)),
+        ChannelData::Timestamp(_) => {
+            return Err(PyNotImplementedError::new_err(
+                "Reading TimeStamp data is not implemented",
+            ))
+        }
+        ChannelData::I8(_) | ChannelData::U8(_) => 1,
+        ChannelData::I16(_) | ChannelData::U16(_) => 2,
+        ChannelData::I32(_) | ChannelData::U32(_) | ChannelData::F32(_) => 4,
+        ChannelData::I64(_) | ChannelData::U64(_) | ChannelData::F64(_) => 8,
+    };
+    let buffer = DataBuffer {
+        shape: [data.len() as isize],
+        strides: [itemsize],
+        data,
+    };
+    Ok(Py::new(py, buffer)?.to_object(py))
+}
+
+/// Get a pointer to the values of numeric data and their struct module format string
+fn buffer_parts(data: &ChannelData) -> Option<(*const u8, &'static [u8])> {
+    match data {
+        ChannelData::I8(values) => Some((values.as_ptr() as *const u8, b"b\0")),
+        ChannelData::I16(values) => Some((values.as_ptr() as *const u8, b"h\0")),
+        ChannelData::I32(values) => Some((values.as_ptr() as *const u8, b"i\0")),
+        ChannelData::I64(values) => Some((values.as_ptr() as *const u8, b"q\0")),
+        ChannelData::U8(values) => Some((values.as_ptr(), b"B\0")),
+        ChannelData::U16(values) => Some((values.as_ptr() as *const u8, b"H\0")),
+        ChannelData::U32(values) => Some((values.as_ptr() as *const u8, b"I\0")),
+        ChannelData::U64(values) => Some((values.as_ptr() as *const u8, b"Q\0")),
+        ChannelData::F32(values) => Some((values.as_ptr() as *const u8, b"f\0")),
+        ChannelData::F64(values) => Some((values.as_ptr() as *const u8, b"d\0")),
+        ChannelData::String(_) | ChannelData::Timestamp(_) => None,
+    }
+}
@@ -1,100 +1,194 @@
+mod arrow_ffi;
+mod buffer;
+mod writer;
+
 use chrono::{Datelike, Timelike};
 use std::fs::File;
 use std::io::{Read, Seek};
 use std::sync::Arc;
 use std::{error, fmt};
 
-use arrow2::array::{Array, PrimitiveArray};
-use arrow2::datatypes::Field;
-use arrow2::ffi::{export_array_to_c, export_field_to_c, Ffi_ArrowArray, Ffi_ArrowSchema};
+use arrow2::array::{Array, PrimitiveArray, StructArray, Utf8Array};
+use arrow2::datatypes::{DataType, Field, TimeUnit};
 use arrow2::types::NativeType as ArrowNativeType;
 use pyo3::exceptions::{PyIOError, PyNotImplementedError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyDateTime, PyDict};
 use rstdms::timestamp::Timestamp;
-use rstdms::{Channel, NativeType, TdmsFile, TdmsReadError, TdmsValue};
+use rstdms::{
+    Channel, ChannelData, Group, NativeType, ReadOptions, TdmsFile, TdmsProperty, TdmsReadError,
+    TdmsValue, WaveformTiming,
+};
+
+use crate::arrow_ffi::ArrowData;
+use crate::buffer::{into_py_data, DataBuffer};
+use crate::writer::PyTdmsWriter;
 
+/// A TDMS file opened for reading
 #[pyclass(name = "InternalTdmsFile")]
+#[pyo3(text_signature = "(path, index_path=None)")]
 struct PyTdmsFile {
     inner: TdmsFile<File>,
 }
 
+/// A timestamp with the full precision of a TDMS timestamp
 #[pyclass]
 struct TdmsTimestamp {
+    /// Positive fractions of a second, in units of 2^-64 seconds
     #[pyo3(get)]
     second_fractions: u64,
+    /// Seconds since the epoch 1904-01-01 00:00:00 UTC
     #[pyo3(get)]
     seconds: i64,
 }
 
 #[pymethods]
 impl PyTdmsFile {
+    /// Open a TDMS file, reading metadata from the index file at index_path if given
     #[new]
-    fn new(path: &str) -> PyResult<Self> {
+    #[args(index_path = "None")]
+    fn new(path: &str, index_path: Option<String>) -> PyResult<Self> {
         let file = File::open(path)?;
-        let tdms_file = TdmsFile::new(file).map_err(PyTdmsError::from)?;
+        let tdms_file = match index_path {
+            Some(index_path) => {
+                TdmsFile::with_index(file, File::open(index_path)?, &ReadOptions::default())
+            }
+            None => TdmsFile::new(file),
+        }
+        .map_err(PyTdmsError::from)?;
         Ok(PyTdmsFile { inner: tdms_file })
     }
 
-    fn groups(&self) -> Vec<String> {
-        self.inner.groups().map(|g| g.name().to_owned()).collect()
+    /// Read the metadata of segments appended to the file since it was opened
+    /// or last refreshed, returning the number of new segments
+    #[pyo3(text_signature = "($self)")]
+    fn refresh(&mut self) -> PyResult<usize> {
+        Ok(self.inner.refresh().map_err(PyTdmsError::from)?)
     }
 
-    fn group_channels(&self, group_name: &str) -> PyResult<Vec<String>> {
-        match self.inner.group(group_name) {
-            Some(group) => Ok(group.channels().map(|c| c.name().to_owned()).collect()),
-            None => Err(PyValueError::new_err(format!(
-                "Invalid group name '{}'",
-                group_name
-            ))),
-        }
+    /// Get all groups in the file
+    #[pyo3(text_signature = "($self)")]
+    fn groups(slf: PyRef<Self>) -> Vec<PyTdmsGroup> {
+        let group_names: Vec<String> = slf.inner.groups().map(|g| g.name().to_owned()).collect();
+        let file: Py<PyTdmsFile> = slf.into();
+        group_names
+            .into_iter()
+            .map(|name| PyTdmsGroup {
+                file: file.clone(),
+                name,
+            })
+            .collect()
     }
 
-    fn properties(&self) -> PyResult<Py<PyAny>> {
-        let gil = Python::acquire_gil();
-        let py = gil.python();
-        let dict = PyDict::new(py);
-        for property in self.inner.properties() {
-            dict.set_item(&property.name, to_py_object(&py, &property.value))?;
+    /// Get a group by name
+    #[pyo3(text_signature = "($self, group_name)")]
+    fn group(slf: PyRef<Self>, group_name: String) -> PyResult<PyTdmsGroup> {
+        slf.find_group(&group_name)?;
+        Ok(PyTdmsGroup {
+            file: slf.into(),
+            name: group_name,
+        })
+    }
+
+    /// Get the properties of the file. Timestamps are converted to timezone aware
+    /// datetimes in UTC, unless raw_timestamps is true.
+    #[pyo3(text_signature = "($self, raw_timestamps=False)")]
+    #[args(raw_timestamps = "false")]
+    fn properties(&self, raw_timestamps: bool) -> PyResult<Py<PyAny>> {
+        properties_dict(self.inner.properties(), raw_timestamps)
+    }
+
+    /// Describe the whole file hierarchy as nested dicts, with the file properties and
+    /// a dict of groups keyed by name. Each group has properties and a dict of channels,
+    /// and each channel has a data type, length and properties.
+    #[pyo3(text_signature = "($self, raw_timestamps=False)")]
+    #[args(raw_timestamps = "false")]
+    fn describe(&self, py: Python, raw_timestamps: bool) -> PyResult<PyObject> {
+        let groups = PyDict::new(py);
+        for group in self.inner.groups() {
+            let channels = PyDict::new(py);
+            for channel in group.channels() {
+                let channel_dict = PyDict::new(py);
+                channel_dict.set_item("data_type", format!("{:?}", channel.data_type()))?;
+                channel_dict.set_item("length", channel.len())?;
+                channel_dict.set_item(
+                    "properties",
+                    properties_dict(channel.properties(), raw_timestamps)?,
+                )?;
+                channels.set_item(channel.name(), channel_dict)?;
+            }
+            let group_dict = PyDict::new(py);
+            group_dict.set_item(
+                "properties",
+                properties_dict(group.properties(), raw_timestamps)?,
+            )?;
+            group_dict.set_item("channels", channels)?;
+            groups.set_item(group.name(), group_dict)?;
         }
+        let dict = PyDict::new(py);
+        dict.set_item(
+            "properties",
+            properties_dict(self.inner.properties(), raw_timestamps)?,
+        )?;
+        dict.set_item("groups", groups)?;
         Ok(dict.to_object(py))
     }
 
-    fn group_properties(&self, group_name: &str) -> PyResult<Py<PyAny>> {
-        match self.inner.group(group_name) {
-            Some(group) => {
-                let gil = Python::acquire_gil();
-                let py = gil.python();
+    /// Describe each segment in the file, as a list of dicts with the segment position,
+    /// data position, table of contents flags and number of objects
+    #[pyo3(text_signature = "($self)")]
+    fn segments(&self, py: Python) -> PyResult<Vec<PyObject>> {
+        self.inner
+            .segments()
+            .iter()
+            .map(|segment| {
                 let dict = PyDict::new(py);
-                for property in group.properties() {
-                    dict.set_item(&property.name, to_py_object(&py, &property.value))?;
-                }
+                dict.set_item("position", segment.position)?;
+                dict.set_item("data_position", segment.data_position)?;
+                dict.set_item("next_segment_position", segment.next_segment_position)?;
+                dict.set_item("version", segment.version)?;
+                dict.set_item("toc_flags", segment.toc_flags)?;
+                dict.set_item("toc", toc_flag_names(segment.toc_flags))?;
+                dict.set_item("object_count", segment.object_count)?;
+                dict.set_item("repetitions", segment.repetitions)?;
                 Ok(dict.to_object(py))
-            }
-            None => Err(PyValueError::new_err(format!(
-                "Invalid group name '{}'",
-                group_name
-            ))),
-        }
+            })
+            .collect()
     }
 
-    fn channel_properties(&self, group_name: &str, channel_name: &str) -> PyResult<Py<PyAny>> {
+    /// Get statistics describing how data is laid out in the file, including the number
+    /// of chunks each channel's data is split into
+    #[pyo3(text_signature = "($self)")]
+    fn layout_stats(&self, py: Python) -> PyResult<PyObject> {
+        let stats = self.inner.stats();
+        let channels = stats
+            .channels
+            .iter()
+            .map(|channel| {
+                let dict = PyDict::new(py);
+                dict.set_item("group_name", &channel.group_name)?;
+                dict.set_item("channel_name", &channel.channel_name)?;
+                dict.set_item("chunk_count", channel.chunk_count)?;
+                dict.set_item("number_of_values", channel.number_of_values)?;
+                Ok(dict.to_object(py))
+            })
+            .collect::<PyResult<Vec<PyObject>>>()?;
+        let dict = PyDict::new(py);
+        dict.set_item("segment_count", stats.segment_count)?;
+        dict.set_item("interleaved_segment_count", stats.interleaved_segment_count)?;
+        dict.set_item("contiguous_segment_count", stats.contiguous_segment_count)?;
+        dict.set_item("metadata_bytes", stats.metadata_bytes)?;
+        dict.set_item("data_bytes", stats.data_bytes)?;
+        dict.set_item("fragmentation", stats.fragmentation)?;
+        dict.set_item("channels", channels)?;
+        Ok(dict.to_object(py))
+    }
+}
+
+impl PyTdmsFile {
+    fn find_group<'a>(&'a self, group_name: &str) -> PyResult<Group<'a, File>> {
         match self.inner.group(group_name) {
-            Some(group) => match group.channel(channel_name) {
-                Some(channel) => {
-                    let gil = Python::acquire_gil();
-                    let py = gil.python();
-                    let dict = PyDict::new(py);
-                    for property in channel.properties() {
-                        dict.set_item(&property.name, to_py_object(&py, &property.value))?;
-                    }
-                    Ok(dict.to_object(py))
-                }
-                None => Err(PyValueError::new_err(format!(
-                    "Invalid channel name '{}'",
-                    channel_name
-                ))),
-            },
+            Some(group) => Ok(group),
             None => Err(PyValueError::new_err(format!(
                 "Invalid group name '{}'",
                 group_name
@@ -102,118 +196,432 @@ impl PyTdmsFile {
         }
     }
 
-    fn channel_data(
-        &self,
+    fn find_channel<'a>(
+        &'a self,
         group_name: &str,
         channel_name: &str,
-        schema_ptr_in: usize,
-        array_ptr_in: usize,
-    ) -> PyResult<()> {
-        let schema_ptr = schema_ptr_in as *mut Ffi_ArrowSchema;
-        let array_ptr = array_ptr_in as *mut Ffi_ArrowArray;
-        match self.inner.group(group_name) {
-            Some(group) => match group.channel(channel_name) {
-                Some(channel) => match channel.data_type() {
-                    rstdms::TdsType::Void => Err(PyValueError::new_err("channel has no data type")),
-                    rstdms::TdsType::I8 => {
-                        read_channel_data::<i8, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::I16 => {
-                        read_channel_data::<i16, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::I32 => {
-                        read_channel_data::<i32, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::I64 => {
-                        read_channel_data::<i64, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::U8 => {
-                        read_channel_data::<u8, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::U16 => {
-                        read_channel_data::<u16, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::U32 => {
-                        read_channel_data::<u32, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::U64 => {
-                        read_channel_data::<u64, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::SingleFloat => {
-                        read_channel_data::<f32, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::DoubleFloat => {
-                        read_channel_data::<f64, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::ExtendedFloat => Err(PyNotImplementedError::new_err(
-                        "Reading ExtendedFloat data is not implemented",
-                    )),
-                    rstdms::TdsType::SingleFloatWithUnit => {
-                        read_channel_data::<f32, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::DoubleFloatWithUnit => {
-                        read_channel_data::<f64, _>(&channel, schema_ptr, array_ptr)
-                    }
-                    rstdms::TdsType::ExtendedFloatWithUnit => Err(PyNotImplementedError::new_err(
-                        "Reading ExtendedFloat data is not implemented",
-                    )),
-                    rstdms::TdsType::String => Err(PyNotImplementedError::new_err(
-                        "Reading String data is not implemented",
-                    )),
-                    rstdms::TdsType::Boolean => Err(PyNotImplementedError::new_err(
-                        "Reading Boolean data is not implemented",
-                    )),
-                    rstdms::TdsType::TimeStamp => Err(PyNotImplementedError::new_err(
-                        "Reading TimeStamp data is not implemented",
-                    )),
-                    rstdms::TdsType::FixedPoint => Err(PyNotImplementedError::new_err(
-                        "Reading FixedPoint data is not implemented",
-                    )),
-                    rstdms::TdsType::ComplexSingleFloat => Err(PyNotImplementedError::new_err(
-                        "Reading ComplexSingleFloat data is not implemented",
-                    )),
-                    rstdms::TdsType::ComplexDoubleFloat => Err(PyNotImplementedError::new_err(
-                        "Reading ComplexDoubleFloat data is not implemented",
-                    )),
-                    rstdms::TdsType::DaqmxRawData => Err(PyNotImplementedError::new_err(
-                        "Reading DaqmxRawData is not implemented",
-                    )),
-                },
-                None => Err(PyValueError::new_err(format!(
-                    "Invalid channel name '{}'",
-                    channel_name
-                ))),
-            },
+    ) -> PyResult<Channel<'a, File>> {
+        match self.find_group(group_name)?.channel(channel_name) {
+            Some(channel) => Ok(channel),
             None => Err(PyValueError::new_err(format!(
-                "Invalid group name '{}'",
-                group_name
+                "Invalid channel name '{}'",
+                channel_name
             ))),
         }
     }
 }
 
+/// A group within a TDMS file
+#[pyclass(name = "InternalTdmsGroup")]
+struct PyTdmsGroup {
+    file: Py<PyTdmsFile>,
+    /// Name of the group
+    #[pyo3(get)]
+    name: String,
+}
+
+#[pymethods]
+impl PyTdmsGroup {
+    /// Get all channels in the group
+    #[pyo3(text_signature = "($self)")]
+    fn channels(&self, py: Python) -> PyResult<Vec<PyTdmsChannel>> {
+        let file = self.file.borrow(py);
+        let channels = file
+            .find_group(&self.name)?
+            .channels()
+            .map(|channel| PyTdmsChannel {
+                file: self.file.clone(),
+                group_name: self.name.clone(),
+                name: channel.name().to_owned(),
+            })
+            .collect();
+        Ok(channels)
+    }
+
+    /// Get a channel by name
+    #[pyo3(text_signature = "($self, channel_name)")]
+    fn channel(&self, py: Python, channel_name: String) -> PyResult<PyTdmsChannel> {
+        self.file
+            .borrow(py)
+            .find_channel(&self.name, &channel_name)?;
+        Ok(PyTdmsChannel {
+            file: self.file.clone(),
+            group_name: self.name.clone(),
+            name: channel_name,
+        })
+    }
+
+    /// Get the properties of the group. Timestamps are converted to timezone aware
+    /// datetimes in UTC, unless raw_timestamps is true.
+    #[pyo3(text_signature = "($self, raw_timestamps=False)")]
+    #[args(raw_timestamps = "false")]
+    fn properties(&self, py: Python, raw_timestamps: bool) -> PyResult<Py<PyAny>> {
+        let file = self.file.borrow(py);
+        let group = file.find_group(&self.name)?;
+        properties_dict(group.properties(), raw_timestamps)
+    }
+
+    /// Get the number of values in each channel of the group,
+    /// which must be the same for all channels
+    #[pyo3(text_signature = "($self)")]
+    fn len(&self, py: Python) -> PyResult<u64> {
+        let file = self.file.borrow(py);
+        let group = file.find_group(&self.name)?;
+        let channels: Vec<Channel<File>> = group.channels().collect();
+        group_len(&self.name, &channels)
+    }
+
+    /// Read the data of all channels in the group as a struct array with one field per
+    /// channel, which can be imported as a record batch. If time_column is true,
+    /// a "time" field is first, computed from the waveform timing of the channels.
+    #[pyo3(text_signature = "($self, time_column=False)")]
+    #[args(time_column = "false")]
+    fn read_all_data(&self, py: Python, time_column: bool) -> PyResult<ArrowData> {
+        self.read_data(py, 0, u64::MAX, time_column)
+    }
+
+    /// Read up to `length` rows of the group starting at index `start`,
+    /// as a struct array with one field per channel
+    #[pyo3(text_signature = "($self, start, length, time_column=False)")]
+    #[args(time_column = "false")]
+    fn read_data(
+        &self,
+        py: Python,
+        start: u64,
+        length: u64,
+        time_column: bool,
+    ) -> PyResult<ArrowData> {
+        let file = self.file.borrow(py);
+        let group = file.find_group(&self.name)?;
+        let channels: Vec<Channel<File>> = group.channels().collect();
+        let len = group_len(&self.name, &channels)?;
+        let start = start.min(len);
+        let length = length.min(len - start);
+
+        let mut fields = Vec::with_capacity(channels.len() + 1);
+        let mut values = Vec::with_capacity(channels.len() + 1);
+        if time_column {
+            fields.push(Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".to_owned())),
+                false,
+            ));
+            values.push(time_array(&channels, start, length)?);
+        }
+        for channel in &channels {
+            let array = read_channel_array(channel, start, length)?;
+            fields.push(Field::new(channel.name(), array.data_type().clone(), false));
+            values.push(array);
+        }
+        let array = StructArray::from_data(DataType::Struct(fields), values, None);
+        Ok(ArrowData::new(Arc::new(array)))
+    }
+}
+
+/// A channel within a TDMS file
+#[pyclass(name = "InternalTdmsChannel")]
+struct PyTdmsChannel {
+    file: Py<PyTdmsFile>,
+    /// Name of the group containing the channel
+    #[pyo3(get)]
+    group_name: String,
+    /// Name of the channel
+    #[pyo3(get)]
+    name: String,
+}
+
+#[pymethods]
+impl PyTdmsChannel {
+    /// Get the TDMS data type name of the channel, for example "DoubleFloat" for 64 bit floats
+    #[getter]
+    fn data_type(&self, py: Python) -> PyResult<String> {
+        let file = self.file.borrow(py);
+        let channel = file.find_channel(&self.group_name, &self.name)?;
+        Ok(format!("{:?}", channel.data_type()))
+    }
+
+    /// Get the number of values in the channel
+    #[pyo3(text_signature = "($self)")]
+    fn len(&self, py: Python) -> PyResult<u64> {
+        let file = self.file.borrow(py);
+        Ok(file.find_channel(&self.group_name, &self.name)?.len())
+    }
+
+    /// Get the properties of the channel. Timestamps are converted to timezone aware
+    /// datetimes in UTC, unless raw_timestamps is true.
+    #[pyo3(text_signature = "($self, raw_timestamps=False)")]
+    #[args(raw_timestamps = "false")]
+    fn properties(&self, py: Python, raw_timestamps: bool) -> PyResult<Py<PyAny>> {
+        let file = self.file.borrow(py);
+        let channel = file.find_channel(&self.group_name, &self.name)?;
+        properties_dict(channel.properties(), raw_timestamps)
+    }
+
+    /// Read all data of the channel
+    #[pyo3(text_signature = "($self)")]
+    fn read_all_data(&self, py: Python) -> PyResult<ArrowData> {
+        let file = self.file.borrow(py);
+        let channel = file.find_channel(&self.group_name, &self.name)?;
+        let array = read_channel_array(&channel, 0, channel.len())?;
+        Ok(ArrowData::new(array))
+    }
+
+    /// Read up to `length` values starting at index `start`.
+    /// Only the data required is read from the file.
+    #[pyo3(text_signature = "($self, start, length)")]
+    fn read_data(&self, py: Python, start: u64, length: u64) -> PyResult<ArrowData> {
+        let file = self.file.borrow(py);
+        let channel = file.find_channel(&self.group_name, &self.name)?;
+        let start = start.min(channel.len());
+        let length = length.min(channel.len() - start);
+        let array = read_channel_array(&channel, start, length)?;
+        Ok(ArrowData::new(array))
+    }
+
+    /// Read up to `length` values starting at index `start` without using Arrow,
+    /// as a buffer that numpy can view without copying, or a list of strings
+    #[pyo3(text_signature = "($self, start, length)")]
+    fn read_buffer(&self, py: Python, start: u64, length: u64) -> PyResult<PyObject> {
+        let file = self.file.borrow(py);
+        let channel = file.find_channel(&self.group_name, &self.name)?;
+        let start = start.min(channel.len());
+        let length = length.min(channel.len() - start);
+        let data = channel
+            .read_data_slice(start, length as usize)
+            .map_err(PyTdmsError::from)?;
+        into_py_data(py, data)
+    }
+
+    /// Get the time of each value from the waveform timing properties of the channel
+    /// as a buffer, either of seconds relative to the first value as 64 bit floats
+    /// or of nanoseconds since the Unix epoch as 64 bit integers
+    #[pyo3(text_signature = "($self, absolute_time=False)")]
+    #[args(absolute_time = "false")]
+    fn time_track(&self, py: Python, absolute_time: bool) -> PyResult<PyObject> {
+        let file = self.file.borrow(py);
+        let channel = file.find_channel(&self.group_name, &self.name)?;
+        let timing = waveform_timing(&channel)?;
+        let data = if absolute_time {
+            ChannelData::I64(absolute_times(&timing, 0, channel.len())?)
+        } else {
+            ChannelData::F64(relative_times(&timing, channel.len()))
+        };
+        into_py_data(py, data)
+    }
+
+    /// Describe how the data of the channel is laid out in each segment containing it,
+    /// as a list of dicts with one dict per chunk of data
+    #[pyo3(text_signature = "($self)")]
+    fn chunks(&self, py: Python) -> PyResult<Vec<PyObject>> {
+        let file = self.file.borrow(py);
+        let channel = file.find_channel(&self.group_name, &self.name)?;
+        channel
+            .chunks()
+            .iter()
+            .map(|chunk| {
+                let dict = PyDict::new(py);
+                dict.set_item("segment_index", chunk.segment_index)?;
+                dict.set_item("segment_position", chunk.segment_position)?;
+                dict.set_item("data_position", chunk.data_position)?;
+                dict.set_item("number_of_values", chunk.number_of_values)?;
+                dict.set_item("repetitions", chunk.repetitions)?;
+                dict.set_item("big_endian", chunk.big_endian)?;
+                dict.set_item("interleaved", chunk.interleaved)?;
+                Ok(dict.to_object(py))
+            })
+            .collect()
+    }
+}
+
+/// Names of the table of contents flags that can be set in a segment lead in
+const TOC_FLAG_NAMES: [(u32, &str); 6] = [
+    (1 << 1, "MetaData"),
+    (1 << 2, "NewObjList"),
+    (1 << 3, "RawData"),
+    (1 << 5, "InterleavedData"),
+    (1 << 6, "BigEndian"),
+    (1 << 7, "DaqMxRawData"),
+];
+
+fn toc_flag_names(toc_flags: u32) -> Vec<&'static str> {
+    TOC_FLAG_NAMES
+        .iter()
+        .filter(|(flag, _)| toc_flags & flag == *flag)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// Get the number of values in the channels of a group, which must all be the same
+fn group_len<TFile: Read + Seek>(group_name: &str, channels: &[Channel<TFile>]) -> PyResult<u64> {
+    let len = match channels.first() {
+        Some(channel) => channel.len(),
+        None => {
+            return Err(PyValueError::new_err(format!(
+                "Group '{}' has no channels",
+                group_name
+            )))
+        }
+    };
+    match channels.iter().find(|channel| channel.len() != len) {
+        Some(channel) => Err(PyValueError::new_err(format!(
+            "Channel '{}' has {} values but other channels in group '{}' have {}",
+            channel.name(),
+            channel.len(),
+            group_name,
+            len
+        ))),
+        None => Ok(len),
+    }
+}
+
+fn read_channel_array<TFile: Read + Seek>(
+    channel: &Channel<TFile>,
+    start: u64,
+    length: u64,
+) -> PyResult<Arc<dyn Array>> {
+    let length = length as usize;
+    match channel.data_type() {
+        rstdms::TdsType::Void => Err(PyValueError::new_err("channel has no data type")),
+        rstdms::TdsType::I8 => read_channel_data::<i8, _>(channel, start, length),
+        rstdms::TdsType::I16 => read_channel_data::<i16, _>(channel, start, length),
+        rstdms::TdsType::I32 => read_channel_data::<i32, _>(channel, start, length),
+        rstdms::TdsType::I64 => read_channel_data::<i64, _>(channel, start, length),
+        rstdms::TdsType::U8 => read_channel_data::<u8, _>(channel, start, length),
+        rstdms::TdsType::U16 => read_channel_data::<u16, _>(channel, start, length),
+        rstdms::TdsType::U32 => read_channel_data::<u32, _>(channel, start, length),
+        rstdms::TdsType::U64 => read_channel_data::<u64, _>(channel, start, length),
+        rstdms::TdsType::SingleFloat => read_channel_data::<f32, _>(channel, start, length),
+        rstdms::TdsType::DoubleFloat => read_channel_data::<f64, _>(channel, start, length),
+        rstdms::TdsType::ExtendedFloat => Err(PyNotImplementedError::new_err(
+            "Reading ExtendedFloat data is not implemented",
+        )),
+        rstdms::TdsType::SingleFloatWithUnit => read_channel_data::<f32, _>(channel, start, length),
+        rstdms::TdsType::DoubleFloatWithUnit => read_channel_data::<f64, _>(channel, start, length),
+        rstdms::TdsType::ExtendedFloatWithUnit => Err(PyNotImplementedError::new_err(
+            "Reading ExtendedFloat data is not implemented",
+        )),
+        rstdms::TdsType::String => read_string_channel_data(channel, start, length),
+        rstdms::TdsType::Boolean => Err(PyNotImplementedError::new_err(
+            "Reading Boolean data is not implemented",
+        )),
+        rstdms::TdsType::TimeStamp => Err(PyNotImplementedError::new_err(
+            "Reading TimeStamp data is not implemented",
+        )),
+        rstdms::TdsType::FixedPoint => Err(PyNotImplementedError::new_err(
+            "Reading FixedPoint data is not implemented",
+        )),
+        rstdms::TdsType::ComplexSingleFloat => Err(PyNotImplementedError::new_err(
+            "Reading ComplexSingleFloat data is not implemented",
+        )),
+        rstdms::TdsType::ComplexDoubleFloat => Err(PyNotImplementedError::new_err(
+            "Reading ComplexDoubleFloat data is not implemented",
+        )),
+        rstdms::TdsType::DaqmxRawData => Err(PyNotImplementedError::new_err(
+            "Reading DaqmxRawData is not implemented",
+        )),
+    }
+}
+
+/// Compute the time of each value of channels with the same waveform timing,
+/// as nanoseconds since the Unix epoch
+fn time_array<TFile: Read + Seek>(
+    channels: &[Channel<TFile>],
+    start: u64,
+    length: u64,
+) -> PyResult<Arc<dyn Array>> {
+    let timings = channels
+        .iter()
+        .map(waveform_timing)
+        .collect::<PyResult<Vec<WaveformTiming>>>()?;
+    let timing = timings[0];
+    if timings
+        .iter()
+        .any(|t| t.start_time != timing.start_time || t.increment != timing.increment)
+    {
+        return Err(PyValueError::new_err(
+            "Cannot compute a time column for channels with different waveform timing",
+        ));
+    }
+    let times = absolute_times(&timing, start, length)?;
+    Ok(Arc::new(PrimitiveArray::from_vec(times).to(
+        DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".to_owned())),
+    )))
+}
+
+fn waveform_timing<TFile: Read + Seek>(channel: &Channel<TFile>) -> PyResult<WaveformTiming> {
+    channel.waveform_timing().map_err(|err| {
+        PyValueError::new_err(format!(
+            "Cannot compute time for channel '{}': {}",
+            channel.name(),
+            err
+        ))
+    })
+}
+
+/// Compute the time of `length` values starting at index `start`
+/// as nanoseconds since the Unix epoch
+fn absolute_times(timing: &WaveformTiming, start: u64, length: u64) -> PyResult<Vec<i64>> {
+    (start..start + length)
+        .map(|index| {
+            timing.time_at(index).to_unix_nanos().ok_or_else(|| {
+                PyValueError::new_err("Time is out of range of a nanosecond timestamp")
+            })
+        })
+        .collect()
+}
+
+/// Compute the time of each value in seconds relative to the first value
+fn relative_times(timing: &WaveformTiming, len: u64) -> Vec<f64> {
+    (0..len)
+        .map(|index| index as f64 * timing.increment)
+        .collect()
+}
+
+/// Read `length` values from a channel starting at `start`, reading all data
+/// in one go when the full channel is requested
+fn read_values<T, TFile: Read + Seek>(
+    channel: &Channel<TFile>,
+    start: u64,
+    length: usize,
+) -> PyResult<Vec<T>>
+where
+    T: NativeType + Default + Clone,
+{
+    let mut data: Vec<T> = vec![Default::default(); length];
+    let result = if start == 0 && length as u64 == channel.len() {
+        channel.read_all_data(&mut data)
+    } else {
+        channel.read_data_range(start, &mut data)
+    };
+    result.map_err(PyTdmsError::from).map_err(PyErr::from)?;
+    Ok(data)
+}
+
 fn read_channel_data<T, TFile: Read + Seek>(
     channel: &Channel<TFile>,
-    schema_ptr: *mut Ffi_ArrowSchema,
-    array_ptr: *mut Ffi_ArrowArray,
-) -> PyResult<()>
+    start: u64,
+    length: usize,
+) -> PyResult<Arc<dyn Array>>
 where
     T: NativeType + ArrowNativeType,
-    TFile: Read + Seek,
 {
-    let len = channel.len();
-    let mut data: Vec<T> = vec![Default::default(); len as usize];
-    channel
-        .read_all_data(&mut data)
-        .map_err(PyTdmsError::from)
-        .map_err(PyErr::from)?;
-    let array: Arc<dyn Array> = Arc::new(PrimitiveArray::from_vec(data));
-    let field = Field::new("data", array.data_type().clone(), false);
-    unsafe {
-        export_field_to_c(&field, schema_ptr);
-        export_array_to_c(array, array_ptr);
+    let data = read_values::<T, _>(channel, start, length)?;
+    Ok(Arc::new(PrimitiveArray::from_vec(data)))
+}
+
+fn read_string_channel_data<TFile: Read + Seek>(
+    channel: &Channel<TFile>,
+    start: u64,
+    length: usize,
+) -> PyResult<Arc<dyn Array>> {
+    let data = read_values::<String, _>(channel, start, length)?;
+    // Use 64 bit offsets only when the total length of the strings requires them
+    let total_bytes: usize = data.iter().map(|value| value.len()).sum();
+    if total_bytes <= i32::MAX as usize {
+        Ok(Arc::new(Utf8Array::<i32>::from_slice(&data)))
+    } else {
+        Ok(Arc::new(Utf8Array::<i64>::from_slice(&data)))
     }
-    Ok(())
 }
 
 impl TdmsTimestamp {
@@ -227,35 +635,67 @@ impl TdmsTimestamp {
 
 #[pymethods]
 impl TdmsTimestamp {
+    /// Convert to a naive datetime in UTC, with microsecond precision
+    #[pyo3(text_signature = "($self)")]
     fn to_datetime(&self) -> PyResult<Py<PyAny>> {
         let gil = Python::acquire_gil();
         let py = gil.python();
         let timestamp = Timestamp::new(self.seconds, self.second_fractions);
-        match timestamp.to_datetime() {
-            Some(datetime) => {
-                let month: u8 = datetime.month().try_into()?;
-                let day: u8 = datetime.day().try_into()?;
-                let hour: u8 = datetime.hour().try_into()?;
-                let minute: u8 = datetime.minute().try_into()?;
-                let second: u8 = datetime.second().try_into()?;
-                PyDateTime::new(
-                    py,
-                    datetime.year(),
-                    month,
-                    day,
-                    hour,
-                    minute,
-                    second,
-                    datetime.nanosecond() / 1000u32,
-                    None,
-                )
-                .map(|dt| dt.into_py(py))
-            }
-            None => Err(PyValueError::new_err("Invalid timestamp")),
+        to_py_datetime(py, &timestamp, None)
+    }
+}
+
+/// Convert a timestamp to a Python datetime with microsecond precision
+fn to_py_datetime(
+    py: Python,
+    timestamp: &Timestamp,
+    tzinfo: Option<&PyObject>,
+) -> PyResult<Py<PyAny>> {
+    match timestamp.to_datetime() {
+        Some(datetime) => {
+            let month: u8 = datetime.month().try_into()?;
+            let day: u8 = datetime.day().try_into()?;
+            let hour: u8 = datetime.hour().try_into()?;
+            let minute: u8 = datetime.minute().try_into()?;
+            let second: u8 = datetime.second().try_into()?;
+            PyDateTime::new(
+                py,
+                datetime.year(),
+                month,
+                day,
+                hour,
+                minute,
+                second,
+                datetime.nanosecond() / 1000u32,
+                tzinfo,
+            )
+            .map(|dt| dt.into_py(py))
         }
+        None => Err(PyValueError::new_err("Invalid timestamp")),
     }
 }
 
+fn properties_dict(properties: &[TdmsProperty], raw_timestamps: bool) -> PyResult<Py<PyAny>> {
+    let gil = Python::acquire_gil();
+    let py = gil.python();
+    let utc = py
+        .import("datetime")?
+        .getattr("timezone")?
+        .getattr("utc")?
+        .to_object(py);
+    let dict = PyDict::new(py);
+    for property in properties {
+        let value = match &property.value {
+            TdmsValue::Timestamp(value) if !raw_timestamps => {
+                to_py_datetime(py, value, Some(&utc))?
+            }
+            value => to_py_object(&py, value),
+        };
+        dict.set_item(&property.name, value)?;
+    }
+    Ok(dict.to_object(py))
+}
+
 fn to_py_object(py: &Python, value: &TdmsValue) -> Py<PyAny> {
     match value {
         TdmsValue::Int8(value) => value.into_py(*py),
@@ -277,6 +717,12 @@ fn to_py_object(py: &Python, value: &TdmsValue) -> Py<PyAny> {
 #[pymodule]
 fn rstdms(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyTdmsFile>()?;
+    m.add_class::<PyTdmsGroup>()?;
+    m.add_class::<PyTdmsChannel>()?;
+    m.add_class::<TdmsTimestamp>()?;
+    m.add_class::<ArrowData>()?;
+    m.add_class::<DataBuffer>()?;
+    m.add_class::<PyTdmsWriter>()?;
     Ok(())
 }
 
@@ -309,9 +755,16 @@ impl From<TdmsReadError> for PyTdmsError {
 
 impl From<PyTdmsError> for PyErr {
     fn from(err: PyTdmsError) -> PyErr {
-        match err {
-            PyTdmsError::TdmsReadError(TdmsReadError::IoError(_)) => PyIOError::new_err(err.to_string()),
-            PyTdmsError::TdmsReadError(_) => PyValueError::new_err(err.to_string()),
+        // Errors may be wrapped with context, so check the underlying error type
+        let is_io_error = match err {
+            PyTdmsError::TdmsReadError(ref e) => {
+                matches!(e.without_context(), TdmsReadError::IoError(_))
+            }
+        };
+        if is_io_error {
+            PyIOError::new_err(err.to_string())
+        } else {
+            PyValueError::new_err(err.to_string())
         }
     }
 }
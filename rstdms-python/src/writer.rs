@@ -0,0 +1,220 @@
+use std::fs::File;
+use std::io::BufWriter;
+
+use arrow2::array::{Array, PrimitiveArray, Utf8Array};
+use arrow2::datatypes::{DataType, TimeUnit};
+use arrow2::types::{NativeType as ArrowNativeType, Offset};
+use chrono::NaiveDate;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDateAccess, PyDateTime, PyDict, PyFloat, PyString, PyTimeAccess};
+use rstdms::timestamp::Timestamp;
+use rstdms::{ChannelData, TdmsProperty, TdmsValue, TdmsWriter, WriteObject};
+
+use crate::arrow_ffi::import_array;
+use crate::{PyTdmsError, TdmsTimestamp};
+
+/// An object to write, as a tuple of group name, channel name, properties and data.
+/// The group name is None for the root object, and the channel name and data are None
+/// for groups.
+type PyWriteObject<'a> = (
+    Option<String>,
+    Option<String>,
+    &'a PyDict,
+    Option<&'a PyAny>,
+);
+
+/// Writes a TDMS file one segment at a time
+#[pyclass(name = "InternalTdmsWriter")]
+#[pyo3(text_signature = "(path)")]
+pub struct PyTdmsWriter {
+    inner: Option<TdmsWriter<BufWriter<File>>>,
+}
+
+#[pymethods]
+impl PyTdmsWriter {
+    /// Create a new TDMS file, replacing any existing file
+    #[new]
+    fn new(path: &str) -> PyResult<Self> {
+        let file = File::create(path)?;
+        Ok(PyTdmsWriter {
+            inner: Some(TdmsWriter::new(BufWriter::new(file))),
+        })
+    }
+
+    /// Write a segment containing the given objects. Each object is a tuple of
+    /// group name, channel name, properties dict and channel data, where data
+    /// implements `__arrow_c_array__`.
+    #[pyo3(text_signature = "($self, objects)")]
+    fn write_segment(&mut self, objects: Vec<PyWriteObject>) -> PyResult<()> {
+        let objects = objects
+            .into_iter()
+            .map(write_object)
+            .collect::<PyResult<Vec<WriteObject>>>()?;
+        let writer = match &mut self.inner {
+            Some(writer) => writer,
+            None => return Err(PyValueError::new_err("Writer has been closed")),
+        };
+        writer.write_segment(&objects).map_err(PyTdmsError::from)?;
+        Ok(())
+    }
+
+    /// Flush any buffered data and close the file
+    #[pyo3(text_signature = "($self)")]
+    fn close(&mut self) -> PyResult<()> {
+        if let Some(writer) = self.inner.take() {
+            writer.into_inner().map_err(PyTdmsError::from)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_object(object: PyWriteObject) -> PyResult<WriteObject> {
+    let (group_name, channel_name, properties, data) = object;
+    let properties = properties
+        .iter()
+        .map(|(name, value)| {
+            Ok(TdmsProperty {
+                name: name.extract()?,
+                value: to_tdms_value(value)?,
+            })
+        })
+        .collect::<PyResult<Vec<TdmsProperty>>>()?;
+    match (group_name, channel_name) {
+        (None, None) => Ok(WriteObject::root(properties)),
+        (Some(group_name), None) => Ok(WriteObject::group(&group_name, properties)),
+        (Some(group_name), Some(channel_name)) => {
+            let data = data.map(channel_data).transpose()?;
+            Ok(WriteObject::channel(
+                &group_name,
+                &channel_name,
+                properties,
+                data,
+            ))
+        }
+        (None, Some(_)) => Err(PyValueError::new_err(
+            "A group name is required for a channel",
+        )),
+    }
+}
+
+/// Convert a Python property value to a TDMS value. Integers are written as 64 bit
+/// integers, and naive datetimes are interpreted as UTC.
+fn to_tdms_value(value: &PyAny) -> PyResult<TdmsValue> {
+    if let Ok(value) = value.downcast::<PyString>() {
+        Ok(TdmsValue::String(value.to_str()?.to_owned()))
+    } else if value.is_instance::<PyBool>()? {
+        Err(PyTypeError::new_err("Boolean properties are not supported"))
+    } else if let Ok(value) = value.extract::<PyRef<TdmsTimestamp>>() {
+        Ok(TdmsValue::Timestamp(Timestamp::new(
+            value.seconds,
+            value.second_fractions,
+        )))
+    } else if let Ok(value) = value.downcast::<PyDateTime>() {
+        Ok(TdmsValue::Timestamp(from_py_datetime(value)?))
+    } else if value.is_instance::<PyFloat>()? {
+        Ok(TdmsValue::Float64(value.extract()?))
+    } else if let Ok(value) = value.extract::<i64>() {
+        Ok(TdmsValue::Int64(value))
+    } else if let Ok(value) = value.extract::<u64>() {
+        Ok(TdmsValue::Uint64(value))
+    } else if let Ok(value) = value.extract::<f64>() {
+        Ok(TdmsValue::Float64(value))
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "Unsupported property type '{}'",
+            value.get_type().name()?
+        )))
+    }
+}
+
+fn from_py_datetime(value: &PyDateTime) -> PyResult<Timestamp> {
+    let py = value.py();
+    let value: &PyDateTime = if value.getattr("tzinfo")?.is_none() {
+        value
+    } else {
+        let utc = py.import("datetime")?.getattr("timezone")?.getattr("utc")?;
+        value.call_method1("astimezone", (utc,))?.downcast()?
+    };
+    let datetime = NaiveDate::from_ymd_opt(
+        value.get_year(),
+        value.get_month() as u32,
+        value.get_day() as u32,
+    )
+    .and_then(|date| {
+        date.and_hms_micro_opt(
+            value.get_hour() as u32,
+            value.get_minute() as u32,
+            value.get_second() as u32,
+            value.get_microsecond(),
+        )
+    })
+    .ok_or_else(|| PyValueError::new_err("Invalid datetime"))?;
+    Ok(Timestamp::from_datetime(datetime.and_utc()))
+}
+
+/// Convert Arrow data to channel data. Nulls are not supported.
+fn channel_data(data: &PyAny) -> PyResult<ChannelData> {
+    let array = import_array(data)?;
+    if array.null_count() > 0 {
+        return Err(PyValueError::new_err(
+            "Channel data cannot contain null values",
+        ));
+    }
+    let array = array.as_ref();
+    match array.data_type() {
+        DataType::Int8 => Ok(ChannelData::I8(primitive_values(array))),
+        DataType::Int16 => Ok(ChannelData::I16(primitive_values(array))),
+        DataType::Int32 => Ok(ChannelData::I32(primitive_values(array))),
+        DataType::Int64 => Ok(ChannelData::I64(primitive_values(array))),
+        DataType::UInt8 => Ok(ChannelData::U8(primitive_values(array))),
+        DataType::UInt16 => Ok(ChannelData::U16(primitive_values(array))),
+        DataType::UInt32 => Ok(ChannelData::U32(primitive_values(array))),
+        DataType::UInt64 => Ok(ChannelData::U64(primitive_values(array))),
+        DataType::Float32 => Ok(ChannelData::F32(primitive_values(array))),
+        DataType::Float64 => Ok(ChannelData::F64(primitive_values(array))),
+        DataType::Utf8 => Ok(ChannelData::String(string_values::<i32>(array))),
+        DataType::LargeUtf8 => Ok(ChannelData::String(string_values::<i64>(array))),
+        DataType::Timestamp(unit, _) => {
+            let nanos_per_unit = match unit {
+                TimeUnit::Second => 1_000_000_000,
+                TimeUnit::Millisecond => 1_000_000,
+                TimeUnit::Microsecond => 1_000,
+                TimeUnit::Nanosecond => 1,
+            };
+            primitive_values::<i64>(array)
+                .into_iter()
+                .map(|value| {
+                    value
+                        .checked_mul(nanos_per_unit)
+                        .map(Timestamp::from_unix_nanos)
+                        .ok_or_else(|| PyValueError::new_err("Timestamp is out of range"))
+                })
+                .collect::<PyResult<Vec<Timestamp>>>()
+                .map(ChannelData::Timestamp)
+        }
+        data_type => Err(PyTypeError::new_err(format!(
+            "Writing {:?} data is not supported",
+            data_type
+        ))),
+    }
+}
+
+fn primitive_values<T: ArrowNativeType>(array: &dyn Array) -> Vec<T> {
+    array
+        .as_any()
+        .downcast_ref::<PrimitiveArray<T>>()
+        .expect("Array type should match its data type")
+        .values()
+        .to_vec()
+}
+
+fn string_values<O: Offset>(array: &dyn Array) -> Vec<String> {
+    array
+        .as_any()
+        .downcast_ref::<Utf8Array<O>>()
+        .expect("Array type should match its data type")
+        .values_iter()
+        .map(|value| value.to_owned())
+        .collect()
+}
@@ -0,0 +1,73 @@
+use crate::error::{Result, TdmsReadError};
+use crate::object_path::ObjectPathId;
+use crate::types::NativeType;
+use crate::{Channel, Group, TdmsFile};
+use ndarray::{Array1, Array2, ShapeBuilder};
+use std::io::{Read, Seek};
+
+impl<'a, R: Read + Seek> Channel<'a, R> {
+    /// Read all data for this channel into a one dimensional array
+    pub fn read_array<T: NativeType + Default + Clone>(&self) -> Result<Array1<T>> {
+        let tdms_reader = &self.file.tdms_reader;
+        tdms_reader.check_memory_limit(tdms_reader.channel_memory_size(self.object_id))?;
+        let mut values = vec![T::default(); tdms_reader.channel_len(self.object_id) as usize];
+        self.file.read_channel_into(self.object_id, &mut values)?;
+        Ok(Array1::from_vec(values))
+    }
+}
+
+impl<'a, R: Read + Seek> Group<'a, R> {
+    /// Read all channels in this group into a two dimensional array with one row per value
+    /// and one column per channel. All channels must have the same length and data type.
+    pub fn read_array2<T: NativeType + Default + Clone>(&self) -> Result<Array2<T>> {
+        let tdms_reader = &self.file.tdms_reader;
+        let channel_ids = tdms_reader.channel_ids(self.name());
+        let len = channel_ids
+            .first()
+            .map(|&channel_id| tdms_reader.channel_len(channel_id))
+            .unwrap_or(0);
+        for &channel_id in &channel_ids {
+            let channel_len = tdms_reader.channel_len(channel_id);
+            if channel_len != len {
+                return Err(TdmsReadError::ChannelLengthMismatch {
+                    path: tdms_reader.object_path_string(channel_id),
+                    length: channel_len,
+                    expected: len,
+                });
+            }
+        }
+        let required = channel_ids
+            .iter()
+            .map(|&channel_id| tdms_reader.channel_memory_size(channel_id))
+            .sum();
+        tdms_reader.check_memory_limit(required)?;
+
+        // Channel data is stored contiguously, so the array uses column major order
+        let len = len as usize;
+        let mut values = vec![T::default(); len * channel_ids.len()];
+        if len > 0 {
+            for (&channel_id, column) in channel_ids.iter().zip(values.chunks_mut(len)) {
+                self.file.read_channel_into(channel_id, column)?;
+            }
+        }
+        Ok(Array2::from_shape_vec((len, channel_ids.len()).f(), values)
+            .expect("Number of values matches the array shape"))
+    }
+}
+
+impl<R: Read + Seek> TdmsFile<R> {
+    fn read_channel_into<T: NativeType>(
+        &self,
+        channel_id: ObjectPathId,
+        buffer: &mut [T],
+    ) -> Result<()> {
+        let mut file_reader = self.file_reader.borrow_mut();
+        if self.unbuffered_data_reads {
+            self.tdms_reader
+                .read_channel_data(file_reader.get_mut(), channel_id, buffer)
+        } else {
+            self.tdms_reader
+                .read_channel_data(&mut *file_reader, channel_id, buffer)
+        }
+    }
+}
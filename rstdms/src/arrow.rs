@@ -0,0 +1,171 @@
+use crate::channel_data::ChannelData;
+use crate::error::{Result, TdmsReadError};
+use crate::object_path::ObjectPathId;
+use crate::types::NativeTypeId;
+use crate::{Group, TdmsFile};
+use arrow2::array::{Array, PrimitiveArray, Utf8Array};
+use arrow2::bitmap::Bitmap;
+use arrow2::chunk::Chunk;
+use arrow2::datatypes::{DataType, Field, TimeUnit};
+use std::io::{Read, Seek};
+use std::sync::Arc;
+
+/// An iterator over the data of the channels in a group as Arrow chunks,
+/// created with `Group::arrow_chunks`
+pub struct ArrowChunkReader<'a, R: Read + Seek> {
+    file: &'a TdmsFile<R>,
+    channels: Vec<(ObjectPathId, u64)>,
+    fields: Vec<Field>,
+    batch_size: usize,
+    position: u64,
+    num_rows: u64,
+}
+
+impl<'a, R: Read + Seek> Group<'a, R> {
+    /// Get an iterator over the data of the channels in this group as Arrow chunks of up to
+    /// `batch_size` rows, with data read incrementally as each chunk is requested.
+    /// Channels without data are not included, and channels shorter than the longest
+    /// channel are padded with nulls.
+    pub fn arrow_chunks(&self, batch_size: usize) -> Result<ArrowChunkReader<'a, R>> {
+        ArrowChunkReader::new(self.file, self.name(), batch_size)
+    }
+}
+
+impl<'a, R: Read + Seek> ArrowChunkReader<'a, R> {
+    fn new(
+        file: &'a TdmsFile<R>,
+        group_name: &str,
+        batch_size: usize,
+    ) -> Result<ArrowChunkReader<'a, R>> {
+        let tdms_reader = &file.tdms_reader;
+        let mut channels = Vec::new();
+        let mut fields = Vec::new();
+        for channel_id in tdms_reader.channel_ids(group_name) {
+            if tdms_reader.get_channel_data_index(channel_id).is_none() {
+                continue;
+            }
+            let data_type = tdms_reader.channel_data_type(channel_id);
+            let arrow_type = match data_type.native_type() {
+                Some(native_type) => arrow_data_type(native_type),
+                None => return Err(TdmsReadError::UnsupportedDataType(data_type)),
+            };
+            let name = tdms_reader.channel_name(channel_id);
+            fields.push(Field::new(name, arrow_type, true));
+            channels.push((channel_id, tdms_reader.channel_len(channel_id)));
+        }
+        let num_rows = channels.iter().map(|&(_, len)| len).max().unwrap_or(0);
+        Ok(ArrowChunkReader {
+            file,
+            channels,
+            fields,
+            batch_size: batch_size.max(1),
+            position: 0,
+            num_rows,
+        })
+    }
+
+    /// Get the Arrow fields describing the arrays in each chunk
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    fn read_array(
+        &self,
+        channel_id: ObjectPathId,
+        channel_len: u64,
+        rows: usize,
+    ) -> Result<Arc<dyn Array>> {
+        let available = channel_len.saturating_sub(self.position).min(rows as u64) as usize;
+        let data = self
+            .file
+            .read_channel_data_range(channel_id, self.position, available)?;
+        Ok(to_arrow_array(data, rows))
+    }
+}
+
+impl<'a, R: Read + Seek> Iterator for ArrowChunkReader<'a, R> {
+    type Item = Result<Chunk<Arc<dyn Array>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.num_rows {
+            return None;
+        }
+        let rows = (self.num_rows - self.position).min(self.batch_size as u64) as usize;
+        let arrays = self
+            .channels
+            .iter()
+            .map(|&(channel_id, len)| self.read_array(channel_id, len, rows))
+            .collect::<Result<Vec<_>>>();
+        self.position += rows as u64;
+        Some(arrays.map(Chunk::new))
+    }
+}
+
+fn arrow_data_type(native_type: NativeTypeId) -> DataType {
+    match native_type {
+        NativeTypeId::I8 => DataType::Int8,
+        NativeTypeId::I16 => DataType::Int16,
+        NativeTypeId::I32 => DataType::Int32,
+        NativeTypeId::I64 => DataType::Int64,
+        NativeTypeId::U8 => DataType::UInt8,
+        NativeTypeId::U16 => DataType::UInt16,
+        NativeTypeId::U32 => DataType::UInt32,
+        NativeTypeId::U64 => DataType::UInt64,
+        NativeTypeId::F32 => DataType::Float32,
+        NativeTypeId::F64 => DataType::Float64,
+        NativeTypeId::String => DataType::Utf8,
+        NativeTypeId::Timestamp => DataType::Timestamp(TimeUnit::Nanosecond, None),
+    }
+}
+
+/// Convert data to an Arrow array with the given number of rows,
+/// where rows past the end of the data are null
+fn to_arrow_array(data: ChannelData, rows: usize) -> Arc<dyn Array> {
+    let len = data.len();
+    let validity = if len < rows {
+        Some((0..rows).map(|i| i < len).collect::<Bitmap>())
+    } else {
+        None
+    };
+    match data {
+        ChannelData::I8(values) => primitive_array(values, rows, validity),
+        ChannelData::I16(values) => primitive_array(values, rows, validity),
+        ChannelData::I32(values) => primitive_array(values, rows, validity),
+        ChannelData::I64(values) => primitive_array(values, rows, validity),
+        ChannelData::U8(values) => primitive_array(values, rows, validity),
+        ChannelData::U16(values) => primitive_array(values, rows, validity),
+        ChannelData::U32(values) => primitive_array(values, rows, validity),
+        ChannelData::U64(values) => primitive_array(values, rows, validity),
+        ChannelData::F32(values) => primitive_array(values, rows, validity),
+        ChannelData::F64(values) => primitive_array(values, rows, validity),
+        ChannelData::String(mut values) => {
+            values.resize(rows, String::new());
+            Arc::new(Utf8Array::<i32>::from_slice(&values).with_validity(validity))
+        }
+        ChannelData::Timestamp(values) => {
+            // Timestamps outside the range of nanoseconds since the Unix epoch are null
+            let mut nanoseconds: Vec<Option<i64>> = values
+                .iter()
+                .map(|timestamp| {
+                    timestamp
+                        .to_datetime()
+                        .and_then(|datetime| datetime.timestamp_nanos_opt())
+                })
+                .collect();
+            nanoseconds.resize(rows, None);
+            Arc::new(
+                PrimitiveArray::<i64>::from(nanoseconds)
+                    .to(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+            )
+        }
+    }
+}
+
+fn primitive_array<T: arrow2::types::NativeType>(
+    mut values: Vec<T>,
+    rows: usize,
+    validity: Option<Bitmap>,
+) -> Arc<dyn Array> {
+    values.resize(rows, T::default());
+    Arc::new(PrimitiveArray::from_vec(values).with_validity(validity))
+}
@@ -0,0 +1,211 @@
+use crate::error::Result;
+use crate::object_path::{path_from_channel, path_from_group, ObjectPathId};
+use crate::options::ReadOptions;
+use crate::properties::TdmsProperty;
+use crate::tdms_reader::{LeadIn, TdmsReader, LEAD_IN_LENGTH, SEGMENT_TAG};
+use crate::toc::TocFlag;
+use crate::types::{NativeType, TdsType};
+use std::io::{Cursor, SeekFrom};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, BufReader};
+use tokio::sync::Mutex;
+
+/// A TDMS file that is read asynchronously using tokio
+pub struct TdmsFileAsync<R: AsyncRead + AsyncSeek + Unpin> {
+    file_reader: Mutex<BufReader<R>>,
+    tdms_reader: TdmsReader,
+}
+
+pub struct AsyncGroup<'a, R: AsyncRead + AsyncSeek + Unpin> {
+    file: &'a TdmsFileAsync<R>,
+    object_id: ObjectPathId,
+}
+
+pub struct AsyncChannel<'a, R: AsyncRead + AsyncSeek + Unpin> {
+    file: &'a TdmsFileAsync<R>,
+    object_id: ObjectPathId,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> TdmsFileAsync<R> {
+    /// Create a new TdmsFileAsync object, parsing TDMS metadata from the reader
+    pub async fn new(file_reader: R) -> Result<TdmsFileAsync<R>> {
+        TdmsFileAsync::with_options(file_reader, &ReadOptions::default()).await
+    }
+
+    /// Create a new TdmsFileAsync object, parsing TDMS metadata from the reader with the given options
+    pub async fn with_options(file_reader: R, options: &ReadOptions) -> Result<TdmsFileAsync<R>> {
+        let mut file_reader =
+            BufReader::with_capacity(options.reader_buffer_capacity(), file_reader);
+        let tdms_reader = read_metadata(&mut file_reader, options).await?;
+        Ok(TdmsFileAsync {
+            file_reader: Mutex::new(file_reader),
+            tdms_reader,
+        })
+    }
+
+    pub fn properties(&self) -> &Vec<TdmsProperty> {
+        self.tdms_reader.root_properties()
+    }
+
+    /// Get warnings about problems in the file that were recovered from when reading metadata
+    pub fn warnings(&self) -> &[String] {
+        self.tdms_reader.warnings()
+    }
+
+    /// Get a group within the TDMS file
+    pub fn group(&self, group_name: &str) -> Option<AsyncGroup<'_, R>> {
+        let group_path = path_from_group(group_name);
+        self.tdms_reader
+            .get_object_id(&group_path)
+            .map(move |object_id| AsyncGroup {
+                file: self,
+                object_id,
+            })
+    }
+
+    /// Get an iterator over groups within this TDMS file
+    pub fn groups(&self) -> impl Iterator<Item = AsyncGroup<'_, R>> {
+        self.tdms_reader
+            .group_ids()
+            .into_iter()
+            .map(move |object_id| AsyncGroup {
+                file: self,
+                object_id,
+            })
+    }
+}
+
+impl<'a, R: AsyncRead + AsyncSeek + Unpin> AsyncGroup<'a, R> {
+    /// Get the name of this group
+    pub fn name(&self) -> &'a str {
+        self.file.tdms_reader.group_name(self.object_id)
+    }
+
+    pub fn properties(&self) -> &'a Vec<TdmsProperty> {
+        self.file.tdms_reader.get_properties(self.object_id)
+    }
+
+    /// Get a channel within this group
+    pub fn channel(&self, channel_name: &str) -> Option<AsyncChannel<'a, R>> {
+        let channel_path = path_from_channel(self.name(), channel_name);
+        let file = self.file;
+        file.tdms_reader
+            .get_object_id(&channel_path)
+            .map(move |object_id| AsyncChannel { file, object_id })
+    }
+
+    /// Get an iterator over channels within this group
+    pub fn channels(&self) -> impl Iterator<Item = AsyncChannel<'a, R>> {
+        let file = self.file;
+        file.tdms_reader
+            .channel_ids(self.name())
+            .into_iter()
+            .map(move |object_id| AsyncChannel { file, object_id })
+    }
+}
+
+impl<'a, R: AsyncRead + AsyncSeek + Unpin> AsyncChannel<'a, R> {
+    /// Get the name of this channel
+    pub fn name(&self) -> &'a str {
+        self.file.tdms_reader.channel_name(self.object_id)
+    }
+
+    pub fn properties(&self) -> &'a Vec<TdmsProperty> {
+        self.file.tdms_reader.get_properties(self.object_id)
+    }
+
+    pub fn data_type(&self) -> TdsType {
+        self.file.tdms_reader.channel_data_type(self.object_id)
+    }
+
+    /// Get the total number of values in this channel
+    pub fn len(&self) -> u64 {
+        self.file.tdms_reader.channel_len(self.object_id)
+    }
+
+    /// Whether this channel has no data
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read all data for this channel into the given buffer.
+    pub async fn read_all_data<T: NativeType>(&self, buffer: &mut [T]) -> Result<()> {
+        let chunks = self
+            .file
+            .tdms_reader
+            .plan_channel_read::<T>(self.object_id, buffer.len())?;
+        let mut reader = self.file.file_reader.lock().await;
+        let mut chunk_bytes = Vec::new();
+        let mut offset = 0;
+        for chunk in chunks {
+            self.file.tdms_reader.chunk_decoded();
+            chunk_bytes.resize(chunk.byte_length as usize, 0);
+            reader.seek(SeekFrom::Start(chunk.position)).await?;
+            reader.read_exact(&mut chunk_bytes).await?;
+            offset += chunk.decode_values(&chunk_bytes, &mut buffer[offset..])?;
+        }
+        Ok(())
+    }
+}
+
+/// Read metadata from all segments, reading each segment's metadata into memory before parsing it
+async fn read_metadata<R: AsyncRead + AsyncSeek + Unpin>(
+    reader: &mut R,
+    options: &ReadOptions,
+) -> Result<TdmsReader> {
+    let mut tdms_reader = TdmsReader::new(options);
+    let mut metadata_bytes = Vec::new();
+    let start_position = reader.stream_position().await?;
+    let file_length = reader.seek(SeekFrom::End(0)).await?;
+    reader.seek(SeekFrom::Start(start_position)).await?;
+    loop {
+        let position = reader.stream_position().await?;
+        let lead_in = match read_lead_in(reader, position).await? {
+            Some(lead_in) => lead_in,
+            None => {
+                // Reached end of file
+                break;
+            }
+        };
+        metadata_bytes.clear();
+        if lead_in.toc_mask.has_flag(TocFlag::MetaData) {
+            // Grow the buffer as data is read in case the raw data offset is corrupt
+            (&mut *reader)
+                .take(lead_in.raw_data_offset)
+                .read_to_end(&mut metadata_bytes)
+                .await?;
+        }
+        let next_segment_position = tdms_reader.add_segment(
+            &lead_in,
+            position,
+            &mut Cursor::new(&metadata_bytes),
+            file_length,
+        )?;
+        tdms_reader.report_progress(start_position, next_segment_position, file_length);
+        // Seek to the start of the next segment
+        reader.seek(SeekFrom::Start(next_segment_position)).await?;
+    }
+    Ok(tdms_reader)
+}
+
+/// Read a segment lead in, returning None if the reader is at the end of the file
+async fn read_lead_in<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    position: u64,
+) -> Result<Option<LeadIn>> {
+    let mut lead_in_bytes = [0u8; LEAD_IN_LENGTH];
+    let mut bytes_read = 0;
+    while bytes_read < 4 {
+        match reader.read(&mut lead_in_bytes[bytes_read..4]).await? {
+            0 => return Ok(None),
+            n => bytes_read += n,
+        }
+    }
+    reader.read_exact(&mut lead_in_bytes[4..]).await?;
+    LeadIn::parse(&lead_in_bytes, position, SEGMENT_TAG).map(Some)
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> std::fmt::Debug for TdmsFileAsync<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TdmsFileAsync").finish()
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{create_file, open_file, FilterArgs, ObjectFilter};
+use clap::Args;
+use rstdms::{Channel, NativeTypeId, TdmsFile, Timestamp};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Computes a SHA-256 checksum of the decoded data of each channel, or verifies
+/// checksums in a manifest. Checksums don't depend on how data is laid out in the file,
+/// so are unchanged by rewriting a file, for example to defragment it.
+#[derive(Args)]
+pub struct ChecksumArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+
+    /// Path of a manifest file to write, with a line per channel containing the checksum
+    /// and channel path. Defaults to writing to standard output
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Path of a manifest file to check the channel checksums against
+    #[arg(long, value_name = "MANIFEST", conflicts_with = "output")]
+    verify: Option<PathBuf>,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+}
+
+pub fn run(args: &ChecksumArgs) -> Result<(), String> {
+    let tdms_file = open_file(&args.path)?;
+    let filter = ObjectFilter::new(&args.filter);
+    match &args.verify {
+        Some(manifest_path) => verify(&tdms_file, &filter, manifest_path),
+        None => write_manifest(args, &tdms_file, &filter),
+    }
+}
+
+fn write_manifest(
+    args: &ChecksumArgs,
+    tdms_file: &TdmsFile<File>,
+    filter: &ObjectFilter,
+) -> Result<(), String> {
+    let (mut writer, manifest_name): (Box<dyn Write>, String) = match &args.output {
+        Some(path) => (
+            Box::new(BufWriter::new(create_file(path, "manifest")?)),
+            path.display().to_string(),
+        ),
+        None => (Box::new(std::io::stdout().lock()), "-".to_owned()),
+    };
+    let write_error = |err| format!("Error writing manifest file {}: {}", manifest_name, err);
+    for group in tdms_file
+        .groups()
+        .filter(|group| filter.matches_group(group.name()))
+    {
+        for channel in
+            group.channels_where(|channel| filter.matches_channel(group.name(), channel.name()))
+        {
+            let checksum = channel_checksum(&channel)?;
+            writeln!(writer, "{}  {}", checksum, channel.path()).map_err(write_error)?;
+        }
+    }
+    writer.flush().map_err(write_error)
+}
+
+/// Compare channel checksums with those in a manifest, printing the result for each channel
+fn verify(
+    tdms_file: &TdmsFile<File>,
+    filter: &ObjectFilter,
+    manifest_path: &Path,
+) -> Result<(), String> {
+    let manifest = match std::fs::read_to_string(manifest_path) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            return Err(format!(
+                "Error reading manifest file {}: {}",
+                manifest_path.display(),
+                err
+            ))
+        }
+    };
+    let mut expected = HashMap::new();
+    for (line_number, line) in manifest.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        match line.split_once("  ") {
+            Some((checksum, path)) => {
+                expected.insert(path, checksum);
+            }
+            None => {
+                return Err(format!(
+                    "Invalid line {} in manifest file {}",
+                    line_number + 1,
+                    manifest_path.display()
+                ))
+            }
+        }
+    }
+
+    let mut failures = 0;
+    let mut checked = 0;
+    for group in tdms_file
+        .groups()
+        .filter(|group| filter.matches_group(group.name()))
+    {
+        for channel in
+            group.channels_where(|channel| filter.matches_channel(group.name(), channel.name()))
+        {
+            let path = channel.path();
+            let status = match expected.remove(path.as_str()) {
+                Some(checksum) if checksum == channel_checksum(&channel)? => "OK",
+                Some(_) => "FAILED",
+                None => "NOT IN MANIFEST",
+            };
+            if status != "OK" {
+                failures += 1;
+            }
+            checked += 1;
+            println!("{}: {}", path, status);
+        }
+    }
+    // Channels excluded by the filter aren't expected to be present
+    if !filter.filters_channels() && filter.group_patterns.is_empty() {
+        let mut missing = expected.into_keys().collect::<Vec<_>>();
+        missing.sort();
+        for path in missing {
+            failures += 1;
+            checked += 1;
+            println!("{}: MISSING", path);
+        }
+    }
+    if failures > 0 {
+        return Err(format!(
+            "{} of {} channels did not match the manifest",
+            failures, checked
+        ));
+    }
+    Ok(())
+}
+
+/// Compute the SHA-256 hash of a channel's values as a hex string. Numbers are hashed as
+/// little-endian bytes, strings as their UTF-8 length as a little-endian u64 followed by
+/// their bytes, and timestamps as their seconds followed by their second fractions.
+fn channel_checksum(channel: &Channel<File>) -> Result<String, String> {
+    let read_error = |err| format!("Error reading channel {}: {}", channel.path(), err);
+    let mut hasher = Sha256::new();
+    macro_rules! hash_values {
+        ($t:ty, |$value:ident| $hash:block) => {
+            for value in channel.iter_values::<$t>().map_err(read_error)? {
+                let $value: $t = value.map_err(read_error)?;
+                $hash
+            }
+        };
+    }
+    macro_rules! hash_numbers {
+        ($t:ty) => {
+            hash_values!($t, |value| { hasher.update(value.to_le_bytes()) })
+        };
+    }
+    match channel.data_type().native_type() {
+        Some(NativeTypeId::I8) => hash_numbers!(i8),
+        Some(NativeTypeId::I16) => hash_numbers!(i16),
+        Some(NativeTypeId::I32) => hash_numbers!(i32),
+        Some(NativeTypeId::I64) => hash_numbers!(i64),
+        Some(NativeTypeId::U8) => hash_numbers!(u8),
+        Some(NativeTypeId::U16) => hash_numbers!(u16),
+        Some(NativeTypeId::U32) => hash_numbers!(u32),
+        Some(NativeTypeId::U64) => hash_numbers!(u64),
+        Some(NativeTypeId::F32) => hash_numbers!(f32),
+        Some(NativeTypeId::F64) => hash_numbers!(f64),
+        Some(NativeTypeId::String) => hash_values!(String, |value| {
+            hasher.update((value.len() as u64).to_le_bytes());
+            hasher.update(value.as_bytes());
+        }),
+        Some(NativeTypeId::Timestamp) => hash_values!(Timestamp, |value| {
+            hasher.update(value.seconds.to_le_bytes());
+            hasher.update(value.second_fractions.to_le_bytes());
+        }),
+        // Channels of other types can't be read so have the checksum of no data
+        None => {}
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
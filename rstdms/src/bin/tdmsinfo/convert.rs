@@ -0,0 +1,387 @@
+#[cfg(feature = "parquet")]
+use crate::group_file_path;
+use crate::{create_file, open_file, parse_timestamp, FilterArgs, ObjectFilter};
+use clap::Args;
+#[cfg(feature = "npz")]
+use rstdms::write_npz;
+use rstdms::{
+    write_csv, write_line_protocol, write_ndjson, Channel, ChannelData, CsvOptions, TdmsWriter,
+    WriteObject,
+};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Exports channel data
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+
+    #[command(flatten)]
+    output: ExportOutput,
+
+    /// Write a time column computed from the wf_increment property
+    #[arg(long)]
+    time: bool,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+}
+
+/// Files to export data to, exactly one of which must be given
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+struct ExportOutput {
+    /// Path of the CSV file to write
+    #[arg(long, value_name = "FILE")]
+    csv: Option<PathBuf>,
+
+    /// Path of a NumPy .npz archive to write, with one array per channel
+    #[cfg(feature = "npz")]
+    #[arg(long, value_name = "FILE", conflicts_with = "time")]
+    npz: Option<PathBuf>,
+
+    /// Path of a file to write InfluxDB line protocol to, with a measurement per group
+    /// and a field per channel, or - to write to standard output
+    #[arg(long, value_name = "FILE", conflicts_with = "time")]
+    influx: Option<PathBuf>,
+
+    /// Path of a file to write newline-delimited JSON to, with a record per value,
+    /// or - to write to standard output
+    #[arg(long, value_name = "FILE", conflicts_with = "time")]
+    ndjson: Option<PathBuf>,
+}
+
+pub fn export(args: &ExportArgs) -> Result<(), String> {
+    let tdms_file = open_file(&args.path)?;
+    let filter = ObjectFilter::new(&args.filter);
+
+    let groups = tdms_file
+        .groups()
+        .filter(|group| filter.matches_group(group.name()))
+        .collect::<Vec<_>>();
+    let channels = groups
+        .iter()
+        .flat_map(|group| {
+            group.channels_where(|channel| filter.matches_channel(group.name(), channel.name()))
+        })
+        .collect::<Vec<_>>();
+    if channels.is_empty() {
+        return Err("No channels to export".to_owned());
+    }
+
+    #[cfg(feature = "npz")]
+    if let Some(npz_path) = &args.output.npz {
+        return export_npz(&channels, npz_path);
+    }
+    if let Some(influx_path) = &args.output.influx {
+        return export_text(
+            &channels,
+            influx_path,
+            "line protocol",
+            |channels, writer| write_line_protocol(channels, writer),
+        );
+    }
+    if let Some(ndjson_path) = &args.output.ndjson {
+        return export_text(&channels, ndjson_path, "NDJSON", |channels, writer| {
+            write_ndjson(channels, writer)
+        });
+    }
+
+    let csv_path = args.output.csv.as_ref().unwrap();
+    let csv_file = create_file(csv_path, "CSV")?;
+    let options = CsvOptions::new().time_column(args.time);
+    match write_csv(&channels, BufWriter::new(csv_file), &options) {
+        Ok(()) => Ok(()),
+        Err(err) => Err(format!(
+            "Error writing CSV file {}: {}",
+            csv_path.display(),
+            err
+        )),
+    }
+}
+
+/// Export channels as text to a file, or to standard output if the path is -
+fn export_text<F>(
+    channels: &[Channel<File>],
+    path: &Path,
+    description: &str,
+    write: F,
+) -> Result<(), String>
+where
+    F: Fn(&[Channel<File>], &mut dyn Write) -> rstdms::Result<()>,
+{
+    let result = if path == Path::new("-") {
+        write(channels, &mut std::io::stdout().lock())
+    } else {
+        let file = create_file(path, description)?;
+        write(channels, &mut BufWriter::new(file))
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(err) => Err(format!(
+            "Error writing {} file {}: {}",
+            description,
+            path.display(),
+            err
+        )),
+    }
+}
+
+#[cfg(feature = "npz")]
+fn export_npz(channels: &[Channel<File>], npz_path: &Path) -> Result<(), String> {
+    let npz_file = create_file(npz_path, "npz")?;
+    match write_npz(channels, BufWriter::new(npz_file)) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(format!(
+            "Error writing npz file {}: {}",
+            npz_path.display(),
+            err
+        )),
+    }
+}
+
+/// Converts each group to a Parquet file, named FILE_GROUP.parquet
+#[cfg(feature = "parquet")]
+#[derive(Args)]
+pub struct ToParquetArgs {
+    /// Paths to the TDMS files to convert
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// Only include groups with names matching a glob pattern, can be repeated
+    #[arg(long = "group", value_name = "PATTERN")]
+    groups: Vec<String>,
+
+    /// Directory to write Parquet files to. Defaults to the current directory
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    output_dir: PathBuf,
+
+    /// Write a time column computed from the wf_increment property
+    #[arg(long)]
+    time: bool,
+}
+
+#[cfg(feature = "parquet")]
+pub fn to_parquet(args: &ToParquetArgs) -> Result<(), String> {
+    let filter = ObjectFilter {
+        group_patterns: &args.groups,
+        channel_patterns: &[],
+    };
+    for path in &args.paths {
+        let tdms_file = open_file(path)?;
+        for group in tdms_file
+            .groups()
+            .filter(|group| filter.matches_group(group.name()))
+        {
+            let parquet_path = group_file_path(&args.output_dir, path, group.name(), "parquet");
+            let parquet_file = create_file(&parquet_path, "Parquet")?;
+            if let Err(err) = group.write_parquet(BufWriter::new(parquet_file), args.time) {
+                return Err(format!(
+                    "Error writing Parquet file {}: {}",
+                    parquet_path.display(),
+                    err
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Converts a CSV file with a header row to a TDMS file, with a channel per column
+#[derive(Args)]
+pub struct FromCsvArgs {
+    /// Path to the CSV file to read
+    csv: PathBuf,
+
+    /// Path of the TDMS file to write
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+
+    /// Name of the group to write channels to
+    #[arg(long, value_name = "NAME", default_value = "Group")]
+    group: String,
+
+    /// Character that separates values
+    #[arg(long, value_name = "CHAR", default_value_t = ',')]
+    delimiter: char,
+}
+
+pub fn from_csv(args: &FromCsvArgs) -> Result<(), String> {
+    let csv_path = args.csv.display();
+    let text = match std::fs::read_to_string(&args.csv) {
+        Ok(text) => text,
+        Err(err) => return Err(format!("Error reading CSV file {}: {}", csv_path, err)),
+    };
+    if args.delimiter == '"' {
+        return Err("Delimiter must be a single character other than '\"'".to_owned());
+    }
+    let mut records = parse_csv(&text, args.delimiter).into_iter();
+    let header = match records.next() {
+        Some(header) => header,
+        None => return Err(format!("CSV file {} is empty", csv_path)),
+    };
+    let mut columns = vec![Vec::new(); header.len()];
+    for (row, record) in records.enumerate() {
+        if record.len() > header.len() {
+            return Err(format!(
+                "Row {} of CSV file {} has {} values but there are {} columns",
+                row + 2,
+                csv_path,
+                record.len(),
+                header.len()
+            ));
+        }
+        let missing = header.len() - record.len();
+        for (column, value) in columns.iter_mut().zip(
+            record
+                .into_iter()
+                .chain(std::iter::repeat_n(String::new(), missing)),
+        ) {
+            column.push(value);
+        }
+    }
+
+    let group_name = &args.group;
+    let mut objects = vec![
+        WriteObject::root(Vec::new()),
+        WriteObject::group(group_name, Vec::new()),
+    ];
+    for (name, mut values) in header.iter().zip(columns) {
+        // Shorter columns are padded with empty values when exported
+        while values.last().is_some_and(|value| value.is_empty()) {
+            values.pop();
+        }
+        let data = if values.is_empty() {
+            None
+        } else {
+            Some(infer_channel_data(values))
+        };
+        objects.push(WriteObject::channel(group_name, name, Vec::new(), data));
+    }
+
+    let output_file = create_file(&args.output, "TDMS")?;
+    let write_error = |err| format!("Error writing TDMS file {}: {}", args.output.display(), err);
+    let mut writer = TdmsWriter::new(BufWriter::new(output_file));
+    writer.write_segment(&objects).map_err(write_error)?;
+    writer.into_inner().map_err(write_error)?;
+    Ok(())
+}
+
+/// Split CSV text into records of fields. Fields may be quoted with double quotes,
+/// in which case they may contain delimiters, newlines and doubled quotes.
+fn parse_csv(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+        } else {
+            match c {
+                '"' => in_quotes = true,
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' | '\r' => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                c if c == delimiter => record.push(std::mem::take(&mut field)),
+                c => field.push(c),
+            }
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Convert the values of a CSV column to channel data, using the narrowest of 32 and 64 bit
+/// integers, 64 bit floats, timestamps and strings that can represent all values.
+/// Empty values are only allowed in float columns, where they are NaN.
+fn infer_channel_data(values: Vec<String>) -> ChannelData {
+    if let Some(ints) = parse_all(&values, |value| value.parse::<i64>().ok()) {
+        return match ints.iter().map(|&v| i32::try_from(v)).collect() {
+            Ok(ints) => ChannelData::I32(ints),
+            Err(_) => ChannelData::I64(ints),
+        };
+    }
+    let parse_float = |value: &str| match value {
+        "" => Some(f64::NAN),
+        value => value.parse::<f64>().ok(),
+    };
+    if let Some(floats) = parse_all(&values, parse_float) {
+        return ChannelData::F64(floats);
+    }
+    if let Some(timestamps) = parse_all(&values, parse_timestamp) {
+        return ChannelData::Timestamp(timestamps);
+    }
+    ChannelData::String(values)
+}
+
+fn parse_all<T, F: Fn(&str) -> Option<T>>(values: &[String], parse: F) -> Option<Vec<T>> {
+    values.iter().map(|value| parse(value.trim())).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstdms::Timestamp;
+
+    #[test]
+    pub fn csv_parsing() {
+        assert_eq!(
+            parse_csv("a,b\r\n1,\"x, \"\"y\"\"\"\n2\n", ','),
+            vec![
+                vec!["a".to_owned(), "b".to_owned()],
+                vec!["1".to_owned(), "x, \"y\"".to_owned()],
+                vec!["2".to_owned()],
+            ]
+        );
+        assert_eq!(
+            parse_csv("a;\"multi\nline\"", ';'),
+            vec![vec!["a".to_owned(), "multi\nline".to_owned()]]
+        );
+        assert!(parse_csv("", ',').is_empty());
+    }
+
+    #[test]
+    pub fn csv_type_inference() {
+        let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            infer_channel_data(strings(&["1", "-2"])),
+            ChannelData::I32(vec![1, -2])
+        );
+        assert_eq!(
+            infer_channel_data(strings(&["1", "3000000000"])),
+            ChannelData::I64(vec![1, 3000000000])
+        );
+        match infer_channel_data(strings(&["1", "", "2.5"])) {
+            ChannelData::F64(values) => {
+                assert_eq!(values[0], 1.0);
+                assert!(values[1].is_nan());
+                assert_eq!(values[2], 2.5);
+            }
+            data => panic!("Unexpected data {:?}", data),
+        }
+        assert_eq!(
+            infer_channel_data(strings(&["2015-09-09T13:19:55.5+00:00"])),
+            ChannelData::Timestamp(vec![Timestamp::new(3524649595, 1 << 63)])
+        );
+        assert_eq!(
+            infer_channel_data(strings(&["1", "a"])),
+            ChannelData::String(strings(&["1", "a"]))
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use crate::yaml::{yaml_float, yaml_string};
+use crate::{open_file, FilterArgs, FormatArgs, ObjectFilter, OutputFormat};
+use clap::Args;
+use rstdms::{TdmsFile, TdmsProperty, TdmsValue};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+
+/// Displays TDMS file metadata
+#[derive(Args)]
+pub struct InfoArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+
+    /// Display object properties
+    #[arg(short, long)]
+    properties: bool,
+
+    /// Keep reading segments appended to the file and display updated channel lengths
+    #[arg(short, long)]
+    follow: bool,
+
+    /// Time between checks for new segments when following a file
+    #[arg(long, value_name = "SECONDS", default_value_t = 1.0)]
+    interval: f64,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+
+    #[command(flatten)]
+    format: FormatArgs,
+}
+
+pub fn run(args: &InfoArgs) -> Result<(), String> {
+    let mut tdms_file = open_file(&args.path)?;
+    let filter = ObjectFilter::new(&args.filter);
+
+    if args.format.format == OutputFormat::Yaml {
+        if args.follow {
+            return Err("--follow can only be used with the text format".to_owned());
+        }
+        print_yaml(&tdms_file, &filter, args.properties);
+        return Ok(());
+    }
+    if args.properties {
+        println!("/");
+        print_properties(tdms_file.properties());
+    }
+    for group in tdms_file.groups() {
+        let channels =
+            group.channels_where(|channel| filter.matches_channel(group.name(), channel.name()));
+        if !filter.matches_group(group.name()) || (channels.is_empty() && filter.filters_channels())
+        {
+            continue;
+        }
+        println!("{}", group.name());
+        if args.properties {
+            print_properties(group.properties());
+        }
+        for channel in channels {
+            println!(
+                "{} / {} ({:?}, {} values, {} bytes)",
+                group.name(),
+                channel.name(),
+                channel.data_type(),
+                channel.len(),
+                channel.raw_data_size()
+            );
+            if args.properties {
+                print_properties(channel.properties());
+            }
+        }
+    }
+
+    if args.follow {
+        follow(args, &mut tdms_file, &filter)?;
+    }
+    Ok(())
+}
+
+/// Poll a file for appended segments, printing the new lengths of channels that have grown
+fn follow(
+    args: &InfoArgs,
+    tdms_file: &mut TdmsFile<File>,
+    filter: &ObjectFilter,
+) -> Result<(), String> {
+    let interval = match args.interval {
+        interval if interval > 0.0 => std::time::Duration::from_secs_f64(interval),
+        _ => return Err("Interval must be a positive number of seconds".to_owned()),
+    };
+    let mut lengths = HashMap::new();
+    loop {
+        for group in tdms_file
+            .groups()
+            .filter(|group| filter.matches_group(group.name()))
+        {
+            for channel in
+                group.channels_where(|channel| filter.matches_channel(group.name(), channel.name()))
+            {
+                let len = channel.len();
+                let previous_len = lengths
+                    .insert((group.name().to_owned(), channel.name().to_owned()), len)
+                    .unwrap_or(len);
+                if len > previous_len {
+                    println!(
+                        "{} / {}: {} values (+{})",
+                        group.name(),
+                        channel.name(),
+                        len,
+                        len - previous_len
+                    );
+                }
+            }
+        }
+        loop {
+            std::thread::sleep(interval);
+            match tdms_file.refresh() {
+                Ok(0) => {}
+                Ok(_) => break,
+                Err(err) => {
+                    return Err(format!(
+                        "Error reading TDMS file {}: {}",
+                        args.path.display(),
+                        err
+                    ))
+                }
+            }
+        }
+    }
+}
+
+fn print_properties(properties: &[TdmsProperty]) {
+    for property in properties {
+        println!(
+            "    {} ({:?}): {}",
+            property.name,
+            property.value.data_type(),
+            property.value
+        );
+    }
+}
+
+/// Print the metadata of the selected groups and channels as a YAML document
+fn print_yaml(tdms_file: &TdmsFile<File>, filter: &ObjectFilter, show_properties: bool) {
+    if show_properties {
+        print_yaml_properties(tdms_file.properties(), "");
+    }
+    println!("groups:");
+    for group in tdms_file.groups() {
+        let channels =
+            group.channels_where(|channel| filter.matches_channel(group.name(), channel.name()));
+        if !filter.matches_group(group.name()) || (channels.is_empty() && filter.filters_channels())
+        {
+            continue;
+        }
+        println!("  - name: {}", yaml_string(group.name()));
+        if show_properties {
+            print_yaml_properties(group.properties(), "    ");
+        }
+        println!("    channels:");
+        for channel in channels {
+            println!("      - name: {}", yaml_string(channel.name()));
+            println!("        data_type: {:?}", channel.data_type());
+            println!("        length: {}", channel.len());
+            println!("        size: {}", channel.raw_data_size());
+            if show_properties {
+                print_yaml_properties(channel.properties(), "        ");
+            }
+        }
+    }
+}
+
+fn print_yaml_properties(properties: &[TdmsProperty], indent: &str) {
+    if properties.is_empty() {
+        println!("{}properties: {{}}", indent);
+        return;
+    }
+    println!("{}properties:", indent);
+    for property in properties {
+        let value = match &property.value {
+            TdmsValue::String(value) => yaml_string(value),
+            TdmsValue::Float32(value) => yaml_float(*value as f64),
+            TdmsValue::Float64(value) => yaml_float(*value),
+            value => value.to_string(),
+        };
+        println!("{}  {}: {}", indent, yaml_string(&property.name), value);
+    }
+}
@@ -0,0 +1,250 @@
+mod checksum;
+mod convert;
+mod info;
+mod plot;
+mod raw;
+mod rewrite;
+mod stats;
+#[cfg(feature = "tui")]
+mod tui;
+mod yaml;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rstdms::{TdmsFile, Timestamp};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Inspects, converts and edits TDMS files
+#[derive(Parser)]
+#[command(name = "tdmsinfo", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    Info(info::InfoArgs),
+    Stats(stats::StatsArgs),
+    Plot(plot::PlotArgs),
+    Checksum(checksum::ChecksumArgs),
+    ExtractRaw(raw::ExtractRawArgs),
+    Export(convert::ExportArgs),
+    #[cfg(feature = "parquet")]
+    ToParquet(convert::ToParquetArgs),
+    FromCsv(convert::FromCsvArgs),
+    Merge(rewrite::MergeArgs),
+    Split(rewrite::SplitArgs),
+    StripProperties(rewrite::StripPropertiesArgs),
+    SetProperty(rewrite::SetPropertyArgs),
+    Rename(rewrite::RenameArgs),
+    Trim(rewrite::TrimArgs),
+    Index(rewrite::IndexArgs),
+    #[cfg(feature = "tui")]
+    Browse(tui::BrowseArgs),
+}
+
+/// Options for selecting groups and channels, shared by commands that read channel data
+#[derive(Args, Default)]
+struct FilterArgs {
+    /// Only include groups with names matching a glob pattern, can be repeated
+    #[arg(long = "group", value_name = "PATTERN")]
+    groups: Vec<String>,
+
+    /// Only include channels with names matching a glob pattern,
+    /// optionally prefixed with a group name pattern, can be repeated
+    #[arg(long = "channel", value_name = "[GROUP/]CHANNEL")]
+    channels: Vec<String>,
+}
+
+/// Options for how results are displayed
+#[derive(Args)]
+struct FormatArgs {
+    /// Format to display results in
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Yaml,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Info(args) => info::run(args),
+        Command::Stats(args) => stats::run(args),
+        Command::Plot(args) => plot::run(args),
+        Command::Checksum(args) => checksum::run(args),
+        Command::ExtractRaw(args) => raw::run(args),
+        Command::Export(args) => convert::export(args),
+        #[cfg(feature = "parquet")]
+        Command::ToParquet(args) => convert::to_parquet(args),
+        Command::FromCsv(args) => convert::from_csv(args),
+        Command::Merge(args) => rewrite::merge(args),
+        Command::Split(args) => rewrite::split(args),
+        Command::StripProperties(args) => rewrite::strip_properties(args),
+        Command::SetProperty(args) => rewrite::set_property(args),
+        Command::Rename(args) => rewrite::rename(args),
+        Command::Trim(args) => rewrite::trim(args),
+        Command::Index(args) => rewrite::index(args),
+        #[cfg(feature = "tui")]
+        Command::Browse(args) => tui::run(args),
+    };
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        std::process::exit(1);
+    }
+}
+
+fn open_file(path: &Path) -> Result<TdmsFile<File>, String> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(format!("Error opening path {}: {}", path.display(), err));
+        }
+    };
+    match TdmsFile::new(file) {
+        Ok(tdms_file) => Ok(tdms_file),
+        Err(err) => Err(format!(
+            "Error reading TDMS file {}: {}",
+            path.display(),
+            err
+        )),
+    }
+}
+
+fn create_file(path: &Path, description: &str) -> Result<File, String> {
+    match File::create(path) {
+        Ok(file) => Ok(file),
+        Err(err) => Err(format!(
+            "Error creating {} file {}: {}",
+            description,
+            path.display(),
+            err
+        )),
+    }
+}
+
+/// Parse a timestamp in RFC 3339 format
+fn parse_timestamp(value: &str) -> Option<Timestamp> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|datetime| Timestamp::from(datetime.with_timezone(&chrono::Utc)))
+}
+
+/// Get the path of a file to write the data of one group of a TDMS file to
+fn group_file_path(
+    output_dir: &Path,
+    tdms_path: &Path,
+    group_name: &str,
+    extension: &str,
+) -> PathBuf {
+    let file_stem = tdms_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // Group names may contain characters that aren't valid in file names
+    let group_name = group_name.replace(['/', '\\'], "_");
+    output_dir.join(format!("{}_{}.{}", file_stem, group_name, extension))
+}
+
+/// Selects groups and channels using glob patterns given on the command line
+struct ObjectFilter<'a> {
+    group_patterns: &'a [String],
+    channel_patterns: &'a [String],
+}
+
+impl<'a> ObjectFilter<'a> {
+    fn new(args: &'a FilterArgs) -> ObjectFilter<'a> {
+        ObjectFilter {
+            group_patterns: &args.groups,
+            channel_patterns: &args.channels,
+        }
+    }
+
+    fn filters_channels(&self) -> bool {
+        !self.channel_patterns.is_empty()
+    }
+
+    fn matches_group(&self, group_name: &str) -> bool {
+        self.group_patterns.is_empty()
+            || self
+                .group_patterns
+                .iter()
+                .any(|pattern| glob_match(pattern, group_name))
+    }
+
+    fn matches_channel(&self, group_name: &str, channel_name: &str) -> bool {
+        self.channel_patterns.is_empty()
+            || self
+                .channel_patterns
+                .iter()
+                .any(|pattern| match pattern.split_once('/') {
+                    Some((group_pattern, channel_pattern)) => {
+                        glob_match(group_pattern, group_name)
+                            && glob_match(channel_pattern, channel_name)
+                    }
+                    None => glob_match(pattern, channel_name),
+                })
+    }
+}
+
+/// Match text against a glob pattern where '*' matches any sequence of characters
+/// and '?' matches any single character
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last '*' in the pattern and the text position it was tried at
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    // Let the last '*' match one more character
+                    backtrack = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    pub fn cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    pub fn glob_patterns() {
+        assert!(glob_match("Channel1", "Channel1"));
+        assert!(!glob_match("Channel1", "Channel10"));
+        assert!(glob_match("Channel*", "Channel10"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*1", "Channel1"));
+        assert!(glob_match("C*n*1", "Channel1"));
+        assert!(!glob_match("C*x*1", "Channel1"));
+        assert!(glob_match("Channel?", "Channel2"));
+        assert!(!glob_match("Channel?", "Channel"));
+        assert!(glob_match("*nel*", "Channel1"));
+    }
+}
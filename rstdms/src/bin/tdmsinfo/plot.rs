@@ -0,0 +1,180 @@
+use crate::open_file;
+use clap::Args;
+use rstdms::{Channel, NativeTypeId};
+use std::fs::File;
+use std::path::PathBuf;
+
+/// Plots the minimum and maximum values of a channel's data in the terminal
+#[derive(Args)]
+pub struct PlotArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+
+    /// Name of the group containing the channel
+    group: String,
+
+    /// Name of the channel to plot
+    channel: String,
+
+    /// Number of columns to divide the channel data into
+    #[arg(long, default_value_t = 72, value_parser = clap::value_parser!(u16).range(1..))]
+    width: u16,
+
+    /// Number of lines the plot spans
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u16).range(2..))]
+    height: u16,
+}
+
+pub fn run(args: &PlotArgs) -> Result<(), String> {
+    let tdms_file = open_file(&args.path)?;
+    let group = match tdms_file.group(&args.group) {
+        Some(group) => group,
+        None => return Err(format!("Group {} not found", args.group)),
+    };
+    let channel = match group.channel(&args.channel) {
+        Some(channel) => channel,
+        None => return Err(format!("Channel {} not found", args.channel)),
+    };
+    let envelope = match channel_envelope(&channel, args.width as usize) {
+        Ok(Some(envelope)) => envelope,
+        Ok(None) if channel.is_empty() => {
+            return Err(format!("Channel {} has no data", channel.path()));
+        }
+        Ok(None) => {
+            return Err(format!(
+                "Channel {} does not have numeric data",
+                channel.path()
+            ));
+        }
+        Err(err) => return Err(format!("Error reading channel {}: {}", channel.path(), err)),
+    };
+    let lines = match render_plot(&envelope, channel.len(), args.height as usize) {
+        Some(lines) => lines,
+        None => return Err(format!("Channel {} has no finite values", channel.path())),
+    };
+    println!(
+        "{} / {} ({} values)",
+        group.name(),
+        channel.name(),
+        channel.len()
+    );
+    for line in lines {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Read the minimum and maximum values within windows of a numeric channel's data,
+/// or None for channels that aren't numeric or have no data
+pub fn channel_envelope(
+    channel: &Channel<File>,
+    num_windows: usize,
+) -> rstdms::Result<Option<Vec<(f64, f64)>>> {
+    if channel.is_empty() {
+        return Ok(None);
+    }
+    macro_rules! read_envelope {
+        ($t:ty) => {
+            channel
+                .read_min_max::<$t>(num_windows)?
+                .into_iter()
+                .map(|(min, max)| (min as f64, max as f64))
+                .collect()
+        };
+    }
+    let envelope = match channel.data_type().native_type() {
+        Some(NativeTypeId::I8) => read_envelope!(i8),
+        Some(NativeTypeId::I16) => read_envelope!(i16),
+        Some(NativeTypeId::I32) => read_envelope!(i32),
+        Some(NativeTypeId::I64) => read_envelope!(i64),
+        Some(NativeTypeId::U8) => read_envelope!(u8),
+        Some(NativeTypeId::U16) => read_envelope!(u16),
+        Some(NativeTypeId::U32) => read_envelope!(u32),
+        Some(NativeTypeId::U64) => read_envelope!(u64),
+        Some(NativeTypeId::F32) => read_envelope!(f32),
+        Some(NativeTypeId::F64) => read_envelope!(f64),
+        _ => return Ok(None),
+    };
+    Ok(Some(envelope))
+}
+
+/// Render an envelope as lines of text with a column per window, filling the range between
+/// each window's minimum and maximum. Windows containing non-finite values are left empty.
+/// Returns None if there are no finite values to plot.
+fn render_plot(envelope: &[(f64, f64)], len: u64, height: usize) -> Option<Vec<String>> {
+    let finite_windows = || {
+        envelope
+            .iter()
+            .enumerate()
+            .filter(|(_, (min, max))| min.is_finite() && max.is_finite())
+    };
+    let (y_min, y_max) = finite_windows().fold(None, |bounds, (_, &(min, max))| match bounds {
+        None => Some((min, max)),
+        Some((y_min, y_max)) => Some((min.min(y_min), max.max(y_max))),
+    })?;
+    let row = |value: f64| {
+        if y_max > y_min {
+            ((y_max - value) / (y_max - y_min) * (height - 1) as f64).round() as usize
+        } else {
+            (height - 1) / 2
+        }
+    };
+    let mut grid = vec![vec![' '; envelope.len()]; height];
+    for (column, &(min, max)) in finite_windows() {
+        for line in grid.iter_mut().take(row(min) + 1).skip(row(max)) {
+            line[column] = '*';
+        }
+    }
+
+    let top_label = format!("{:.3}", y_max);
+    let bottom_label = format!("{:.3}", y_min);
+    let label_width = top_label.len().max(bottom_label.len());
+    let mut lines = Vec::with_capacity(height + 2);
+    for (i, line) in grid.into_iter().enumerate() {
+        let label = match i {
+            0 => top_label.as_str(),
+            i if i == height - 1 => bottom_label.as_str(),
+            _ => "",
+        };
+        let line = line.into_iter().collect::<String>();
+        lines.push(format!("{:>width$} |{}", label, line, width = label_width));
+    }
+    lines.push(format!(
+        "{:>width$} +{}",
+        "",
+        "-".repeat(envelope.len()),
+        width = label_width
+    ));
+    let len_label = len.to_string();
+    let padding = envelope.len().saturating_sub(len_label.len() + 1);
+    lines.push(format!(
+        "{:>width$}  0{}{}",
+        "",
+        " ".repeat(padding),
+        len_label,
+        width = label_width
+    ));
+    Some(lines)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn plot_rendering() {
+        let envelope = [(0.0, 0.0), (0.0, 1.0), (f64::NAN, f64::NAN), (2.0, 2.0)];
+        let lines = render_plot(&envelope, 8, 3).unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                "2.000 |   *",
+                "      | *  ",
+                "0.000 |**  ",
+                "      +----",
+                "       0  8",
+            ]
+        );
+        assert!(render_plot(&[(f64::NAN, f64::NAN)], 1, 3).is_none());
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{create_file, open_file};
+use clap::Args;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+/// Writes the undecoded raw data of a channel to a file, concatenated across segments
+#[derive(Args)]
+pub struct ExtractRawArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+
+    /// Name of the group containing the channel
+    group: String,
+
+    /// Name of the channel to extract
+    channel: String,
+
+    /// Path of the file to write raw data to
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+}
+
+pub fn run(args: &ExtractRawArgs) -> Result<(), String> {
+    let tdms_file = open_file(&args.path)?;
+    let group = match tdms_file.group(&args.group) {
+        Some(group) => group,
+        None => return Err(format!("Group {} not found", args.group)),
+    };
+    let channel = match group.channel(&args.channel) {
+        Some(channel) => channel,
+        None => return Err(format!("Channel {} not found", args.channel)),
+    };
+    let output_file = create_file(&args.output, "raw data")?;
+    let bytes_written = match channel.write_raw_data(BufWriter::new(output_file)) {
+        Ok(bytes_written) => bytes_written,
+        Err(err) => {
+            return Err(format!(
+                "Error writing raw data file {}: {}",
+                args.output.display(),
+                err
+            ))
+        }
+    };
+
+    let chunks = channel.chunks();
+    let big_endian_segments = chunks.iter().filter(|chunk| chunk.big_endian).count();
+    let byte_order = if big_endian_segments == 0 {
+        "little endian".to_owned()
+    } else if big_endian_segments == chunks.len() {
+        "big endian".to_owned()
+    } else {
+        format!(
+            "mixed ({} of {} segments are big endian)",
+            big_endian_segments,
+            chunks.len()
+        )
+    };
+    println!("Data type: {:?}", channel.data_type());
+    println!("Values: {}", channel.len());
+    println!("Bytes: {}", bytes_written);
+    println!("Byte order: {}", byte_order);
+    Ok(())
+}
@@ -0,0 +1,571 @@
+use crate::{
+    create_file, glob_match, group_file_path, open_file, parse_timestamp, FilterArgs, ObjectFilter,
+};
+use clap::{Args, ValueEnum};
+use rstdms::{
+    property_names, write_index, Group, TdmsFile, TdmsProperty, TdmsValue, TdmsWriter, Timestamp,
+    WriteObject,
+};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Concatenates the data of several TDMS files into one file
+#[derive(Args)]
+pub struct MergeArgs {
+    /// Path of the TDMS file to write
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+
+    /// Paths to the TDMS files to merge, in order
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+}
+
+pub fn merge(args: &MergeArgs) -> Result<(), String> {
+    let files = open_files_to_merge(&args.paths)?;
+    let output_file = create_file(&args.output, "TDMS")?;
+    let filter_args = FilterArgs::default();
+    let filter = ObjectFilter::new(&filter_args);
+    let write_error = |err| format!("Error writing TDMS file {}: {}", args.output.display(), err);
+    let mut writer = TdmsWriter::new(BufWriter::new(output_file));
+    for tdms_file in &files {
+        writer
+            .write_segment(&[WriteObject::root(tdms_file.properties().clone())])
+            .map_err(write_error)?;
+        // Write a segment per group so only one group's data is held in memory at once
+        for group in tdms_file.groups() {
+            writer
+                .write_segment(&group_objects(&group, &filter).map_err(write_error)?)
+                .map_err(write_error)?;
+        }
+    }
+    writer.into_inner().map_err(write_error)?;
+    Ok(())
+}
+
+/// Writes each group to a separate TDMS file, named FILE_GROUP.tdms
+#[derive(Args)]
+pub struct SplitArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+
+    /// Directory to write TDMS files to. Defaults to the current directory
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    output_dir: PathBuf,
+}
+
+pub fn split(args: &SplitArgs) -> Result<(), String> {
+    let tdms_file = open_file(&args.path)?;
+    let filter = ObjectFilter::new(&args.filter);
+    for group in tdms_file
+        .groups()
+        .filter(|group| filter.matches_group(group.name()))
+    {
+        let objects = group_objects(&group, &filter)
+            .map_err(|err| format!("Error reading TDMS file {}: {}", args.path.display(), err))?;
+        // Skip groups where no channels were selected
+        if filter.filters_channels() && objects.len() == 1 {
+            continue;
+        }
+        let output_path = group_file_path(&args.output_dir, &args.path, group.name(), "tdms");
+        let output_file = create_file(&output_path, "TDMS")?;
+        let write_error =
+            |err| format!("Error writing TDMS file {}: {}", output_path.display(), err);
+        let mut writer = TdmsWriter::new(BufWriter::new(output_file));
+        writer
+            .write_segment(&[WriteObject::root(tdms_file.properties().clone())])
+            .map_err(write_error)?;
+        writer.write_segment(&objects).map_err(write_error)?;
+        writer.into_inner().map_err(write_error)?;
+    }
+    Ok(())
+}
+
+/// Copies a TDMS file with properties removed
+#[derive(Args)]
+pub struct StripPropertiesArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+
+    /// Path of the TDMS file to write
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+
+    /// Remove properties with names matching a glob pattern, can be repeated
+    #[arg(long = "property", value_name = "PATTERN", required = true)]
+    properties: Vec<String>,
+}
+
+pub fn strip_properties(args: &StripPropertiesArgs) -> Result<(), String> {
+    let tdms_file = open_file(&args.path)?;
+    let output_file = create_file(&args.output, "TDMS")?;
+    let result = tdms_file.rewrite(BufWriter::new(output_file), |_, properties| {
+        properties.retain(|property| {
+            !args
+                .properties
+                .iter()
+                .any(|pattern| glob_match(pattern, &property.name))
+        })
+    });
+    match result {
+        Ok(_) => Ok(()),
+        Err(err) => Err(format!(
+            "Error writing TDMS file {}: {}",
+            args.output.display(),
+            err
+        )),
+    }
+}
+
+/// Copies a TDMS file with a group or channel renamed
+#[derive(Args)]
+pub struct RenameArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+
+    /// Name of the group to rename, or that contains the channel to rename
+    group: String,
+
+    /// Name of the channel to rename
+    channel: Option<String>,
+
+    /// New name of the group or channel
+    #[arg(long, value_name = "NAME")]
+    to: String,
+
+    /// Path of the TDMS file to write
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+}
+
+pub fn rename(args: &RenameArgs) -> Result<(), String> {
+    let tdms_file = open_file(&args.path)?;
+    let group = match tdms_file.group(&args.group) {
+        Some(group) => group,
+        None => return Err(format!("Group {} not found", args.group)),
+    };
+    // Renaming to an existing name would merge the objects
+    match &args.channel {
+        None if args.to != args.group && tdms_file.group(&args.to).is_some() => {
+            return Err(format!("Group {} already exists", args.to));
+        }
+        Some(channel_name) if group.channel(channel_name).is_none() => {
+            return Err(format!("Channel {} not found", channel_name));
+        }
+        Some(channel_name) if args.to != *channel_name && group.channel(&args.to).is_some() => {
+            return Err(format!("Channel {} already exists", args.to));
+        }
+        _ => {}
+    }
+
+    let output_file = create_file(&args.output, "TDMS")?;
+    let rename_group = |group_name: &str| match &args.channel {
+        None if group_name == args.group => args.to.clone(),
+        _ => group_name.to_owned(),
+    };
+    let rename_channel = |group_name: &str, channel_name: &str| match &args.channel {
+        Some(channel) if group_name == args.group && channel_name == channel => args.to.clone(),
+        _ => channel_name.to_owned(),
+    };
+    match tdms_file.rewrite_renamed(BufWriter::new(output_file), rename_group, rename_channel) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(format!(
+            "Error writing TDMS file {}: {}",
+            args.output.display(),
+            err
+        )),
+    }
+}
+
+/// Sets a property by appending a segment containing only metadata to a TDMS file,
+/// without rewriting the file. The .tdms_index file is also updated if present.
+#[derive(Args)]
+pub struct SetPropertyArgs {
+    /// Path to the TDMS file to modify
+    path: PathBuf,
+
+    /// Name of the property
+    name: String,
+
+    /// Type of the property value
+    #[arg(value_enum, value_name = "TYPE")]
+    value_type: PropertyType,
+
+    /// Value of the property. Timestamps are given in RFC 3339 format
+    value: String,
+
+    /// Name of the group to set the property on. Defaults to the file's root object
+    #[arg(long, value_name = "NAME")]
+    group: Option<String>,
+
+    /// Name of the channel within the group to set the property on
+    #[arg(long, value_name = "NAME", requires = "group")]
+    channel: Option<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PropertyType {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    String,
+    Timestamp,
+}
+
+pub fn set_property(args: &SetPropertyArgs) -> Result<(), String> {
+    let value = match parse_property_value(args.value_type, &args.value) {
+        Some(value) => value,
+        None => {
+            return Err(format!(
+                "Invalid {} value '{}'",
+                args.value_type.to_possible_value().unwrap().get_name(),
+                args.value
+            ))
+        }
+    };
+    let properties = vec![TdmsProperty {
+        name: args.name.clone(),
+        value,
+    }];
+
+    // Check the object exists so that a typo doesn't create a new object
+    let tdms_file = open_file(&args.path)?;
+    let object = match (&args.group, &args.channel) {
+        (None, _) => WriteObject::root(properties),
+        (Some(group_name), channel_name) => {
+            let group = match tdms_file.group(group_name) {
+                Some(group) => group,
+                None => return Err(format!("Group {} not found", group_name)),
+            };
+            match channel_name {
+                None => WriteObject::group(group_name, properties),
+                Some(channel_name) if group.channel(channel_name).is_some() => {
+                    WriteObject::channel(group_name, channel_name, properties, None)
+                }
+                Some(channel_name) => return Err(format!("Channel {} not found", channel_name)),
+            }
+        }
+    };
+    drop(tdms_file);
+
+    let mut writer = TdmsWriter::new(Vec::new());
+    let segment = writer
+        .write_properties(&[object])
+        .and_then(|_| writer.into_inner())
+        .map_err(|err| format!("Error writing segment: {}", err))?;
+    append_to_file(&args.path, &segment, "TDMS")?;
+
+    // Index files contain each segment's lead in and metadata, and this segment has no data
+    let mut index_path = args.path.clone().into_os_string();
+    index_path.push("_index");
+    let index_path = PathBuf::from(index_path);
+    if index_path.exists() {
+        let mut index_segment = b"TDSh".to_vec();
+        index_segment.extend_from_slice(&segment[4..]);
+        append_to_file(&index_path, &index_segment, "index")?;
+    }
+    Ok(())
+}
+
+/// Parse a property value given on the command line
+fn parse_property_value(value_type: PropertyType, value: &str) -> Option<TdmsValue> {
+    Some(match value_type {
+        PropertyType::I8 => TdmsValue::Int8(value.parse().ok()?),
+        PropertyType::I16 => TdmsValue::Int16(value.parse().ok()?),
+        PropertyType::I32 => TdmsValue::Int32(value.parse().ok()?),
+        PropertyType::I64 => TdmsValue::Int64(value.parse().ok()?),
+        PropertyType::U8 => TdmsValue::Uint8(value.parse().ok()?),
+        PropertyType::U16 => TdmsValue::Uint16(value.parse().ok()?),
+        PropertyType::U32 => TdmsValue::Uint32(value.parse().ok()?),
+        PropertyType::U64 => TdmsValue::Uint64(value.parse().ok()?),
+        PropertyType::F32 => TdmsValue::Float32(value.parse().ok()?),
+        PropertyType::F64 => TdmsValue::Float64(value.parse().ok()?),
+        PropertyType::String => TdmsValue::String(value.to_owned()),
+        PropertyType::Timestamp => TdmsValue::Timestamp(parse_timestamp(value)?),
+    })
+}
+
+fn append_to_file(path: &Path, bytes: &[u8], description: &str) -> Result<(), String> {
+    let result = OpenOptions::new()
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(bytes));
+    match result {
+        Ok(()) => Ok(()),
+        Err(err) => Err(format!(
+            "Error writing {} file {}: {}",
+            description,
+            path.display(),
+            err
+        )),
+    }
+}
+
+/// Writes the .tdms_index file for TDMS files that do not have one
+#[derive(Args)]
+pub struct IndexArgs {
+    /// Paths to the TDMS files to index
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// Overwrite existing index files
+    #[arg(long)]
+    force: bool,
+}
+
+pub fn index(args: &IndexArgs) -> Result<(), String> {
+    for path in &args.paths {
+        let mut index_path = path.clone().into_os_string();
+        index_path.push("_index");
+        let index_path = PathBuf::from(index_path);
+        if !args.force && index_path.exists() {
+            continue;
+        }
+        let mut file = match File::open(path) {
+            Ok(file) => file,
+            Err(err) => {
+                return Err(format!("Error opening path {}: {}", path.display(), err));
+            }
+        };
+        let index_file = create_file(&index_path, "index")?;
+        if let Err(err) = write_index(&mut file, BufWriter::new(index_file)) {
+            return Err(format!(
+                "Error writing index file {}: {}",
+                index_path.display(),
+                err
+            ));
+        }
+        println!("{}", index_path.display());
+    }
+    Ok(())
+}
+
+/// Extracts the data within a time range from one or more TDMS files into a new file,
+/// using the wf_start_time and wf_increment properties of channels. Data from each file
+/// is appended in order, and each channel's wf_start_time is set to the time of the first
+/// value extracted. If an acquisition was restarted part way through a channel,
+/// times are computed from its first start time.
+#[derive(Args)]
+pub struct TrimArgs {
+    /// Paths to the TDMS files to read, in order
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+
+    /// Path of the TDMS file to write
+    #[arg(short, long, value_name = "FILE")]
+    output: PathBuf,
+
+    /// Start of the time range, as an RFC 3339 timestamp or a number of seconds
+    /// after the earliest start time of the selected channels
+    #[arg(long, value_name = "TIME")]
+    start: String,
+
+    /// End of the time range, which is excluded, in the same formats as the start
+    #[arg(long, value_name = "TIME")]
+    end: String,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+}
+
+pub fn trim(args: &TrimArgs) -> Result<(), String> {
+    let files = open_files_to_merge(&args.paths)?;
+    let filter = ObjectFilter::new(&args.filter);
+
+    let mut earliest_start: Option<Timestamp> = None;
+    for tdms_file in &files {
+        for group in tdms_file
+            .groups()
+            .filter(|group| filter.matches_group(group.name()))
+        {
+            for channel in
+                group.channels_where(|channel| filter.matches_channel(group.name(), channel.name()))
+            {
+                let timing = match channel.waveform_timing() {
+                    Ok(timing) => timing,
+                    Err(err) => {
+                        return Err(format!(
+                            "Cannot trim channel {}: {}. Use --group or --channel to exclude it",
+                            channel.path(),
+                            err
+                        ))
+                    }
+                };
+                if earliest_start.is_none_or(|earliest| timing.start_time < earliest) {
+                    earliest_start = Some(timing.start_time);
+                }
+            }
+        }
+    }
+    let earliest_start = match earliest_start {
+        Some(earliest_start) => earliest_start,
+        None => return Err("No channels to trim".to_owned()),
+    };
+    let start = parse_time(&args.start, earliest_start)?;
+    let end = parse_time(&args.end, earliest_start)?;
+    if end <= start {
+        return Err("End time must be after the start time".to_owned());
+    }
+
+    let output_file = create_file(&args.output, "TDMS")?;
+    let write_error = |err| format!("Error writing TDMS file {}: {}", args.output.display(), err);
+    let mut writer = TdmsWriter::new(BufWriter::new(output_file));
+    let mut written_channels = HashSet::new();
+    for tdms_file in &files {
+        writer
+            .write_segment(&[WriteObject::root(tdms_file.properties().clone())])
+            .map_err(write_error)?;
+        for group in tdms_file
+            .groups()
+            .filter(|group| filter.matches_group(group.name()))
+        {
+            let mut objects = vec![WriteObject::group(group.name(), group.properties().clone())];
+            for channel in
+                group.channels_where(|channel| filter.matches_channel(group.name(), channel.name()))
+            {
+                let mut properties = channel.properties().clone();
+                // Timings were checked above
+                let timing = channel.waveform_timing().unwrap();
+                let first_index = timing.index_at(&start);
+                let end_index = timing.index_at(&end);
+                let data = if end_index > first_index {
+                    let data = channel
+                        .read_data_slice(first_index, (end_index - first_index) as usize)
+                        .map_err(|err| {
+                            format!("Error reading channel {}: {}", channel.path(), err)
+                        })?;
+                    for property in properties.iter_mut() {
+                        if property.name == property_names::WF_START_TIME {
+                            property.value = TdmsValue::Timestamp(timing.time_at(first_index));
+                        }
+                    }
+                    Some(data)
+                } else if written_channels.contains(&channel.path()) {
+                    // Don't overwrite the start time of data from an earlier file
+                    continue;
+                } else {
+                    None
+                };
+                written_channels.insert(channel.path());
+                objects.push(WriteObject::channel(
+                    group.name(),
+                    channel.name(),
+                    properties,
+                    data,
+                ));
+            }
+            writer.write_segment(&objects).map_err(write_error)?;
+        }
+    }
+    writer.into_inner().map_err(write_error)?;
+    Ok(())
+}
+
+/// Parse a time given as an RFC 3339 timestamp or a number of seconds after a reference time
+fn parse_time(value: &str, reference: Timestamp) -> Result<Timestamp, String> {
+    if let Some(timestamp) = parse_timestamp(value) {
+        return Ok(timestamp);
+    }
+    let time = value
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite())
+        .and_then(|seconds| {
+            let nanos = (seconds * 1e9).round() as i64;
+            reference.to_unix_nanos()?.checked_add(nanos)
+        })
+        .map(Timestamp::from_unix_nanos);
+    match time {
+        Some(time) => Ok(time),
+        None => Err(format!(
+            "Invalid time '{}', expected an RFC 3339 timestamp or a number of seconds",
+            value
+        )),
+    }
+}
+
+/// Open TDMS files to merge, checking that channels that appear in multiple files are
+/// compatible so they can be concatenated
+fn open_files_to_merge(paths: &[PathBuf]) -> Result<Vec<TdmsFile<File>>, String> {
+    let files = paths
+        .iter()
+        .map(|path| open_file(path))
+        .collect::<Result<Vec<_>, String>>()?;
+    for (i, tdms_file) in files.iter().enumerate() {
+        for group in tdms_file.groups() {
+            for channel in group.channels() {
+                for earlier_file in &files[..i] {
+                    let earlier_group = match earlier_file.group(group.name()) {
+                        Some(earlier_group) => earlier_group,
+                        None => continue,
+                    };
+                    if let Some(earlier_channel) = earlier_group.channel(channel.name()) {
+                        if let Err(err) = earlier_channel.check_compatible(&channel) {
+                            return Err(format!("Cannot merge files: {}", err));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Get objects to write for a group and its channels selected by the filter,
+/// including channel data
+fn group_objects(group: &Group<File>, filter: &ObjectFilter) -> rstdms::Result<Vec<WriteObject>> {
+    let mut objects = vec![WriteObject::group(group.name(), group.properties().clone())];
+    for channel in group
+        .channels()
+        .filter(|channel| filter.matches_channel(group.name(), channel.name()))
+    {
+        let data = if channel.is_empty() {
+            None
+        } else {
+            Some(channel.read_data()?)
+        };
+        objects.push(WriteObject::channel(
+            group.name(),
+            channel.name(),
+            channel.properties().clone(),
+            data,
+        ));
+    }
+    Ok(objects)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn time_parsing() {
+        let reference = Timestamp::from_unix_nanos(1_000_000_000);
+        assert_eq!(
+            parse_time("1970-01-01T00:00:02.5Z", reference),
+            Ok(Timestamp::from_unix_nanos(2_500_000_000))
+        );
+        assert_eq!(
+            parse_time("1.25", reference),
+            Ok(Timestamp::from_unix_nanos(2_250_000_000))
+        );
+        assert_eq!(
+            parse_time("-1", reference),
+            Ok(Timestamp::from_unix_nanos(0))
+        );
+        assert!(parse_time("NaN", reference).is_err());
+        assert!(parse_time("soon", reference).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::yaml::{yaml_float, yaml_string};
+use crate::{open_file, FilterArgs, FormatArgs, ObjectFilter, OutputFormat};
+use clap::Args;
+use rstdms::{Channel, NativeTypeId};
+use std::fs::File;
+use std::path::PathBuf;
+
+/// Displays statistics of numeric channel data
+#[derive(Args)]
+pub struct StatsArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+
+    #[command(flatten)]
+    format: FormatArgs,
+}
+
+pub fn run(args: &StatsArgs) -> Result<(), String> {
+    let tdms_file = open_file(&args.path)?;
+    let filter = ObjectFilter::new(&args.filter);
+
+    for group in tdms_file
+        .groups()
+        .filter(|group| filter.matches_group(group.name()))
+    {
+        let channels = group.channels_where(|channel| {
+            channel.data_type().is_numeric()
+                && channel.data_type().native_type().is_some()
+                && filter.matches_channel(group.name(), channel.name())
+        });
+        for channel in channels {
+            let stats = match channel_stats(&channel) {
+                Ok(stats) => stats,
+                Err(err) => {
+                    return Err(format!("Error reading channel {}: {}", channel.path(), err))
+                }
+            };
+            match args.format.format {
+                OutputFormat::Text => {
+                    println!("{} / {}", group.name(), channel.name());
+                    println!("    count: {}", stats.count);
+                    println!("    nan count: {}", stats.nan_count);
+                    if stats.count > 0 {
+                        println!("    min: {}", stats.min);
+                        println!("    max: {}", stats.max);
+                        println!("    mean: {}", stats.mean);
+                        println!("    std: {}", stats.std());
+                    }
+                }
+                OutputFormat::Yaml => {
+                    println!("- group: {}", yaml_string(group.name()));
+                    println!("  channel: {}", yaml_string(channel.name()));
+                    println!("  count: {}", stats.count);
+                    println!("  nan_count: {}", stats.nan_count);
+                    if stats.count > 0 {
+                        println!("  min: {}", yaml_float(stats.min));
+                        println!("  max: {}", yaml_float(stats.max));
+                        println!("  mean: {}", yaml_float(stats.mean));
+                        println!("  std: {}", yaml_float(stats.std()));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Running statistics of channel values, computed without holding all values in memory
+struct RunningStats {
+    /// Number of values excluding NaNs
+    count: u64,
+    nan_count: u64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    /// Sum of squared differences from the mean
+    m2: f64,
+}
+
+impl RunningStats {
+    fn new() -> RunningStats {
+        RunningStats {
+            count: 0,
+            nan_count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            mean: 0.0,
+            m2: 0.0,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        if value.is_nan() {
+            self.nan_count += 1;
+            return;
+        }
+        self.count += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        // Welford's algorithm for a numerically stable variance
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Population standard deviation
+    fn std(&self) -> f64 {
+        (self.m2 / self.count as f64).sqrt()
+    }
+}
+
+fn channel_stats(channel: &Channel<File>) -> rstdms::Result<RunningStats> {
+    let mut stats = RunningStats::new();
+    macro_rules! push_values {
+        ($t:ty) => {
+            for value in channel.iter_values::<$t>()? {
+                stats.push(value? as f64);
+            }
+        };
+    }
+    match channel.data_type().native_type() {
+        Some(NativeTypeId::I8) => push_values!(i8),
+        Some(NativeTypeId::I16) => push_values!(i16),
+        Some(NativeTypeId::I32) => push_values!(i32),
+        Some(NativeTypeId::I64) => push_values!(i64),
+        Some(NativeTypeId::U8) => push_values!(u8),
+        Some(NativeTypeId::U16) => push_values!(u16),
+        Some(NativeTypeId::U32) => push_values!(u32),
+        Some(NativeTypeId::U64) => push_values!(u64),
+        Some(NativeTypeId::F32) => push_values!(f32),
+        Some(NativeTypeId::F64) => push_values!(f64),
+        _ => {}
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn running_stats() {
+        let mut stats = RunningStats::new();
+        for value in [1.0, f64::NAN, 2.0, 3.0, 4.0] {
+            stats.push(value);
+        }
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.nan_count, 1);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 4.0);
+        assert_eq!(stats.mean, 2.5);
+        assert!((stats.std() - 1.25_f64.sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::open_file;
+use crate::plot::channel_envelope;
+use clap::Args;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::{
+    Axis, Block, Borders, Chart, Dataset, GraphType, List, ListState, Paragraph,
+};
+use ratatui::{DefaultTerminal, Frame};
+use rstdms::{TdmsFile, TdmsProperty};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+
+/// Number of windows the data envelope of a channel is computed over
+const ENVELOPE_WINDOWS: usize = 200;
+
+/// Height of the data envelope chart, including its border
+const CHART_HEIGHT: u16 = 14;
+
+/// An object listed in the browser
+enum Item {
+    Root,
+    Group(String),
+    Channel(String, String),
+}
+
+/// Minimum and maximum values within windows of a channel's data
+type Envelope = Result<Option<Vec<(f64, f64)>>, String>;
+
+struct Browser<'f> {
+    tdms_file: &'f TdmsFile<File>,
+    items: Vec<Item>,
+    list_state: ListState,
+    /// Envelopes of channels that have been selected, by item index
+    envelopes: HashMap<usize, Envelope>,
+}
+
+/// Browses groups, channels, properties and data in an interactive terminal UI
+#[derive(Args)]
+pub struct BrowseArgs {
+    /// Path to the TDMS file to read
+    path: PathBuf,
+}
+
+pub fn run(args: &BrowseArgs) -> Result<(), String> {
+    browse(&open_file(&args.path)?)
+}
+
+/// Run an interactive terminal browser of the groups, channels and properties in a file
+pub fn browse(tdms_file: &TdmsFile<File>) -> Result<(), String> {
+    let mut browser = Browser::new(tdms_file);
+    let mut terminal = match ratatui::try_init() {
+        Ok(terminal) => terminal,
+        Err(err) => return Err(format!("Error initializing terminal: {}", err)),
+    };
+    let result = browser.run(&mut terminal);
+    ratatui::restore();
+    result.map_err(|err| format!("Error updating terminal: {}", err))
+}
+
+impl<'f> Browser<'f> {
+    fn new(tdms_file: &'f TdmsFile<File>) -> Browser<'f> {
+        let mut items = vec![Item::Root];
+        for group in tdms_file.groups() {
+            items.push(Item::Group(group.name().to_owned()));
+            for channel in group.channels() {
+                items.push(Item::Channel(
+                    group.name().to_owned(),
+                    channel.name().to_owned(),
+                ));
+            }
+        }
+        Browser {
+            tdms_file,
+            items,
+            list_state: ListState::default().with_selected(Some(0)),
+            envelopes: HashMap::new(),
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Down | KeyCode::Char('j') => self.list_state.select_next(),
+                    KeyCode::Up | KeyCode::Char('k') => self.list_state.select_previous(),
+                    KeyCode::Home | KeyCode::Char('g') => self.list_state.select_first(),
+                    KeyCode::End | KeyCode::Char('G') => self.list_state.select_last(),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [list_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(frame.area());
+
+        let labels = self.items.iter().map(|item| match item {
+            Item::Root => "/".to_owned(),
+            Item::Group(group_name) => group_name.clone(),
+            Item::Channel(_, channel_name) => format!("  {}", channel_name),
+        });
+        let list = List::new(labels)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Objects (q to quit) "),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list_state);
+
+        // The selection is clamped to the number of items when the list is rendered
+        let selected = self.list_state.selected().unwrap_or(0);
+        let tdms_file = self.tdms_file;
+        match &self.items[selected] {
+            Item::Root => draw_properties(frame, details_area, "/", tdms_file.properties()),
+            Item::Group(group_name) => {
+                if let Some(group) = tdms_file.group(group_name) {
+                    draw_properties(frame, details_area, group_name, group.properties());
+                }
+            }
+            Item::Channel(group_name, channel_name) => {
+                let group = match tdms_file.group(group_name) {
+                    Some(group) => group,
+                    None => return,
+                };
+                let channel = match group.channel(channel_name) {
+                    Some(channel) => channel,
+                    None => return,
+                };
+                let [properties_area, chart_area] =
+                    Layout::vertical([Constraint::Min(0), Constraint::Length(CHART_HEIGHT)])
+                        .areas(details_area);
+                let title = format!(
+                    "{} / {} ({:?}, {} values)",
+                    group_name,
+                    channel_name,
+                    channel.data_type(),
+                    channel.len()
+                );
+                draw_properties(frame, properties_area, &title, channel.properties());
+                let envelope = self.envelopes.entry(selected).or_insert_with(|| {
+                    channel_envelope(&channel, ENVELOPE_WINDOWS).map_err(|err| err.to_string())
+                });
+                draw_envelope(frame, chart_area, channel.len(), envelope);
+            }
+        }
+    }
+}
+
+fn draw_properties(frame: &mut Frame, area: Rect, title: &str, properties: &[TdmsProperty]) {
+    let lines: Vec<Line> = properties
+        .iter()
+        .map(|property| {
+            Line::from(format!(
+                "{} ({:?}): {}",
+                property.name,
+                property.value.data_type(),
+                property.value
+            ))
+        })
+        .collect();
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", title)),
+    );
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_envelope(frame: &mut Frame, area: Rect, len: u64, envelope: &Envelope) {
+    let block = Block::default().borders(Borders::ALL).title(" Data ");
+    let windows = match envelope {
+        Ok(Some(windows)) if !windows.is_empty() => windows,
+        Ok(_) => {
+            frame.render_widget(Paragraph::new("No numeric data").block(block), area);
+            return;
+        }
+        Err(err) => {
+            frame.render_widget(Paragraph::new(format!("Error: {}", err)).block(block), area);
+            return;
+        }
+    };
+
+    // Plot against the index of the first value in each window
+    let window_size = len as f64 / windows.len() as f64;
+    let mins: Vec<(f64, f64)> = windows
+        .iter()
+        .enumerate()
+        .map(|(i, (min, _))| (i as f64 * window_size, *min))
+        .collect();
+    let maxs: Vec<(f64, f64)> = windows
+        .iter()
+        .enumerate()
+        .map(|(i, (_, max))| (i as f64 * window_size, *max))
+        .collect();
+    let finite_values = windows
+        .iter()
+        .flat_map(|(min, max)| [*min, *max])
+        .filter(|value| value.is_finite());
+    let (y_min, y_max) = finite_values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+        (lo.min(v), hi.max(v))
+    });
+    if y_min > y_max {
+        frame.render_widget(Paragraph::new("No finite values").block(block), area);
+        return;
+    }
+
+    let datasets = vec![
+        Dataset::default()
+            .name("max")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .data(&maxs),
+        Dataset::default()
+            .name("min")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .data(&mins),
+    ];
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, len as f64])
+                .labels(["0".to_owned(), len.to_string()]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([y_min, y_max])
+                .labels([format!("{:.3}", y_min), format!("{:.3}", y_max)]),
+        );
+    frame.render_widget(chart, area);
+}
@@ -0,0 +1,58 @@
+pub fn yaml_float(value: f64) -> String {
+    if value.is_nan() {
+        ".nan".to_owned()
+    } else if value.is_infinite() {
+        if value > 0.0 { ".inf" } else { "-.inf" }.to_owned()
+    } else {
+        // Debug formatting always includes a decimal point or exponent
+        format!("{:?}", value)
+    }
+}
+
+/// Format a string as a YAML scalar, quoting it unless it can't be mistaken for another type
+pub fn yaml_string(value: &str) -> String {
+    let is_plain = value.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && !value.ends_with(' ')
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || " _-./()".contains(c))
+        && !["true", "false", "yes", "no", "on", "off", "null"]
+            .contains(&value.to_lowercase().as_str());
+    if is_plain {
+        return value.to_owned();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn yaml_strings() {
+        assert_eq!(yaml_string("Channel 1"), "Channel 1");
+        assert_eq!(yaml_string("unit_string"), "unit_string");
+        assert_eq!(yaml_string("1.5"), "\"1.5\"");
+        assert_eq!(yaml_string("No"), "\"No\"");
+        assert_eq!(yaml_string(""), "\"\"");
+        assert_eq!(yaml_string("a: b"), "\"a: b\"");
+        assert_eq!(yaml_string("say \"hi\"\n"), "\"say \\\"hi\\\"\\n\"");
+        assert_eq!(yaml_float(1.0), "1.0");
+        assert_eq!(yaml_float(f64::NAN), ".nan");
+        assert_eq!(yaml_float(f64::NEG_INFINITY), "-.inf");
+    }
+}
@@ -0,0 +1,119 @@
+use crate::error::{Result, TdmsReadError};
+use crate::segment::DataChunk;
+use crate::timestamp::Timestamp;
+use crate::types::{NativeTypeId, TdsType};
+use std::io::{Read, Seek};
+
+/// Data for a channel, stored as a vector of the channel's native type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChannelData {
+    I8(Vec<i8>),
+    I16(Vec<i16>),
+    I32(Vec<i32>),
+    I64(Vec<i64>),
+    U8(Vec<u8>),
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+    U64(Vec<u64>),
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+    String(Vec<String>),
+    Timestamp(Vec<Timestamp>),
+}
+
+impl ChannelData {
+    /// Create channel data with the given length, filled with default values.
+    /// Channels without any data have the Void data type, and are represented
+    /// by empty F64 data.
+    pub fn with_len(data_type: TdsType, len: usize) -> Result<ChannelData> {
+        match data_type.native_type() {
+            None if data_type == TdsType::Void && len == 0 => Ok(ChannelData::F64(Vec::new())),
+            Some(NativeTypeId::I8) => Ok(ChannelData::I8(vec![0; len])),
+            Some(NativeTypeId::I16) => Ok(ChannelData::I16(vec![0; len])),
+            Some(NativeTypeId::I32) => Ok(ChannelData::I32(vec![0; len])),
+            Some(NativeTypeId::I64) => Ok(ChannelData::I64(vec![0; len])),
+            Some(NativeTypeId::U8) => Ok(ChannelData::U8(vec![0; len])),
+            Some(NativeTypeId::U16) => Ok(ChannelData::U16(vec![0; len])),
+            Some(NativeTypeId::U32) => Ok(ChannelData::U32(vec![0; len])),
+            Some(NativeTypeId::U64) => Ok(ChannelData::U64(vec![0; len])),
+            Some(NativeTypeId::F32) => Ok(ChannelData::F32(vec![0.0; len])),
+            Some(NativeTypeId::F64) => Ok(ChannelData::F64(vec![0.0; len])),
+            Some(NativeTypeId::String) => Ok(ChannelData::String(vec![String::new(); len])),
+            Some(NativeTypeId::Timestamp) => {
+                Ok(ChannelData::Timestamp(vec![Timestamp::new(0, 0); len]))
+            }
+            None => Err(TdmsReadError::UnsupportedDataType(data_type)),
+        }
+    }
+
+    /// Get the number of values
+    pub fn len(&self) -> usize {
+        match self {
+            ChannelData::I8(values) => values.len(),
+            ChannelData::I16(values) => values.len(),
+            ChannelData::I32(values) => values.len(),
+            ChannelData::I64(values) => values.len(),
+            ChannelData::U8(values) => values.len(),
+            ChannelData::U16(values) => values.len(),
+            ChannelData::U32(values) => values.len(),
+            ChannelData::U64(values) => values.len(),
+            ChannelData::F32(values) => values.len(),
+            ChannelData::F64(values) => values.len(),
+            ChannelData::String(values) => values.len(),
+            ChannelData::Timestamp(values) => values.len(),
+        }
+    }
+
+    /// Whether there are no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read the values in a chunk directly from a reader into this data starting at
+    /// the given offset, returning the number of values read
+    pub(crate) fn read_chunk<R: Read + Seek>(
+        &mut self,
+        chunk: &DataChunk,
+        reader: &mut R,
+        offset: usize,
+    ) -> Result<usize> {
+        match self {
+            ChannelData::I8(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::I16(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::I32(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::I64(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::U8(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::U16(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::U32(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::U64(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::F32(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::F64(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::String(values) => chunk.read_values(reader, &mut values[offset..]),
+            ChannelData::Timestamp(values) => chunk.read_values(reader, &mut values[offset..]),
+        }
+    }
+
+    /// Decode the values in a chunk into this data starting at the given offset,
+    /// returning the number of values decoded
+    pub(crate) fn decode_chunk(
+        &mut self,
+        chunk: &DataChunk,
+        bytes: &[u8],
+        offset: usize,
+    ) -> Result<usize> {
+        match self {
+            ChannelData::I8(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::I16(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::I32(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::I64(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::U8(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::U16(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::U32(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::U64(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::F32(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::F64(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::String(values) => chunk.decode_values(bytes, &mut values[offset..]),
+            ChannelData::Timestamp(values) => chunk.decode_values(bytes, &mut values[offset..]),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::error::{Result, TdmsReadError};
+use crate::types::NativeType;
+use crate::Channel;
+use std::io::{Read, Seek};
+
+/// Read the data for two matching channels from different files back to back into one
+/// buffer, for example when a long acquisition has been split across multiple files.
+///
+/// The channels must have the same path, data type and dimension. Each file is read using
+/// its own segment layout and byte order, so these may differ between the files.
+/// The buffer must be at least as long as the combined length of both channels.
+pub fn read_concatenated<T, R1, R2>(
+    first: &Channel<'_, R1>,
+    second: &Channel<'_, R2>,
+    buffer: &mut [T],
+) -> Result<()>
+where
+    T: NativeType,
+    R1: Read + Seek,
+    R2: Read + Seek,
+{
+    first.check_compatible(second)?;
+    let first_len = first.len();
+    let required = first_len + second.len();
+    if required > buffer.len() as u64 {
+        return Err(TdmsReadError::BufferSizeMismatch {
+            required,
+            actual: buffer.len(),
+        });
+    }
+    let (first_buffer, second_buffer) = buffer.split_at_mut(first_len as usize);
+    first.read_all_data(first_buffer)?;
+    second.read_all_data(second_buffer)
+}
@@ -0,0 +1,118 @@
+use crate::channel_data::ChannelData;
+use crate::error::Result;
+use crate::object_path::ObjectPathId;
+use crate::Group;
+use polars::prelude::{Column, DataFrame, DataType, NamedFrom, Series, TimeUnit};
+#[cfg(feature = "parquet")]
+use polars::prelude::{KeyValueMetadata, ParquetWriter};
+#[cfg(feature = "parquet")]
+use std::io::Write;
+use std::io::{Read, Seek};
+
+impl<'a, R: Read + Seek> Group<'a, R> {
+    /// Read all channels in this group into a polars DataFrame with one column per channel.
+    /// Channels without data are not included, and channels shorter than the longest
+    /// channel are padded with nulls. If `time_column` is true, a first "Time" column is
+    /// added with the time in seconds of each row relative to the start of the first
+    /// channel, computed from its `wf_increment` property.
+    pub fn to_polars(&self, time_column: bool) -> Result<DataFrame> {
+        let tdms_reader = &self.file.tdms_reader;
+        let channel_ids: Vec<ObjectPathId> = tdms_reader
+            .channel_ids(self.name())
+            .into_iter()
+            .filter(|&channel_id| tdms_reader.get_channel_data_index(channel_id).is_some())
+            .collect();
+        let channel_data = {
+            let mut file_reader = self.file.file_reader.borrow_mut();
+            if self.file.unbuffered_data_reads {
+                tdms_reader.read_channels_data(file_reader.get_mut(), &channel_ids)?
+            } else {
+                tdms_reader.read_channels_data(&mut *file_reader, &channel_ids)?
+            }
+        };
+        let rows = channel_data
+            .iter()
+            .map(|data| data.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut columns = Vec::with_capacity(channel_ids.len() + 1);
+        if let (true, Some(&first_id)) = (time_column, channel_ids.first()) {
+            let increment = tdms_reader.waveform_increment(first_id)?;
+            let times: Vec<f64> = (0..rows).map(|i| i as f64 * increment).collect();
+            columns.push(Column::from(Series::new("Time".into(), times)));
+        }
+        for (channel_id, data) in channel_ids.into_iter().zip(channel_data) {
+            let name = tdms_reader.channel_name(channel_id);
+            columns.push(Column::from(to_series(name, data, rows)?));
+        }
+        Ok(DataFrame::new(columns)?)
+    }
+
+    /// Write all channels in this group to Parquet, with the same columns as `to_polars`.
+    /// Properties of the root object, this group and its channels are stored as key-value
+    /// metadata with keys of the form "{object path}:{property name}",
+    /// eg. "/'Group'/'Channel1':unit_string".
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write>(&self, writer: W, time_column: bool) -> Result<()> {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut object_ids = Vec::new();
+        object_ids.extend(tdms_reader.get_object_id("/"));
+        object_ids.push(self.object_id);
+        object_ids.extend(tdms_reader.channel_ids(self.name()));
+        let mut metadata = Vec::new();
+        for object_id in object_ids {
+            let path = tdms_reader.object_path_string(object_id);
+            for property in tdms_reader.get_properties(object_id) {
+                metadata.push((
+                    format!("{}:{}", path, property.name),
+                    property.value.to_string(),
+                ));
+            }
+        }
+
+        let mut df = self.to_polars(time_column)?;
+        ParquetWriter::new(writer)
+            .with_key_value_metadata(Some(KeyValueMetadata::from_static(metadata)))
+            .finish(&mut df)?;
+        Ok(())
+    }
+}
+
+fn to_series(name: &str, data: ChannelData, rows: usize) -> Result<Series> {
+    let name = name.into();
+    let series = match data {
+        ChannelData::I8(values) => Series::new(name, padded(values, rows)),
+        ChannelData::I16(values) => Series::new(name, padded(values, rows)),
+        ChannelData::I32(values) => Series::new(name, padded(values, rows)),
+        ChannelData::I64(values) => Series::new(name, padded(values, rows)),
+        ChannelData::U8(values) => Series::new(name, padded(values, rows)),
+        ChannelData::U16(values) => Series::new(name, padded(values, rows)),
+        ChannelData::U32(values) => Series::new(name, padded(values, rows)),
+        ChannelData::U64(values) => Series::new(name, padded(values, rows)),
+        ChannelData::F32(values) => Series::new(name, padded(values, rows)),
+        ChannelData::F64(values) => Series::new(name, padded(values, rows)),
+        ChannelData::String(values) => Series::new(name, padded(values, rows)),
+        ChannelData::Timestamp(values) => {
+            // Timestamps outside the range of nanoseconds since the Unix epoch are null
+            let mut nanoseconds: Vec<Option<i64>> = values
+                .iter()
+                .map(|timestamp| {
+                    timestamp
+                        .to_datetime()
+                        .and_then(|datetime| datetime.timestamp_nanos_opt())
+                })
+                .collect();
+            nanoseconds.resize(rows, None);
+            Series::new(name, nanoseconds).cast(&DataType::Datetime(TimeUnit::Nanoseconds, None))?
+        }
+    };
+    Ok(series)
+}
+
+/// Convert values to options, padded with `None` up to the given number of rows
+fn padded<T>(values: Vec<T>, rows: usize) -> Vec<Option<T>> {
+    let mut padded: Vec<Option<T>> = values.into_iter().map(Some).collect();
+    padded.resize_with(rows, || None);
+    padded
+}
@@ -0,0 +1,71 @@
+use crate::error::{Result, TdmsReadError};
+use crate::types::{NativeType, TdsType};
+use crate::Channel;
+use std::io::{Read, Seek};
+
+impl<'a, R: Read + Seek> Channel<'a, R> {
+    /// Number of digital lines in each sample of a digital port channel,
+    /// which is the number of bits in the channel's unsigned integer data type
+    pub fn digital_line_count(&self) -> Result<u32> {
+        match self.data_type() {
+            TdsType::U8 => Ok(8),
+            TdsType::U16 => Ok(16),
+            TdsType::U32 => Ok(32),
+            data_type => Err(TdmsReadError::UnsupportedDataType(data_type)),
+        }
+    }
+
+    /// Read a digital port channel storing packed U8, U16 or U32 samples, expanding
+    /// the samples into a vector of boolean values for each line.
+    /// Line 0 corresponds to the least significant bit.
+    pub fn read_digital_lines(&'a self) -> Result<Vec<Vec<bool>>> {
+        let line_count = self.digital_line_count()?;
+        let mut lines = vec![Vec::with_capacity(self.len() as usize); line_count as usize];
+        self.read_port_samples(|sample| {
+            for (line_index, line) in lines.iter_mut().enumerate() {
+                line.push(sample & (1 << line_index) != 0);
+            }
+        })?;
+        Ok(lines)
+    }
+
+    /// Read the values of a single line from a digital port channel storing packed
+    /// U8, U16 or U32 samples. Line 0 corresponds to the least significant bit.
+    pub fn read_digital_line(&'a self, line_index: u32) -> Result<Vec<bool>> {
+        let line_count = self.digital_line_count()?;
+        if line_index >= line_count {
+            return Err(TdmsReadError::RangeOutOfBounds {
+                start: line_index as u64,
+                end: line_index as u64 + 1,
+                length: line_count as u64,
+            });
+        }
+        let mut line = Vec::with_capacity(self.len() as usize);
+        self.read_port_samples(|sample| line.push(sample & (1 << line_index) != 0))?;
+        Ok(line)
+    }
+
+    fn read_port_samples<F: FnMut(u32)>(&'a self, f: F) -> Result<()> {
+        match self.data_type() {
+            TdsType::U8 => self.read_samples_as_u32::<u8, F>(f),
+            TdsType::U16 => self.read_samples_as_u32::<u16, F>(f),
+            TdsType::U32 => self.read_samples_as_u32::<u32, F>(f),
+            data_type => Err(TdmsReadError::UnsupportedDataType(data_type)),
+        }
+    }
+
+    fn read_samples_as_u32<T, F>(&'a self, mut f: F) -> Result<()>
+    where
+        T: NativeType + Default + Clone + Copy + Into<u32>,
+        F: FnMut(u32),
+    {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        let f = |samples: &[T]| samples.iter().for_each(|&sample| f(sample.into()));
+        if self.file.unbuffered_data_reads {
+            tdms_reader.read_channel_data_blocks(file_reader.get_mut(), self.object_id, f)
+        } else {
+            tdms_reader.read_channel_data_blocks(&mut *file_reader, self.object_id, f)
+        }
+    }
+}
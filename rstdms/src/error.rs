@@ -1,43 +1,221 @@
+use crate::types::{NativeTypeId, TdsType};
+use thiserror::Error;
+
 /// All possible errors that may be returned when reading a TDMS file
-#[derive(Debug)]
+#[derive(Debug, Error)]
 pub enum TdmsReadError {
-    /// Invalid data format
-    TdmsError(String),
+    /// A segment does not start with the expected "TDSm" tag, or "TDSh" in an index file
+    #[error("Invalid segment header at position {position}: {header:?}")]
+    InvalidSegmentHeader { position: u64, header: [u8; 4] },
+    /// An offset in a segment lead in points outside the file or before the segment
+    #[error("Invalid offset {offset} in lead in of segment at position {position}")]
+    InvalidSegmentOffset { position: u64, offset: u64 },
+    /// The raw data described by the segment metadata does not fit in the segment
+    #[error("Raw data size {data_size} of segment at position {position} is larger than the segment data size {segment_data_size}")]
+    SegmentDataSizeMismatch {
+        position: u64,
+        data_size: u64,
+        segment_data_size: u64,
+    },
+    /// A count of items in the metadata is larger than could fit in the metadata
+    #[error("Number of {item_name} ({count}) in segment at position {position} is too large for the metadata size")]
+    MetadataCountTooLarge {
+        item_name: &'static str,
+        count: u32,
+        position: u64,
+    },
+    /// An object path could not be parsed
+    #[error("Invalid object path {0}")]
+    InvalidObjectPath(String),
+    /// A data type id is not a known TDMS data type
+    #[error("Invalid type id: {0}")]
+    InvalidTypeId(u32),
+    /// Data of this type cannot be read
+    #[error("Unsupported data type {0:?}")]
+    UnsupportedDataType(TdsType),
+    /// A property has a type that cannot be read
+    #[error("Unsupported property type {0:?}")]
+    UnknownPropertyType(TdsType),
+    /// A raw data index has a dimension of zero
+    #[error("Dimension must be at least 1, got {0}")]
+    UnsupportedDimension(u32),
+    /// The number of values in a raw data index is too large for the data size
+    #[error("Number of values {number_of_values} is too large for the size of {data_type:?} data")]
+    TooManyValues {
+        number_of_values: u64,
+        data_type: TdsType,
+    },
+    /// The end offset of a string in string data is before the end of the previous string
+    #[error("String end offset {offset} is less than the previous end offset {previous}")]
+    InvalidStringOffset { offset: u32, previous: u32 },
+    /// A raw data index refers to the previous index of an object that has none
+    #[error("Object has no previous raw data index")]
+    NoPreviousDataIndex,
+    /// The data type of a channel changed between segments
+    #[error("Data type {data_type:?} does not match existing data type {existing_data_type:?}")]
+    DataIndexMismatch {
+        data_type: TdsType,
+        existing_data_type: TdsType,
+    },
+    /// The dimension of a channel changed between segments
+    #[error("Dimension {dimension} does not match existing dimension {existing_dimension}")]
+    DimensionMismatch {
+        dimension: u32,
+        existing_dimension: u32,
+    },
+    /// Channels in an interleaved segment have different numbers of values
+    #[error("Different data lengths in interleaved data segment. Expected length {expected} but got {actual}")]
+    InterleavedLengthMismatch { expected: u64, actual: u64 },
+    /// A buffer is too small to hold all channel data
+    #[error(
+        "Buffer length needs to be at least {required}, received a buffer with length {actual}"
+    )]
+    BufferSizeMismatch { required: u64, actual: usize },
+    /// A buffer has a different item type to the channel data type
+    #[error("Cannot read channel {channel_path} with data type {data_type:?} into a buffer with item type {requested:?}, expected a buffer with item type {expected:?}")]
+    BufferTypeMismatch {
+        channel_path: String,
+        data_type: TdsType,
+        requested: NativeTypeId,
+        expected: NativeTypeId,
+    },
+    /// Channels to be read together have different paths, data types or dimensions
+    #[error("Channel {path} with data type {data_type:?} is not compatible with channel {other_path} with data type {other_data_type:?}")]
+    IncompatibleChannels {
+        path: String,
+        data_type: TdsType,
+        other_path: String,
+        other_data_type: TdsType,
+    },
+    /// Channels to be read together have different lengths
+    #[error("Channel {path} has length {length} but expected length {expected}")]
+    ChannelLengthMismatch {
+        path: String,
+        length: u64,
+        expected: u64,
+    },
+    /// A range of values extends beyond the end of a channel
+    #[error("Range {start}..{end} is out of bounds for channel with length {length}")]
+    RangeOutOfBounds { start: u64, end: u64, length: u64 },
+    /// The stride of a decimated read is zero
+    #[error("Stride must be at least 1, got {0}")]
+    InvalidStride(usize),
+    /// A property required for an operation is missing or has the wrong type
+    #[error("Object {path} does not have a valid {name} property")]
+    MissingProperty { path: String, name: &'static str },
+    /// A channel's unit cannot be converted to the requested kind of quantity
+    #[cfg(feature = "uom")]
+    #[error("Unit '{unit}' of channel {path} cannot be converted to {quantity}")]
+    IncompatibleUnit {
+        path: String,
+        unit: String,
+        quantity: &'static str,
+    },
+    /// The number of buffers provided does not match the number of channels to read
+    #[error("Number of buffers ({buffers}) does not match the number of channels ({channels})")]
+    BufferCountMismatch { buffers: usize, channels: usize },
+    /// Reading data would require more memory than the configured limit
+    #[error("Reading data requires {required} bytes of memory, which exceeds the limit of {limit} bytes")]
+    MemoryLimitExceeded { required: u64, limit: u64 },
     /// An IO error reading the underlying file
-    IoError(std::io::Error),
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
     /// An error decoding UTF-8 strings
-    Utf8Error(std::string::FromUtf8Error),
+    #[error("UTF-8 decode error: {0}")]
+    Utf8Error(#[from] std::string::FromUtf8Error),
+    /// An error creating a polars DataFrame
+    #[cfg(feature = "polars")]
+    #[error("Polars error: {0}")]
+    PolarsError(#[from] polars::prelude::PolarsError),
+    /// An error writing a zip archive
+    #[cfg(feature = "npz")]
+    #[error("Zip error: {0}")]
+    ZipError(#[from] zip::result::ZipError),
+    /// An error with information about where in the file it occurred
+    #[error("{source} ({context})")]
+    WithContext {
+        context: ErrorContext,
+        source: Box<TdmsReadError>,
+    },
+}
+
+/// Describes where in a TDMS file an error occurred
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Index of the segment being read
+    pub segment_index: Option<usize>,
+    /// Byte offset of the start of the segment within the file
+    pub segment_position: Option<u64>,
+    /// Path of the object being read
+    pub object_path: Option<String>,
 }
 
-impl std::error::Error for TdmsReadError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match *self {
-            TdmsReadError::TdmsError(_) => None,
-            TdmsReadError::IoError(ref e) => Some(e),
-            TdmsReadError::Utf8Error(ref e) => Some(e),
+impl TdmsReadError {
+    /// Get the context describing where the error occurred, if known
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            TdmsReadError::WithContext { context, .. } => Some(context),
+            _ => None,
         }
     }
-}
 
-impl std::fmt::Display for TdmsReadError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match *self {
-            TdmsReadError::TdmsError(ref s) => write!(f, "{}", s),
-            TdmsReadError::IoError(ref e) => write!(f, "IO error: {}", e),
-            TdmsReadError::Utf8Error(ref e) => write!(f, "UTF-8 decode error: {}", e),
+    /// Get the underlying error without any context
+    pub fn without_context(&self) -> &TdmsReadError {
+        match self {
+            TdmsReadError::WithContext { source, .. } => source,
+            _ => self,
         }
     }
-}
 
-impl From<std::io::Error> for TdmsReadError {
-    fn from(err: std::io::Error) -> TdmsReadError {
-        TdmsReadError::IoError(err)
+    /// Add the segment being read to the error context
+    pub(crate) fn in_segment(self, segment_index: usize, segment_position: u64) -> TdmsReadError {
+        self.update_context(|context| {
+            context.segment_index = Some(segment_index);
+            context.segment_position = Some(segment_position);
+        })
+    }
+
+    /// Add the path of the object being read to the error context
+    pub(crate) fn in_object(self, object_path: String) -> TdmsReadError {
+        self.update_context(|context| {
+            context.object_path.get_or_insert(object_path);
+        })
+    }
+
+    fn update_context<F: FnOnce(&mut ErrorContext)>(self, update: F) -> TdmsReadError {
+        match self {
+            TdmsReadError::WithContext {
+                mut context,
+                source,
+            } => {
+                update(&mut context);
+                TdmsReadError::WithContext { context, source }
+            }
+            error => {
+                let mut context = ErrorContext::default();
+                update(&mut context);
+                TdmsReadError::WithContext {
+                    context,
+                    source: Box::new(error),
+                }
+            }
+        }
     }
 }
 
-impl From<std::string::FromUtf8Error> for TdmsReadError {
-    fn from(err: std::string::FromUtf8Error) -> TdmsReadError {
-        TdmsReadError::Utf8Error(err)
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(segment_index) = self.segment_index {
+            parts.push(format!("segment {}", segment_index));
+        }
+        if let Some(segment_position) = self.segment_position {
+            parts.push(format!("segment offset {}", segment_position));
+        }
+        if let Some(ref object_path) = self.object_path {
+            parts.push(format!("object {}", object_path));
+        }
+        write!(f, "{}", parts.join(", "))
     }
 }
 
@@ -0,0 +1,427 @@
+use crate::channel_data::ChannelData;
+use crate::error::Result;
+use crate::object_path::ObjectPath;
+use crate::types::TdsType;
+use crate::value_iter::ChannelValues;
+use crate::Channel;
+use indexmap::IndexMap;
+use std::io::{Read, Seek, Write};
+
+/// Number of rows of channel data read at a time when exporting
+const EXPORT_BLOCK_ROWS: u64 = 64 * 1024;
+
+/// Reads the data of a channel one block of rows at a time, with blocks read in order.
+/// String values can't be located within a chunk without decoding the whole chunk,
+/// so string channels are read sequentially so that each chunk is only decoded once.
+enum ColumnReader<'c, 'a, R: Read + Seek> {
+    Range(&'c Channel<'a, R>),
+    Strings(ChannelValues<'a, R, String>),
+}
+
+impl<'c, 'a, R: Read + Seek> ColumnReader<'c, 'a, R> {
+    fn new(channel: &'c Channel<'a, R>) -> Result<ColumnReader<'c, 'a, R>> {
+        Ok(match channel.data_type() {
+            TdsType::String => ColumnReader::Strings(channel.iter_values()?),
+            _ => ColumnReader::Range(channel),
+        })
+    }
+
+    /// Read the values for the block of rows starting at `start`,
+    /// which may be fewer than `rows` values if the channel ends within the block
+    fn read_block(&mut self, start: u64, rows: u64) -> Result<ChannelData> {
+        match self {
+            ColumnReader::Range(channel) => {
+                let available = channel.len().saturating_sub(start).min(rows) as usize;
+                channel
+                    .file
+                    .read_channel_data_range(channel.object_id, start, available)
+            }
+            ColumnReader::Strings(values) => Ok(ChannelData::String(
+                values.by_ref().take(rows as usize).collect::<Result<_>>()?,
+            )),
+        }
+    }
+}
+
+/// Options that control how channel data is written as CSV
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    delimiter: char,
+    time_column: bool,
+    units_header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> CsvOptions {
+        CsvOptions {
+            delimiter: ',',
+            time_column: false,
+            units_header: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Create options with the default settings
+    pub fn new() -> CsvOptions {
+        CsvOptions::default()
+    }
+
+    /// Set the character used to separate values. The default is a comma.
+    pub fn delimiter(mut self, delimiter: char) -> CsvOptions {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Write a first column with the time in seconds of each row relative to the start
+    /// of the first channel, computed from its `wf_increment` property
+    pub fn time_column(mut self, time_column: bool) -> CsvOptions {
+        self.time_column = time_column;
+        self
+    }
+
+    /// Write a second header row with the `unit_string` property of each channel
+    pub fn units_header(mut self, units_header: bool) -> CsvOptions {
+        self.units_header = units_header;
+        self
+    }
+}
+
+/// Write the data of one or more channels to CSV, with one column per channel.
+/// Data is read in blocks so that channels larger than memory can be exported.
+/// Where channels have different lengths, the missing values of shorter channels are empty.
+pub fn write_csv<R: Read + Seek, W: Write>(
+    channels: &[Channel<'_, R>],
+    mut writer: W,
+    options: &CsvOptions,
+) -> Result<()> {
+    let increment = match channels.first() {
+        Some(channel) if options.time_column => Some(
+            channel
+                .file
+                .tdms_reader
+                .waveform_increment(channel.object_id)?,
+        ),
+        _ => None,
+    };
+
+    let mut header = Vec::new();
+    if increment.is_some() {
+        header.push("Time".to_owned());
+    }
+    header.extend(channels.iter().map(|channel| channel.name().to_owned()));
+    write_row(&mut writer, &header, options.delimiter)?;
+
+    if options.units_header {
+        let mut units = Vec::new();
+        if increment.is_some() {
+            units.push("s".to_owned());
+        }
+        units.extend(
+            channels
+                .iter()
+                .map(|channel| channel.unit_string().unwrap_or_default().to_owned()),
+        );
+        write_row(&mut writer, &units, options.delimiter)?;
+    }
+
+    let num_rows = channels
+        .iter()
+        .map(|channel| channel.file.tdms_reader.channel_len(channel.object_id))
+        .max()
+        .unwrap_or(0);
+    let mut readers = channels
+        .iter()
+        .map(ColumnReader::new)
+        .collect::<Result<Vec<_>>>()?;
+    let mut start = 0;
+    while start < num_rows {
+        let rows = (num_rows - start).min(EXPORT_BLOCK_ROWS);
+        let columns = readers
+            .iter_mut()
+            .map(|reader| reader.read_block(start, rows))
+            .collect::<Result<Vec<_>>>()?;
+        let mut row = Vec::with_capacity(header.len());
+        for row_index in 0..rows as usize {
+            row.clear();
+            if let Some(increment) = increment {
+                row.push(((start + row_index as u64) as f64 * increment).to_string());
+            }
+            row.extend(
+                columns
+                    .iter()
+                    .map(|column| format_value(column, row_index).unwrap_or_default()),
+            );
+            write_row(&mut writer, &row, options.delimiter)?;
+        }
+        start += rows;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write the data of one or more channels as InfluxDB line protocol, with a measurement per
+/// group and a field per channel. Within each group, timestamps are taken from the first
+/// channel with timestamp data, or if there is none, computed from the `wf_start_time` and
+/// `wf_increment` properties of the first channel.
+/// Values that are missing or can't be represented, such as NaNs, are omitted,
+/// and rows without any values or with a time outside the range of 64 bit nanoseconds
+/// since the Unix epoch are skipped. Channels without any data are ignored.
+pub fn write_line_protocol<R: Read + Seek, W: Write>(
+    channels: &[Channel<'_, R>],
+    mut writer: W,
+) -> Result<()> {
+    let mut groups: IndexMap<&str, Vec<&Channel<'_, R>>> = IndexMap::new();
+    for channel in channels.iter().filter(|channel| !channel.is_empty()) {
+        let tdms_reader = &channel.file.tdms_reader;
+        if let Some(ObjectPath::Channel(group_name, _)) =
+            tdms_reader.get_object_path(channel.object_id)
+        {
+            groups.entry(group_name).or_default().push(channel);
+        }
+    }
+
+    for (group_name, mut channels) in groups {
+        let measurement = escape_line_protocol(group_name, &[',', ' ']);
+        let time_channel = channels
+            .iter()
+            .position(|channel| channel.data_type() == TdsType::TimeStamp)
+            .map(|index| channels.remove(index));
+        let timing = match (&time_channel, channels.first()) {
+            (None, Some(channel)) => Some(channel.waveform_timing()?),
+            _ => None,
+        };
+        let field_keys = channels
+            .iter()
+            .map(|channel| escape_line_protocol(channel.name(), &[',', '=', ' ']))
+            .collect::<Vec<_>>();
+        let num_rows = match &time_channel {
+            Some(time_channel) => time_channel.len(),
+            None => channels
+                .iter()
+                .map(|channel| channel.len())
+                .max()
+                .unwrap_or(0),
+        };
+
+        let mut readers = channels
+            .iter()
+            .map(|&channel| ColumnReader::new(channel))
+            .collect::<Result<Vec<_>>>()?;
+        let mut start = 0;
+        while start < num_rows {
+            let rows = (num_rows - start).min(EXPORT_BLOCK_ROWS);
+            let times: Vec<Option<i64>> = match (&time_channel, &timing) {
+                (Some(time_channel), _) => {
+                    match time_channel.file.read_channel_data_range(
+                        time_channel.object_id,
+                        start,
+                        rows as usize,
+                    )? {
+                        ChannelData::Timestamp(values) => {
+                            values.iter().map(|time| time.to_unix_nanos()).collect()
+                        }
+                        _ => unreachable!("Time channel has timestamp data"),
+                    }
+                }
+                (None, Some(timing)) => (start..start + rows)
+                    .map(|index| {
+                        timing
+                            .start_time
+                            .add_seconds(index as f64 * timing.increment)
+                            .to_unix_nanos()
+                    })
+                    .collect(),
+                (None, None) => unreachable!("Rows without channels to export"),
+            };
+            let columns = readers
+                .iter_mut()
+                .map(|reader| reader.read_block(start, rows))
+                .collect::<Result<Vec<_>>>()?;
+            let mut fields = Vec::with_capacity(columns.len());
+            for (row_index, time) in times.into_iter().enumerate() {
+                let time = match time {
+                    Some(time) => time,
+                    None => continue,
+                };
+                fields.clear();
+                for (key, column) in field_keys.iter().zip(&columns) {
+                    if let Some(value) = format_field_value(column, row_index) {
+                        fields.push(format!("{}={}", key, value));
+                    }
+                }
+                if !fields.is_empty() {
+                    writeln!(writer, "{} {} {}", measurement, fields.join(","), time)?;
+                }
+            }
+            start += rows;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write the data of one or more channels as newline-delimited JSON, with a record per value
+/// containing the channel path, the index of the value, its time and the value, eg.
+/// `{"channel":"/'Group'/'Channel'","index":0,"time":"2015-09-09T13:19:55+00:00","value":1.5}`.
+/// Times are computed from the `wf_start_time` and `wf_increment` properties, and are null
+/// for channels without these. Values that can't be represented in JSON, such as NaNs,
+/// are null. Channels are written one after another and data is read in blocks,
+/// so channels larger than memory can be exported.
+pub fn write_ndjson<R: Read + Seek, W: Write>(
+    channels: &[Channel<'_, R>],
+    mut writer: W,
+) -> Result<()> {
+    for channel in channels {
+        let path = json_string(&channel.path());
+        // Channels without waveform properties don't have times
+        let timing = channel.waveform_timing().ok();
+        let len = channel.len();
+        let mut reader = ColumnReader::new(channel)?;
+        let mut start = 0;
+        while start < len {
+            let rows = (len - start).min(EXPORT_BLOCK_ROWS);
+            let data = reader.read_block(start, rows)?;
+            for row_index in 0..rows as usize {
+                let index = start + row_index as u64;
+                let time = timing
+                    .and_then(|timing| timing.time_at(index).to_datetime())
+                    .map(|datetime| json_string(&datetime.to_rfc3339()));
+                let value = format_json_value(&data, row_index);
+                writeln!(
+                    writer,
+                    "{{\"channel\":{},\"index\":{},\"time\":{},\"value\":{}}}",
+                    path,
+                    index,
+                    time.as_deref().unwrap_or("null"),
+                    value.as_deref().unwrap_or("null")
+                )?;
+            }
+            start += rows;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Format a value as JSON, or None if it is missing or not finite
+fn format_json_value(data: &ChannelData, index: usize) -> Option<String> {
+    match data {
+        ChannelData::F32(values) => values
+            .get(index)
+            .filter(|v| v.is_finite())
+            .map(|v| v.to_string()),
+        ChannelData::F64(values) => values
+            .get(index)
+            .filter(|v| v.is_finite())
+            .map(|v| v.to_string()),
+        ChannelData::String(values) => values.get(index).map(|v| json_string(v)),
+        ChannelData::Timestamp(values) => values
+            .get(index)
+            .and_then(|v| v.to_datetime())
+            .map(|datetime| json_string(&datetime.to_rfc3339())),
+        // Integers are formatted the same as in CSV
+        data => format_value(data, index),
+    }
+}
+
+/// Quote a string for JSON, escaping quotes, backslashes and control characters
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Format a value as a line protocol field value, or None if it is missing or not finite
+fn format_field_value(data: &ChannelData, index: usize) -> Option<String> {
+    let integer = |value: i64| Some(format!("{}i", value));
+    match data {
+        ChannelData::I8(values) => values.get(index).and_then(|&v| integer(v.into())),
+        ChannelData::I16(values) => values.get(index).and_then(|&v| integer(v.into())),
+        ChannelData::I32(values) => values.get(index).and_then(|&v| integer(v.into())),
+        ChannelData::I64(values) => values.get(index).and_then(|&v| integer(v)),
+        ChannelData::U8(values) => values.get(index).and_then(|&v| integer(v.into())),
+        ChannelData::U16(values) => values.get(index).and_then(|&v| integer(v.into())),
+        ChannelData::U32(values) => values.get(index).and_then(|&v| integer(v.into())),
+        // Unsigned fields aren't supported by InfluxDB 1.x, so are only used when required
+        ChannelData::U64(values) => values.get(index).map(|&v| match i64::try_from(v) {
+            Ok(v) => format!("{}i", v),
+            Err(_) => format!("{}u", v),
+        }),
+        ChannelData::F32(values) => values
+            .get(index)
+            .filter(|v| v.is_finite())
+            .map(|v| v.to_string()),
+        ChannelData::F64(values) => values
+            .get(index)
+            .filter(|v| v.is_finite())
+            .map(|v| v.to_string()),
+        ChannelData::String(values) => values
+            .get(index)
+            .map(|v| format!("\"{}\"", v.replace('\\', "\\\\").replace('"', "\\\""))),
+        ChannelData::Timestamp(values) => values
+            .get(index)
+            .and_then(|v| v.to_datetime())
+            .map(|datetime| format!("\"{}\"", datetime.to_rfc3339())),
+    }
+}
+
+/// Escape special characters in a measurement name or field key with backslashes
+fn escape_line_protocol(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn format_value(data: &ChannelData, index: usize) -> Option<String> {
+    match data {
+        ChannelData::I8(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::I16(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::I32(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::I64(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::U8(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::U16(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::U32(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::U64(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::F32(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::F64(values) => values.get(index).map(|v| v.to_string()),
+        ChannelData::String(values) => values.get(index).cloned(),
+        ChannelData::Timestamp(values) => values.get(index).map(|v| {
+            v.to_datetime()
+                .map(|datetime| datetime.to_rfc3339())
+                .unwrap_or_default()
+        }),
+    }
+}
+
+fn write_row<W: Write>(writer: &mut W, fields: &[String], delimiter: char) -> Result<()> {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            write!(writer, "{}", delimiter)?;
+        }
+        if field.contains(delimiter) || field.contains(['"', '\n', '\r']) {
+            write!(writer, "\"{}\"", field.replace('"', "\"\""))?;
+        } else {
+            write!(writer, "{}", field)?;
+        }
+    }
+    writeln!(writer)?;
+    Ok(())
+}
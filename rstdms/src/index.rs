@@ -0,0 +1,46 @@
+use crate::error::{Result, TdmsReadError};
+use crate::tdms_reader::{read_lead_in, INDEX_SEGMENT_TAG, LEAD_IN_LENGTH};
+use std::io::{Read, Seek, SeekFrom, Write};
+
+/// Write the contents of a TDMS index file (`.tdms_index`) for a TDMS file.
+///
+/// The index contains the lead in and metadata of each segment without the raw data,
+/// allowing readers that support index files to find segments without reading the whole file.
+/// Segments are written as found, so the index of an incomplete final segment is also incomplete.
+pub fn write_index<R: Read + Seek, W: Write>(reader: &mut R, mut writer: W) -> Result<W> {
+    let file_length = reader.seek(SeekFrom::End(0))?;
+    let mut position = reader.seek(SeekFrom::Start(0))?;
+    let mut lead_in_bytes = [0u8; LEAD_IN_LENGTH];
+    let mut metadata = Vec::new();
+    while let Some(lead_in) = read_lead_in(reader, position)? {
+        let data_start = position + LEAD_IN_LENGTH as u64;
+        if data_start.saturating_add(lead_in.raw_data_offset) > file_length {
+            return Err(TdmsReadError::InvalidSegmentOffset {
+                position,
+                offset: lead_in.raw_data_offset,
+            });
+        }
+        // Re-read the lead in bytes so they can be copied with the original byte order
+        reader.seek(SeekFrom::Start(position))?;
+        reader.read_exact(&mut lead_in_bytes)?;
+        metadata.resize(lead_in.raw_data_offset as usize, 0);
+        reader.read_exact(&mut metadata)?;
+        writer.write_all(INDEX_SEGMENT_TAG)?;
+        writer.write_all(&lead_in_bytes[4..])?;
+        writer.write_all(&metadata)?;
+
+        if lead_in.is_incomplete() {
+            break;
+        }
+        let next_segment_position = data_start.saturating_add(lead_in.next_segment_offset);
+        if next_segment_position > file_length {
+            return Err(TdmsReadError::InvalidSegmentOffset {
+                position,
+                offset: lead_in.next_segment_offset,
+            });
+        }
+        position = reader.seek(SeekFrom::Start(next_segment_position))?;
+    }
+    writer.flush()?;
+    Ok(writer)
+}
@@ -41,11 +41,21 @@ impl<'a> Read for InterleavedReader<'a> {
         let total_bytes = self.type_size * self.bytes.len() / self.chunk_width;
         let num_bytes_to_read = min(buf.len(), total_bytes - self.position);
 
-        for i in 0..num_bytes_to_read {
-            let position = i + self.position;
+        // Copy a whole value at a time, apart from at the start or end of the
+        // buffer where it may not be aligned with a value boundary.
+        let mut bytes_copied = 0;
+        while bytes_copied < num_bytes_to_read {
+            let position = bytes_copied + self.position;
             let type_idx = position / self.type_size;
             let type_offset = position % self.type_size;
-            buf[i] = self.bytes[self.offset + type_idx * self.chunk_width + type_offset];
+            let copy_length = min(
+                self.type_size - type_offset,
+                num_bytes_to_read - bytes_copied,
+            );
+            let start = self.offset + type_idx * self.chunk_width + type_offset;
+            buf[bytes_copied..bytes_copied + copy_length]
+                .copy_from_slice(&self.bytes[start..start + copy_length]);
+            bytes_copied += copy_length;
         }
 
         self.position += num_bytes_to_read;
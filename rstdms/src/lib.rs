@@ -3,29 +3,96 @@ extern crate chrono;
 extern crate id_arena;
 extern crate num_enum;
 
+#[cfg(feature = "ndarray")]
+mod array;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "tokio")]
+mod async_reader;
+mod channel_data;
+mod concat;
+#[cfg(feature = "polars")]
+mod dataframe;
+mod digital;
 mod error;
+mod export;
+mod index;
 mod interleaved;
+mod metadata;
+mod metrics;
+#[cfg(feature = "npz")]
+mod npz;
 mod object_map;
 mod object_path;
+#[cfg(feature = "object_store")]
+mod object_store_reader;
+mod options;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod properties;
+pub mod property_names;
+mod quality;
+mod read_at;
 mod segment;
+mod stats;
+mod streaming;
 mod tdms_reader;
 pub mod timestamp;
 mod toc;
+mod typed_channel;
 mod types;
-
-pub use crate::error::{Result, TdmsReadError};
+#[cfg(feature = "uom")]
+mod units;
+mod value_iter;
+mod waveform;
+mod writer;
+
+#[cfg(feature = "arrow")]
+pub use crate::arrow::ArrowChunkReader;
+#[cfg(feature = "tokio")]
+pub use crate::async_reader::{AsyncChannel, AsyncGroup, TdmsFileAsync};
+pub use crate::channel_data::ChannelData;
+pub use crate::concat::read_concatenated;
+pub use crate::error::{ErrorContext, Result, TdmsReadError};
+pub use crate::export::{write_csv, write_line_protocol, write_ndjson, CsvOptions};
+pub use crate::index::write_index;
+pub use crate::metadata::{ChannelMetadata, FileMetadata, GroupMetadata};
+pub use crate::metrics::IoMetrics;
+use crate::metrics::{CountingReader, ReaderCounters};
+#[cfg(feature = "npz")]
+pub use crate::npz::write_npz;
 use crate::object_path::{path_from_channel, path_from_group, ObjectPath, ObjectPathId};
-pub use crate::properties::{TdmsProperty, TdmsValue};
-use crate::tdms_reader::{read_metadata, TdmsReader};
+#[cfg(feature = "object_store")]
+pub use crate::object_store_reader::ObjectStoreReader;
+pub use crate::options::{ReadOptions, ReadProgress};
+use crate::properties::properties_map;
+pub use crate::properties::{PropertyRevision, TdmsProperty, TdmsValue};
+pub use crate::quality::{QualityOptions, QualityReport};
+pub use crate::read_at::ReadAt;
+pub use crate::stats::{ChannelStats, FileStats, SegmentChunks, SegmentInfo};
+pub use crate::streaming::{ChannelDataChunk, TdmsStreamReader};
+use crate::tdms_reader::{read_index_metadata, read_metadata, TdmsReader};
 pub use crate::timestamp::Timestamp;
-pub use crate::types::{NativeType, TdsType};
+pub use crate::toc::{FormatInfo, TDMS_VERSION_1_0, TDMS_VERSION_2_0};
+pub use crate::typed_channel::TypedChannel;
+pub use crate::types::{NativeType, NativeTypeId, TdsType};
+#[cfg(feature = "uom")]
+pub use crate::units::{BaseUnit, Unit, UnitQuantity};
+pub use crate::value_iter::ChannelValues;
+pub use crate::waveform::{align_waveforms, WaveformAlignment, WaveformTiming};
+pub use crate::writer::{TdmsWriter, WriteObject};
+use indexmap::IndexMap;
 use std::cell::RefCell;
-use std::io::{BufReader, Read, Seek};
+use std::collections::HashMap;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::sync::Arc;
 
 pub struct TdmsFile<R: Read + Seek> {
-    file_reader: RefCell<BufReader<R>>,
+    file_reader: RefCell<BufReader<CountingReader<R>>>,
     tdms_reader: TdmsReader,
+    unbuffered_data_reads: bool,
+    reader_counters: Arc<ReaderCounters>,
 }
 
 pub struct Group<'a, R: Read + Seek> {
@@ -38,6 +105,12 @@ pub struct Channel<'a, R: Read + Seek> {
     object_id: ObjectPathId,
 }
 
+/// A group or channel object within a TDMS file
+pub enum TdmsObject<'a, R: Read + Seek> {
+    Group(Group<'a, R>),
+    Channel(Channel<'a, R>),
+}
+
 pub struct GroupIterator<'a, R: Read + Seek> {
     file: &'a TdmsFile<R>,
     object_iterator: std::vec::IntoIter<ObjectPathId>,
@@ -51,27 +124,226 @@ pub struct ChannelIterator<'a, R: Read + Seek> {
 impl<R: Read + Seek> TdmsFile<R> {
     /// Create a new TdmsFile object, parsing TDMS metadata from the reader
     pub fn new(file_reader: R) -> Result<TdmsFile<R>> {
-        let mut file_reader = BufReader::new(file_reader);
-        let tdms_reader = read_metadata(&mut file_reader)?;
+        TdmsFile::with_options(file_reader, &ReadOptions::default())
+    }
+
+    /// Create a new TdmsFile object, parsing TDMS metadata from the reader with the given options
+    pub fn with_options(file_reader: R, options: &ReadOptions) -> Result<TdmsFile<R>> {
+        TdmsFile::from_buf_reader(
+            BufReader::with_capacity(
+                options.reader_buffer_capacity(),
+                CountingReader::new(file_reader),
+            ),
+            options,
+        )
+    }
+
+    /// Create a new TdmsFile object, reading TDMS metadata from the file's index
+    /// (`.tdms_index`) rather than from the data file. The index contains the metadata of
+    /// all segments contiguously, so is much faster to read for large files.
+    /// Data is still read from the data file, and segments appended to the data file
+    /// after the index was written can be read with `refresh`.
+    pub fn with_index<I: Read + Seek>(
+        mut file_reader: R,
+        mut index_reader: I,
+        options: &ReadOptions,
+    ) -> Result<TdmsFile<R>> {
+        let file_length = file_reader.seek(SeekFrom::End(0))?;
+        let file_reader = BufReader::with_capacity(
+            options.reader_buffer_capacity(),
+            CountingReader::new(file_reader),
+        );
+        let reader_counters = file_reader.get_ref().counters();
+        let tdms_reader = read_index_metadata(
+            &mut BufReader::with_capacity(options.reader_buffer_capacity(), &mut index_reader),
+            file_length,
+            options,
+        )?;
+        Ok(TdmsFile {
+            file_reader: RefCell::new(file_reader),
+            tdms_reader,
+            unbuffered_data_reads: options.unbuffered_data_reads,
+            reader_counters,
+        })
+    }
+
+    /// Read the metadata of any segments appended to the file since it was opened or last
+    /// refreshed, for monitoring a file that is still being written.
+    /// Returns the number of new segments read. A segment that has not been completely
+    /// written yet is left to be read by a later refresh. If the file ended with an
+    /// incomplete segment when it was opened, no further segments will be found.
+    pub fn refresh(&mut self) -> Result<usize> {
+        self.tdms_reader
+            .read_appended_segments(self.file_reader.get_mut())
+    }
+
+    fn from_buf_reader(
+        mut file_reader: BufReader<CountingReader<R>>,
+        options: &ReadOptions,
+    ) -> Result<TdmsFile<R>> {
+        let reader_counters = file_reader.get_ref().counters();
+        let tdms_reader = read_metadata(&mut file_reader, options)?;
         Ok(TdmsFile {
             file_reader: RefCell::new(file_reader),
             tdms_reader,
+            unbuffered_data_reads: options.unbuffered_data_reads,
+            reader_counters,
         })
     }
 
     pub fn properties(&self) -> &Vec<TdmsProperty> {
-        if let Some(object_id) = self.tdms_reader.get_object_id("/") {
-            match self.tdms_reader.properties.get(&object_id) {
-                Some(properties) => &properties,
-                None => &EMPTY_PROPERITES,
+        self.tdms_reader.root_properties()
+    }
+
+    /// Get the value of a root object property
+    pub fn property(&self, name: &str) -> Option<&TdmsValue> {
+        match self.tdms_reader.get_object_id("/") {
+            Some(object_id) => self.tdms_reader.get_property(object_id, name),
+            None => None,
+        }
+    }
+
+    /// Get the file name from the root object name property
+    pub fn name(&self) -> Option<&str> {
+        self.property(property_names::NAME)
+            .and_then(TdmsValue::as_str)
+    }
+
+    /// Get the file description from the root object description property
+    pub fn description(&self) -> Option<&str> {
+        self.property(property_names::DESCRIPTION)
+            .and_then(TdmsValue::as_str)
+    }
+
+    /// Get the file title from the root object title property
+    pub fn title(&self) -> Option<&str> {
+        self.property(property_names::TITLE)
+            .and_then(TdmsValue::as_str)
+    }
+
+    /// Get the file author from the root object author property
+    pub fn author(&self) -> Option<&str> {
+        self.property(property_names::AUTHOR)
+            .and_then(TdmsValue::as_str)
+    }
+
+    /// Get the time the file was created from the root object datetime property
+    pub fn datetime(&self) -> Option<Timestamp> {
+        self.property(property_names::DATETIME)
+            .and_then(TdmsValue::as_timestamp)
+    }
+
+    /// Get the root object properties as a map from name to value, in definition order
+    pub fn properties_map(&self) -> IndexMap<&str, &TdmsValue> {
+        properties_map(self.properties())
+    }
+
+    /// Get every value a root object property has been set to, in segment order.
+    /// This is empty unless property history is enabled with `ReadOptions::property_history`.
+    pub fn property_history(&self, name: &str) -> Vec<PropertyRevision> {
+        match self.tdms_reader.get_object_id("/") {
+            Some(object_id) => self.tdms_reader.property_history(object_id, name),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get warnings about problems in the file that were recovered from when reading metadata
+    pub fn warnings(&self) -> &[String] {
+        self.tdms_reader.warnings()
+    }
+
+    /// Get the format version and the kinds of data layout used in the file
+    pub fn format_info(&self) -> FormatInfo {
+        self.tdms_reader.format_info()
+    }
+
+    /// Describe the position, table of contents flags and data layout of each segment
+    /// in the file, for debugging the structure of a file
+    pub fn segments(&self) -> Vec<SegmentInfo> {
+        self.tdms_reader.segments()
+    }
+
+    /// Get statistics describing how data is laid out in the file,
+    /// which can be used to decide whether the file would benefit from defragmentation
+    pub fn stats(&self) -> FileStats {
+        self.tdms_reader.stats()
+    }
+
+    /// Get counters describing how the file has been accessed so far, including reading
+    /// metadata when the file was opened, to help monitor and optimize access patterns
+    pub fn metrics(&self) -> IoMetrics {
+        IoMetrics {
+            bytes_read: self.reader_counters.bytes_read(),
+            seeks: self.reader_counters.seeks(),
+            segments_parsed: self.tdms_reader.segments_read() as u64,
+            chunks_decoded: self.tdms_reader.chunks_decoded(),
+        }
+    }
+
+    /// Get a summary of the metadata of all objects in the file,
+    /// which can be serialized when the `serde` feature is enabled
+    pub fn metadata(&self) -> FileMetadata {
+        FileMetadata::new(self)
+    }
+
+    /// Read a range of values from a channel into newly allocated data of the channel's type
+    pub(crate) fn read_channel_data_range(
+        &self,
+        channel_id: ObjectPathId,
+        start: u64,
+        len: usize,
+    ) -> Result<ChannelData> {
+        // Validate the range and memory required before allocating so an invalid length
+        // can't cause an excessive allocation. Empty reads are allowed from any position.
+        if len > 0 {
+            self.tdms_reader
+                .check_channel_range(channel_id, start, len as u64)?;
+            self.tdms_reader
+                .check_values_memory_limit(channel_id, len as u64)?;
+        }
+        let data_type = self.tdms_reader.channel_data_type(channel_id);
+        let mut data = ChannelData::with_len(data_type, len)?;
+        if len > 0 {
+            match &mut data {
+                ChannelData::I8(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::I16(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::I32(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::I64(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::U8(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::U16(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::U32(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::U64(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::F32(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::F64(values) => self.read_channel_range(channel_id, start, values)?,
+                ChannelData::String(values) => {
+                    self.read_channel_range(channel_id, start, values)?
+                }
+                ChannelData::Timestamp(values) => {
+                    self.read_channel_range(channel_id, start, values)?
+                }
             }
+        }
+        Ok(data)
+    }
+
+    fn read_channel_range<T: NativeType + Default + Clone>(
+        &self,
+        channel_id: ObjectPathId,
+        start: u64,
+        buffer: &mut [T],
+    ) -> Result<()> {
+        let mut file_reader = self.file_reader.borrow_mut();
+        if self.unbuffered_data_reads {
+            self.tdms_reader
+                .read_channel_range(file_reader.get_mut(), channel_id, start, buffer)
         } else {
-            &EMPTY_PROPERITES
+            self.tdms_reader
+                .read_channel_range(&mut *file_reader, channel_id, start, buffer)
         }
     }
 
     /// Get a group within the TDMS file
-    pub fn group<'a>(&'a self, group_name: &'a str) -> Option<Group<'a, R>> {
+    pub fn group<'a>(&'a self, group_name: &str) -> Option<Group<'a, R>> {
         let group_path = path_from_group(group_name);
         self.tdms_reader
             .get_object_id(&group_path)
@@ -82,6 +354,50 @@ impl<R: Read + Seek> TdmsFile<R> {
     pub fn groups<'a>(&'a self) -> GroupIterator<'a, R> {
         GroupIterator::new(self)
     }
+
+    /// Find all groups and channels matching a predicate, in the order they were first
+    /// defined in the file
+    pub fn find_objects<'a, F>(&'a self, mut predicate: F) -> Vec<TdmsObject<'a, R>>
+    where
+        F: FnMut(&TdmsObject<'a, R>) -> bool,
+    {
+        self.tdms_reader
+            .objects()
+            .filter_map(|(object_id, path)| match path {
+                ObjectPath::Root => None,
+                ObjectPath::Group(_) => Some(TdmsObject::Group(Group::new(self, object_id))),
+                ObjectPath::Channel(_, _) => {
+                    Some(TdmsObject::Channel(Channel::new(self, object_id)))
+                }
+            })
+            .filter(|object| predicate(object))
+            .collect()
+    }
+
+    /// Find all groups and channels with a property set to the given value,
+    /// eg. `find_by_property("unit_string", &TdmsValue::String("V".to_owned()))`
+    pub fn find_by_property<'a>(&'a self, name: &str, value: &TdmsValue) -> Vec<TdmsObject<'a, R>> {
+        self.find_objects(|object| object.property(name) == Some(value))
+    }
+}
+
+impl<B: AsRef<[u8]>> TdmsFile<Cursor<B>> {
+    /// Create a new TdmsFile from TDMS data held in memory, such as a `Vec<u8>`.
+    /// Reads are made directly from the data without any intermediate buffering.
+    pub fn from_bytes(bytes: B) -> Result<TdmsFile<Cursor<B>>> {
+        // A zero capacity buffer means reads go straight to the cursor
+        TdmsFile::from_buf_reader(
+            BufReader::with_capacity(0, CountingReader::new(Cursor::new(bytes))),
+            &ReadOptions::default(),
+        )
+    }
+}
+
+impl<'b> TdmsFile<Cursor<&'b [u8]>> {
+    /// Create a new TdmsFile that borrows TDMS data from a byte slice
+    pub fn from_slice(bytes: &'b [u8]) -> Result<TdmsFile<Cursor<&'b [u8]>>> {
+        TdmsFile::from_bytes(bytes)
+    }
 }
 
 impl<'a, R: Read + Seek> Group<'a, R> {
@@ -91,29 +407,31 @@ impl<'a, R: Read + Seek> Group<'a, R> {
 
     /// Get the name of this group
     pub fn name(&self) -> &str {
-        let group_path = self
-            .file
-            .tdms_reader
-            .get_object_path(self.object_id)
-            .unwrap();
-        match group_path {
-            ObjectPath::Group(ref group_name) => group_name,
-            _ => panic!(
-                "Expected a group path for object id {:?}, got {:?}",
-                self.object_id, group_path
-            ),
-        }
+        self.file.tdms_reader.group_name(self.object_id)
     }
 
     pub fn properties(&self) -> &Vec<TdmsProperty> {
-        match self.file.tdms_reader.properties.get(&self.object_id) {
-            Some(properties) => &properties,
-            None => &EMPTY_PROPERITES,
-        }
+        self.file.tdms_reader.get_properties(self.object_id)
+    }
+
+    /// Get the value of a property of this group
+    pub fn property(&self, name: &str) -> Option<&TdmsValue> {
+        self.file.tdms_reader.get_property(self.object_id, name)
+    }
+
+    /// Get the properties of this group as a map from name to value, in definition order
+    pub fn properties_map(&self) -> IndexMap<&str, &TdmsValue> {
+        properties_map(self.properties())
+    }
+
+    /// Get every value a property of this group has been set to, in segment order.
+    /// This is empty unless property history is enabled with `ReadOptions::property_history`.
+    pub fn property_history(&self, name: &str) -> Vec<PropertyRevision> {
+        self.file.tdms_reader.property_history(self.object_id, name)
     }
 
     /// Get a channel within this group
-    pub fn channel<'b>(&'b self, channel_name: &str) -> Option<Channel<'b, R>> {
+    pub fn channel(&self, channel_name: &str) -> Option<Channel<'a, R>> {
         let channel_path = path_from_channel(self.name(), channel_name);
         self.file
             .tdms_reader
@@ -121,10 +439,74 @@ impl<'a, R: Read + Seek> Group<'a, R> {
             .map(move |object_id| Channel::new(self.file, object_id))
     }
 
+    /// Get the number of channels within this group
+    pub fn channel_count(&self) -> usize {
+        self.file.tdms_reader.channel_count(self.name())
+    }
+
+    /// Check whether this group contains a channel with the given name
+    pub fn contains_channel(&self, channel_name: &str) -> bool {
+        let channel_path = path_from_channel(self.name(), channel_name);
+        self.file.tdms_reader.get_object_id(&channel_path).is_some()
+    }
+
+    /// Get the names of all channels within this group
+    pub fn channel_names(&self) -> Vec<&str> {
+        let tdms_reader = &self.file.tdms_reader;
+        tdms_reader
+            .channel_ids(self.name())
+            .into_iter()
+            .map(|channel_id| tdms_reader.channel_name(channel_id))
+            .collect()
+    }
+
     /// Get an iterator over channels within this group
     pub fn channels<'b>(&'b self) -> ChannelIterator<'b, R> {
         ChannelIterator::new(self.file, self.name())
     }
+
+    /// Get the channels within this group that match a predicate
+    pub fn channels_where<'b, F>(&'b self, mut predicate: F) -> Vec<Channel<'b, R>>
+    where
+        F: FnMut(&Channel<'b, R>) -> bool,
+    {
+        self.channels()
+            .filter(|channel| predicate(channel))
+            .collect()
+    }
+
+    /// Get the channels within this group with the given data type
+    pub fn channels_of_type<'b>(&'b self, data_type: TdsType) -> Vec<Channel<'b, R>> {
+        self.channels_where(|channel| channel.data_type() == data_type)
+    }
+
+    /// Get the channels within this group with integer or floating point data
+    pub fn numeric_channels<'b>(&'b self) -> Vec<Channel<'b, R>> {
+        self.channels_where(|channel| channel.data_type().is_numeric())
+    }
+
+    /// Read data for all channels in this group, returning a map from channel name to data.
+    /// This reads through the data of each segment only once, so is more efficient than
+    /// reading each channel separately. Channels without any data are not included.
+    pub fn read_all_channels(&self) -> Result<HashMap<String, ChannelData>> {
+        let tdms_reader = &self.file.tdms_reader;
+        let channel_ids: Vec<ObjectPathId> = tdms_reader
+            .channel_ids(self.name())
+            .into_iter()
+            .filter(|&channel_id| tdms_reader.get_channel_data_index(channel_id).is_some())
+            .collect();
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        let channel_data = if self.file.unbuffered_data_reads {
+            tdms_reader.read_channels_data(file_reader.get_mut(), &channel_ids)?
+        } else {
+            tdms_reader.read_channels_data(&mut *file_reader, &channel_ids)?
+        };
+        Ok(channel_ids
+            .into_iter()
+            .map(|channel_id| tdms_reader.channel_name(channel_id).to_owned())
+            .zip(channel_data)
+            .collect())
+    }
 }
 
 impl<'a, R: Read + Seek> Channel<'a, R> {
@@ -134,91 +516,333 @@ impl<'a, R: Read + Seek> Channel<'a, R> {
 
     /// Get the name of this channel
     pub fn name(&self) -> &str {
-        let channel_path = self
+        self.file.tdms_reader.channel_name(self.object_id)
+    }
+
+    pub fn properties(&self) -> &Vec<TdmsProperty> {
+        self.file.tdms_reader.get_properties(self.object_id)
+    }
+
+    /// Get the value of a property of this channel
+    pub fn property(&self, name: &str) -> Option<&TdmsValue> {
+        self.file.tdms_reader.get_property(self.object_id, name)
+    }
+
+    /// Get the unit of the channel values from the unit_string property
+    pub fn unit_string(&self) -> Option<&str> {
+        self.property(property_names::UNIT_STRING)
+            .and_then(TdmsValue::as_str)
+    }
+
+    /// Get the properties of this channel as a map from name to value, in definition order
+    pub fn properties_map(&self) -> IndexMap<&str, &TdmsValue> {
+        properties_map(self.properties())
+    }
+
+    /// Get every value a property of this channel has been set to, in segment order.
+    /// This is empty unless property history is enabled with `ReadOptions::property_history`.
+    pub fn property_history(&self, name: &str) -> Vec<PropertyRevision> {
+        self.file.tdms_reader.property_history(self.object_id, name)
+    }
+
+    pub fn data_type(&self) -> TdsType {
+        self.file.tdms_reader.channel_data_type(self.object_id)
+    }
+
+    /// Get the total number of values in this channel
+    pub fn len(&self) -> u64 {
+        self.file.tdms_reader.channel_len(self.object_id)
+    }
+
+    /// Get the full path of this channel
+    pub fn path(&self) -> String {
+        self.file.tdms_reader.object_path_string(self.object_id)
+    }
+
+    /// Check that this channel can be concatenated with a channel from another file,
+    /// which requires the channels to have the same path, data type and dimension.
+    /// Channels without any data are compatible with any channel with the same path.
+    pub fn check_compatible<S: Read + Seek>(&self, other: &Channel<'_, S>) -> Result<()> {
+        let compatible = self.path() == other.path()
+            && (self.is_empty()
+                || other.is_empty()
+                || (self.data_type() == other.data_type()
+                    && self.dimension() == other.dimension()));
+        if compatible {
+            Ok(())
+        } else {
+            Err(TdmsReadError::IncompatibleChannels {
+                path: self.path(),
+                data_type: self.data_type(),
+                other_path: other.path(),
+                other_data_type: other.data_type(),
+            })
+        }
+    }
+
+    /// Get the number of elements in each value of this channel.
+    /// This is 1 for scalar values, but some legacy writers store array values
+    /// with a larger dimension. Array values are read as a flat sequence of elements,
+    /// and the length of the channel is the total number of elements.
+    pub fn dimension(&self) -> u32 {
+        self.file.tdms_reader.channel_dimension(self.object_id)
+    }
+
+    /// Get the shape of the channel data when read as a flat buffer.
+    /// This is `[len]` for scalar values, or `[len / dimension, dimension]` for array values.
+    pub fn shape(&self) -> Vec<u64> {
+        let dimension = self.dimension() as u64;
+        if dimension == 1 {
+            vec![self.len()]
+        } else {
+            vec![self.len() / dimension, dimension]
+        }
+    }
+
+    /// Whether this channel has no data
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read all data for this channel into newly allocated memory.
+    /// This returns an error if the data would exceed the memory limit set in the read options.
+    /// A channel without any data has no known data type, and is read as empty F64 data.
+    pub fn read_data(&'a self) -> Result<ChannelData> {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        let mut channel_data = if self.file.unbuffered_data_reads {
+            tdms_reader.read_channels_data(file_reader.get_mut(), &[self.object_id])?
+        } else {
+            tdms_reader.read_channels_data(&mut *file_reader, &[self.object_id])?
+        };
+        Ok(channel_data.remove(0))
+    }
+
+    /// Read every `stride`th value of this channel, starting with the first value,
+    /// for example to preview a large channel without reading all of its data.
+    /// Returns an `InvalidStride` error if `stride` is zero.
+    pub fn read_decimated<T: NativeType + Default + Clone>(
+        &'a self,
+        stride: usize,
+    ) -> Result<Vec<T>> {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        if self.file.unbuffered_data_reads {
+            tdms_reader.read_channel_data_decimated(file_reader.get_mut(), self.object_id, stride)
+        } else {
+            tdms_reader.read_channel_data_decimated(&mut *file_reader, self.object_id, stride)
+        }
+    }
+
+    /// Describe how the data for this channel is laid out in each segment that contains it
+    pub fn chunks(&self) -> Vec<SegmentChunks> {
+        self.file.tdms_reader.channel_segment_chunks(self.object_id)
+    }
+
+    /// Get the ranges of bytes within the file that contain the raw data for this channel,
+    /// in order, with adjacent ranges merged. This allows the data to be memory mapped or
+    /// copied directly, and can be combined with the byte order from [`Channel::chunks`].
+    ///
+    /// For segments with interleaved data, the range covers the data of all channels
+    /// in the segment.
+    pub fn byte_ranges(&self) -> Result<Vec<Range<u64>>> {
+        self.file.tdms_reader.channel_byte_ranges(self.object_id)
+    }
+
+    /// Write the undecoded raw data for this channel to `writer`, concatenated across all
+    /// segments in order, and return the number of bytes written. This is useful for
+    /// debugging decoding issues or passing data to other tools.
+    ///
+    /// Values from interleaved segments are de-interleaved, but are otherwise written
+    /// as stored in the file, so the byte order of each segment's data is given by
+    /// [`Channel::chunks`]. String data is written as stored, so each segment's data is
+    /// a 32 bit offset of the end of each string, relative to the start of that
+    /// segment's string contents, followed by the string contents.
+    pub fn write_raw_data<W: Write>(&self, mut writer: W) -> Result<u64> {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        let bytes_written = if self.file.unbuffered_data_reads {
+            tdms_reader.copy_channel_raw_data(file_reader.get_mut(), self.object_id, &mut writer)
+        } else {
+            tdms_reader.copy_channel_raw_data(&mut *file_reader, self.object_id, &mut writer)
+        }?;
+        writer.flush()?;
+        Ok(bytes_written)
+    }
+
+    /// Check that this channel's data can be read as values of type `T`, returning a
+    /// handle that can then read data without checking the type again
+    pub fn typed<T: NativeType + Default + Clone>(&self) -> Result<TypedChannel<'a, R, T>> {
+        TypedChannel::new(self)
+    }
+
+    /// Get an iterator over the values in this channel.
+    /// Values are read lazily in blocks as the iterator is advanced, so only a small
+    /// part of the channel is held in memory at once.
+    pub fn iter_values<T: NativeType + Default + Clone>(&self) -> Result<ChannelValues<'a, R, T>> {
+        let blocks = self
             .file
             .tdms_reader
-            .get_object_path(self.object_id)
-            .unwrap();
-        match channel_path {
-            ObjectPath::Channel(_, ref channel_name) => channel_name,
-            _ => panic!(
-                "Expected a channel path for object id {:?}, got {:?}",
-                self.object_id, channel_path
-            ),
+            .plan_channel_blocks::<T>(self.object_id)?;
+        Ok(ChannelValues::new(self.file, self.object_id, blocks))
+    }
+
+    /// Read a single value from this channel at the given index,
+    /// or return None if the index is beyond the end of the channel
+    pub fn value_at<T: NativeType + Default + Clone>(&'a self, index: u64) -> Result<Option<T>> {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        if self.file.unbuffered_data_reads {
+            tdms_reader.read_channel_value(file_reader.get_mut(), self.object_id, index)
+        } else {
+            tdms_reader.read_channel_value(&mut *file_reader, self.object_id, index)
         }
     }
 
-    pub fn properties(&self) -> &Vec<TdmsProperty> {
-        match self.file.tdms_reader.properties.get(&self.object_id) {
-            Some(properties) => &properties,
-            None => &EMPTY_PROPERITES,
+    /// Get the timing of this channel's data from the `wf_start_time` and `wf_increment`
+    /// properties. If the acquisition was restarted part way through the channel,
+    /// the first start time is used.
+    pub fn waveform_timing(&self) -> Result<WaveformTiming> {
+        self.file.tdms_reader.waveform_timing(self.object_id)
+    }
+
+    /// Read a range of values from this channel starting at the given index,
+    /// filling the buffer. Only the data required is read.
+    pub fn read_data_range<T: NativeType + Default + Clone>(
+        &'a self,
+        start: u64,
+        buffer: &mut [T],
+    ) -> Result<()> {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        if self.file.unbuffered_data_reads {
+            tdms_reader.read_channel_range(file_reader.get_mut(), self.object_id, start, buffer)
+        } else {
+            tdms_reader.read_channel_range(&mut *file_reader, self.object_id, start, buffer)
         }
     }
 
-    pub fn data_type(&'a self) -> TdsType {
-        match self.file.tdms_reader.get_channel_data_index(self.object_id) {
-            Some(channel_data_index) => channel_data_index.data_type,
-            None => TdsType::Void,
+    /// Read `len` values from this channel starting at the given index into newly
+    /// allocated data of the channel's type. Only the data required is read.
+    pub fn read_data_slice(&'a self, start: u64, len: usize) -> Result<ChannelData> {
+        self.file
+            .read_channel_data_range(self.object_id, start, len)
+    }
+
+    /// Read the values of a waveform channel with times between `start` (inclusive) and
+    /// `end` (exclusive), using the `wf_start_time` and `wf_increment` properties.
+    /// If the start time changes part way through the channel because the acquisition was
+    /// restarted, times are computed relative to the start time in effect for each value.
+    pub fn read_between<T: NativeType + Default + Clone>(
+        &'a self,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<T>> {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        if self.file.unbuffered_data_reads {
+            tdms_reader.read_channel_between(file_reader.get_mut(), self.object_id, start, end)
+        } else {
+            tdms_reader.read_channel_between(&mut *file_reader, self.object_id, start, end)
         }
     }
 
-    /// Get the total number of values in this channel
-    pub fn len(&'a self) -> u64 {
-        match self.file.tdms_reader.get_channel_data_index(self.object_id) {
-            Some(channel_data) => channel_data.number_of_values,
-            None => 0,
+    /// For a channel of timestamps in ascending order, find the index of the value nearest
+    /// to the given time. This uses a binary search so only reads a small number of values.
+    /// Returns None if the channel is empty.
+    pub fn find_time_index(&'a self, time: Timestamp) -> Result<Option<u64>> {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        if self.file.unbuffered_data_reads {
+            tdms_reader.find_channel_time_index(file_reader.get_mut(), self.object_id, time)
+        } else {
+            tdms_reader.find_channel_time_index(&mut *file_reader, self.object_id, time)
         }
     }
 
+    /// Compute the minimum and maximum values within `num_windows` equal width windows
+    /// of this channel's data, for example to plot a large channel.
+    /// Data is processed incrementally so the full channel is never held in memory.
+    pub fn read_min_max<T: NativeType + Default + Clone + PartialOrd>(
+        &'a self,
+        num_windows: usize,
+    ) -> Result<Vec<(T, T)>> {
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        if self.file.unbuffered_data_reads {
+            tdms_reader.read_channel_min_max(file_reader.get_mut(), self.object_id, num_windows)
+        } else {
+            tdms_reader.read_channel_min_max(&mut *file_reader, self.object_id, num_windows)
+        }
+    }
+
+    /// Estimate the number of bytes of memory required to read all data for this channel
+    pub fn memory_size(&'a self) -> u64 {
+        self.file.tdms_reader.channel_memory_size(self.object_id)
+    }
+
+    /// Get the total size in bytes of the raw data for this channel within the file
+    pub fn raw_data_size(&self) -> u64 {
+        self.file.tdms_reader.channel_raw_data_size(self.object_id)
+    }
+
     /// Read all data for this channel into the given buffer.
     pub fn read_all_data<T: NativeType>(&'a self, buffer: &mut [T]) -> Result<()> {
-        match self.file.tdms_reader.get_channel_data_index(self.object_id) {
-            Some(channel_data_index) => {
-                if channel_data_index.number_of_values > buffer.len() as u64 {
-                    return Err(TdmsReadError::TdmsError(format!(
-                        "Buffer length needs to be at least {}, received a buffer with length {}",
-                        channel_data_index.number_of_values,
-                        buffer.len()
-                    )));
-                }
-                let tdms_type = channel_data_index.data_type;
-                let expected_native_type = tdms_type.native_type();
-                match expected_native_type {
-                    Some(expected_native_type) if expected_native_type == T::native_type() => {
-                        // Buffer type matches expected native type, safe to read data
-                        self.file.tdms_reader.read_channel_data(
-                            &mut *self.file.file_reader.borrow_mut(),
-                            self.object_id,
-                            buffer,
-                        )?;
-                        Ok(())
-                    }
-                    Some(expected_native_type) => Err(TdmsReadError::TdmsError(format!(
-                        "Expected a buffer with item type {:?}",
-                        expected_native_type
-                    ))),
-                    None => Err(TdmsReadError::TdmsError(format!(
-                        "Reading data of type {:?} is not supported",
-                        tdms_type
-                    ))),
-                }
-            }
-            None => Ok(()),
+        let tdms_reader = &self.file.tdms_reader;
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        // Data reads always seek to an absolute position first, which discards any
+        // buffered data, so it is safe to bypass the buffer here
+        if self.file.unbuffered_data_reads {
+            tdms_reader.read_channel_data(file_reader.get_mut(), self.object_id, buffer)
+        } else {
+            tdms_reader.read_channel_data(&mut *file_reader, self.object_id, buffer)
+        }
+    }
+
+    /// Read all data for this channel into the given buffer using positioned reads from
+    /// `source`, which must contain the same data as this file, for example another
+    /// handle to the same file. This does not use the file's own reader, so may be used
+    /// to read channels concurrently.
+    pub fn read_all_data_at<S: ReadAt + ?Sized, T: NativeType>(
+        &'a self,
+        source: &S,
+        buffer: &mut [T],
+    ) -> Result<()> {
+        self.file
+            .tdms_reader
+            .read_channel_data_at(source, self.object_id, buffer)
+    }
+}
+
+impl<'a, R: Read + Seek> TdmsObject<'a, R> {
+    /// Get the path of the object
+    pub fn path(&self) -> String {
+        let (file, object_id) = match self {
+            TdmsObject::Group(group) => (group.file, group.object_id),
+            TdmsObject::Channel(channel) => (channel.file, channel.object_id),
+        };
+        file.tdms_reader.object_path_string(object_id)
+    }
+
+    pub fn properties(&self) -> &Vec<TdmsProperty> {
+        match self {
+            TdmsObject::Group(group) => group.properties(),
+            TdmsObject::Channel(channel) => channel.properties(),
+        }
+    }
+
+    /// Get the value of a property of the object
+    pub fn property(&self, name: &str) -> Option<&TdmsValue> {
+        match self {
+            TdmsObject::Group(group) => group.property(name),
+            TdmsObject::Channel(channel) => channel.property(name),
         }
     }
 }
 
 impl<'a, R: Read + Seek> GroupIterator<'a, R> {
     fn new(file: &'a TdmsFile<R>) -> GroupIterator<'a, R> {
-        let group_objects: Vec<ObjectPathId> = file
-            .tdms_reader
-            .objects()
-            .filter(|(_, path)| match path {
-                ObjectPath::Group(_) => true,
-                _ => false,
-            })
-            .map(|(id, _)| id)
-            .collect();
+        let group_objects = file.tdms_reader.group_ids();
         GroupIterator {
             file,
             object_iterator: group_objects.into_iter(),
@@ -238,15 +862,7 @@ impl<'a, R: Read + Seek> Iterator for GroupIterator<'a, R> {
 
 impl<'a, R: Read + Seek> ChannelIterator<'a, R> {
     fn new(file: &'a TdmsFile<R>, group_name: &str) -> ChannelIterator<'a, R> {
-        let channel_objects: Vec<ObjectPathId> = file
-            .tdms_reader
-            .objects()
-            .filter(|(_, path)| match path {
-                ObjectPath::Channel(g, _) if g == group_name => true,
-                _ => false,
-            })
-            .map(|(id, _)| id)
-            .collect();
+        let channel_objects = file.tdms_reader.channel_ids(group_name);
         ChannelIterator {
             file,
             object_iterator: channel_objects.into_iter(),
@@ -266,20 +882,68 @@ impl<'a, R: Read + Seek> Iterator for ChannelIterator<'a, R> {
 
 impl<R: Read + Seek> std::fmt::Debug for TdmsFile<R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("TdmsFile").finish()
+        f.debug_struct("TdmsFile")
+            .field("group_count", &self.tdms_reader.group_ids().len())
+            .field("property_count", &self.properties().len())
+            .finish()
+    }
+}
+
+impl<R: Read + Seek> std::fmt::Display for TdmsFile<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TDMS file with {} groups and {} properties",
+            self.tdms_reader.group_ids().len(),
+            self.properties().len()
+        )
     }
 }
 
 impl<'a, R: Read + Seek> std::fmt::Debug for Group<'a, R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Group").finish()
+        f.debug_struct("Group")
+            .field("name", &self.name())
+            .field("channel_count", &self.channel_count())
+            .field("property_count", &self.properties().len())
+            .finish()
+    }
+}
+
+impl<'a, R: Read + Seek> std::fmt::Display for Group<'a, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Group '{}' with {} channels and {} properties",
+            self.name(),
+            self.channel_count(),
+            self.properties().len()
+        )
     }
 }
 
 impl<'a, R: Read + Seek> std::fmt::Debug for Channel<'a, R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Group").finish()
+        let tdms_reader = &self.file.tdms_reader;
+        f.debug_struct("Channel")
+            .field("path", &self.path())
+            .field("data_type", &tdms_reader.channel_data_type(self.object_id))
+            .field("len", &tdms_reader.channel_len(self.object_id))
+            .field("property_count", &self.properties().len())
+            .finish()
     }
 }
 
-static EMPTY_PROPERITES: Vec<TdmsProperty> = Vec::new();
+impl<'a, R: Read + Seek> std::fmt::Display for Channel<'a, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tdms_reader = &self.file.tdms_reader;
+        write!(
+            f,
+            "Channel {} with {} values of type {:?} and {} properties",
+            self.path(),
+            tdms_reader.channel_len(self.object_id),
+            tdms_reader.channel_data_type(self.object_id),
+            self.properties().len()
+        )
+    }
+}
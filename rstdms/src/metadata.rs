@@ -0,0 +1,58 @@
+use crate::properties::TdmsProperty;
+use crate::types::TdsType;
+use crate::TdmsFile;
+use std::io::{Read, Seek};
+
+/// A summary of the metadata of all objects in a TDMS file
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileMetadata {
+    /// Properties of the root object
+    pub properties: Vec<TdmsProperty>,
+    pub groups: Vec<GroupMetadata>,
+}
+
+/// Metadata for a group and the channels within it
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GroupMetadata {
+    pub name: String,
+    pub properties: Vec<TdmsProperty>,
+    pub channels: Vec<ChannelMetadata>,
+}
+
+/// Metadata for a single channel
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelMetadata {
+    pub name: String,
+    pub data_type: TdsType,
+    /// Total number of values in the channel
+    pub len: u64,
+    pub properties: Vec<TdmsProperty>,
+}
+
+impl FileMetadata {
+    pub(crate) fn new<R: Read + Seek>(file: &TdmsFile<R>) -> FileMetadata {
+        let groups = file
+            .groups()
+            .map(|group| GroupMetadata {
+                name: group.name().to_owned(),
+                properties: group.properties().clone(),
+                channels: group
+                    .channels()
+                    .map(|channel| ChannelMetadata {
+                        name: channel.name().to_owned(),
+                        data_type: channel.data_type(),
+                        len: channel.len(),
+                        properties: channel.properties().clone(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        FileMetadata {
+            properties: file.properties().clone(),
+            groups,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::io::{Read, Result, Seek, SeekFrom};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Counters describing how a TDMS file has been accessed, returned by `TdmsFile::metrics`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IoMetrics {
+    /// Number of bytes read from the underlying reader
+    pub bytes_read: u64,
+    /// Number of seeks performed on the underlying reader
+    pub seeks: u64,
+    /// Number of segments whose metadata has been parsed
+    pub segments_parsed: u64,
+    /// Number of chunks of channel data that have been read and decoded
+    pub chunks_decoded: u64,
+}
+
+/// Counters for reads and seeks made on an underlying reader
+#[derive(Debug, Default)]
+pub(crate) struct ReaderCounters {
+    bytes_read: AtomicU64,
+    seeks: AtomicU64,
+}
+
+impl ReaderCounters {
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+
+    pub fn seeks(&self) -> u64 {
+        self.seeks.load(Ordering::Relaxed)
+    }
+}
+
+/// Wraps a reader to count the bytes read and seeks performed
+pub(crate) struct CountingReader<R> {
+    inner: R,
+    counters: Arc<ReaderCounters>,
+}
+
+impl<R> CountingReader<R> {
+    pub fn new(inner: R) -> CountingReader<R> {
+        CountingReader {
+            inner,
+            counters: Arc::new(ReaderCounters::default()),
+        }
+    }
+
+    pub fn counters(&self) -> Arc<ReaderCounters> {
+        self.counters.clone()
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        self.counters
+            .bytes_read
+            .fetch_add(bytes_read as u64, Ordering::Relaxed);
+        Ok(bytes_read)
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        self.counters.seeks.fetch_add(1, Ordering::Relaxed);
+        self.inner.seek(pos)
+    }
+}
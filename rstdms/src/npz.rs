@@ -0,0 +1,110 @@
+use crate::channel_data::ChannelData;
+use crate::error::Result;
+use crate::object_path::ObjectPath;
+use crate::writer::write_channel_data;
+use crate::Channel;
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::{Read, Seek, Write};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// Value of a NumPy datetime64 that is not a time
+const NAT: i64 = i64::MIN;
+
+/// Write the data of one or more channels to a compressed NumPy `.npz` archive,
+/// with one array per channel named `GROUP/CHANNEL`, which can be loaded with `numpy.load`.
+///
+/// Timestamps are written as `datetime64[ns]` values, with timestamps outside the range
+/// of nanoseconds since the Unix epoch written as `NaT`. Strings are written as
+/// fixed width Unicode arrays. Channels without any data are written as empty
+/// `float64` arrays.
+pub fn write_npz<R: Read + Seek, W: Write + Seek>(
+    channels: &[Channel<'_, R>],
+    writer: W,
+) -> Result<W> {
+    let mut zip_writer = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    let mut array = Vec::new();
+    for channel in channels {
+        let name = match channel.file.tdms_reader.get_object_path(channel.object_id) {
+            Some(ObjectPath::Channel(group_name, channel_name)) => {
+                format!("{}/{}.npy", group_name, channel_name)
+            }
+            _ => format!("{}.npy", channel.name()),
+        };
+        array.clear();
+        write_npy(&mut array, &channel.read_data()?)?;
+        zip_writer.start_file(name, options)?;
+        zip_writer.write_all(&array)?;
+    }
+    Ok(zip_writer.finish()?)
+}
+
+/// Write channel data in the NumPy `.npy` format, as a one dimensional little endian array
+fn write_npy(bytes: &mut Vec<u8>, data: &ChannelData) -> Result<()> {
+    let descr = match data {
+        ChannelData::I8(_) => "|i1".to_owned(),
+        ChannelData::I16(_) => "<i2".to_owned(),
+        ChannelData::I32(_) => "<i4".to_owned(),
+        ChannelData::I64(_) => "<i8".to_owned(),
+        ChannelData::U8(_) => "|u1".to_owned(),
+        ChannelData::U16(_) => "<u2".to_owned(),
+        ChannelData::U32(_) => "<u4".to_owned(),
+        ChannelData::U64(_) => "<u8".to_owned(),
+        ChannelData::F32(_) => "<f4".to_owned(),
+        ChannelData::F64(_) => "<f8".to_owned(),
+        ChannelData::String(values) => format!("<U{}", max_string_chars(values)),
+        ChannelData::Timestamp(_) => "<M8[ns]".to_owned(),
+    };
+    write_npy_header(bytes, &descr, data.len())?;
+    match data {
+        ChannelData::String(values) => {
+            // Each string is padded with zeros to the same number of UTF-32 code points
+            let width = max_string_chars(values);
+            for value in values {
+                let mut chars = 0;
+                for c in value.chars() {
+                    bytes.write_u32::<LittleEndian>(c as u32)?;
+                    chars += 1;
+                }
+                bytes.resize(bytes.len() + 4 * (width - chars), 0);
+            }
+        }
+        ChannelData::Timestamp(values) => {
+            for value in values {
+                bytes.write_i64::<LittleEndian>(value.to_unix_nanos().unwrap_or(NAT))?;
+            }
+        }
+        _ => write_channel_data(bytes, data)?,
+    }
+    Ok(())
+}
+
+/// Write the `.npy` magic string, version and header describing a one dimensional array
+fn write_npy_header(bytes: &mut Vec<u8>, descr: &str, len: usize) -> Result<()> {
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': ({},), }}",
+        descr, len
+    );
+    // The header is padded with spaces and terminated with a newline so that the data
+    // starts at a multiple of 64 bytes, after the 10 bytes of magic string, version and length
+    let unpadded_length = 10 + header.len() + 1;
+    header.extend(std::iter::repeat_n(' ', (64 - unpadded_length % 64) % 64));
+    header.push('\n');
+    bytes.extend_from_slice(b"\x93NUMPY\x01\x00");
+    bytes.write_u16::<LittleEndian>(header.len() as u16)?;
+    bytes.extend_from_slice(header.as_bytes());
+    Ok(())
+}
+
+/// Get the width of a NumPy Unicode array that can hold all strings, which must be at least one
+fn max_string_chars(values: &[String]) -> usize {
+    values
+        .iter()
+        .map(|value| value.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(1)
+}
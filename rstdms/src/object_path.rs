@@ -21,6 +21,18 @@ pub enum ObjectPath {
     Channel(String, String),
 }
 
+impl std::fmt::Display for ObjectPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ObjectPath::Root => write!(f, "/"),
+            ObjectPath::Group(group_name) => write!(f, "{}", path_from_group(group_name)),
+            ObjectPath::Channel(group_name, channel_name) => {
+                write!(f, "{}", path_from_channel(group_name, channel_name))
+            }
+        }
+    }
+}
+
 enum PathParserState {
     /// We're expecting the beginning of a new componet
     ComponentStart,
@@ -58,10 +70,7 @@ impl ObjectPath {
                             parser_state = PathParserState::InComponent(start_index + 2);
                         }
                         _ => {
-                            return Err(TdmsReadError::TdmsError(format!(
-                                "Invalid object path {}",
-                                input_string
-                            )))
+                            return Err(TdmsReadError::InvalidObjectPath(input_string.to_string()))
                         }
                     }
                 }
@@ -81,10 +90,7 @@ impl ObjectPath {
                         }
                         (None, _) => {
                             // Unexpected end of path
-                            return Err(TdmsReadError::TdmsError(format!(
-                                "Invalid object path {}",
-                                input_string
-                            )));
+                            return Err(TdmsReadError::InvalidObjectPath(input_string.to_string()));
                         }
                     }
                 }
@@ -93,18 +99,15 @@ impl ObjectPath {
             next_char = char_iterator.next();
         }
 
-        return match components.as_slice() {
+        match components.as_slice() {
             [] => Ok(ObjectPath::Root),
             [group_name] => Ok(ObjectPath::Group(group_name.replace("''", "'"))),
             [group_name, channel_name] => Ok(ObjectPath::Channel(
                 group_name.replace("''", "'"),
                 channel_name.replace("''", "'"),
             )),
-            _ => Err(TdmsReadError::TdmsError(format!(
-                "Invalid object path '{}' with more than 2 components",
-                input_string
-            ))),
-        };
+            _ => Err(TdmsReadError::InvalidObjectPath(input_string.to_string())),
+        }
     }
 }
 
@@ -132,10 +135,7 @@ impl ObjectPathCache {
     }
 
     pub fn get_id(&self, object_path: &str) -> Option<ObjectPathId> {
-        match self.path_to_id.get(object_path) {
-            Some(&obj_id) => Some(obj_id),
-            None => None,
-        }
+        self.path_to_id.get(object_path).copied()
     }
 
     pub fn get_path(&self, object_path_id: ObjectPathId) -> Option<&ObjectPath> {
@@ -0,0 +1,153 @@
+use crate::error::Result;
+use bytes::Bytes;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use std::future::Future;
+use std::io::{Error, ErrorKind, SeekFrom};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+/// Default number of bytes fetched per range request
+const DEFAULT_BLOCK_SIZE: usize = 64 * 1024;
+
+type PendingRead = Pin<Box<dyn Future<Output = object_store::Result<Bytes>> + Send>>;
+
+/// Reads an object from an object store such as S3 or Azure Blob Storage using range requests,
+/// so that it can be opened with `TdmsFileAsync` without downloading the whole file.
+///
+/// Data is fetched in blocks of at least the configured block size and the most recent block
+/// is cached, so the lead in and metadata of a segment (and of consecutive small segments)
+/// are usually read with a single request.
+pub struct ObjectStoreReader {
+    store: Arc<dyn ObjectStore>,
+    location: Path,
+    size: u64,
+    position: u64,
+    block_size: usize,
+    block: Bytes,
+    block_start: u64,
+    pending_read: Option<(u64, PendingRead)>,
+}
+
+impl ObjectStoreReader {
+    /// Create a reader for an object, requesting the object metadata to determine its size
+    pub async fn new(store: Arc<dyn ObjectStore>, location: Path) -> Result<ObjectStoreReader> {
+        let object_meta = store.head(&location).await.map_err(to_io_error)?;
+        Ok(ObjectStoreReader::with_size(
+            store,
+            location,
+            object_meta.size,
+        ))
+    }
+
+    /// Create a reader for an object with a known size
+    pub fn with_size(store: Arc<dyn ObjectStore>, location: Path, size: u64) -> ObjectStoreReader {
+        ObjectStoreReader {
+            store,
+            location,
+            size,
+            position: 0,
+            block_size: DEFAULT_BLOCK_SIZE,
+            block: Bytes::new(),
+            block_start: 0,
+            pending_read: None,
+        }
+    }
+
+    /// Set the minimum number of bytes to request at once
+    pub fn block_size(mut self, block_size: usize) -> ObjectStoreReader {
+        self.block_size = block_size.max(1);
+        self
+    }
+
+    /// The size of the object in bytes
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    fn cached_bytes(&self) -> Option<&[u8]> {
+        let block_end = self.block_start + self.block.len() as u64;
+        if self.position >= self.block_start && self.position < block_end {
+            Some(&self.block[(self.position - self.block_start) as usize..])
+        } else {
+            None
+        }
+    }
+}
+
+impl AsyncRead for ObjectStoreReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.position >= this.size || buf.remaining() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+            if let Some(cached_bytes) = this.cached_bytes() {
+                let length = cached_bytes.len().min(buf.remaining());
+                buf.put_slice(&cached_bytes[..length]);
+                this.position += length as u64;
+                return Poll::Ready(Ok(()));
+            }
+            let (start, pending_read) = this.pending_read.get_or_insert_with(|| {
+                let start = this.position;
+                let length = this.block_size.max(buf.remaining()) as u64;
+                let end = this.size.min(start + length);
+                let store = this.store.clone();
+                let location = this.location.clone();
+                let pending_read: PendingRead =
+                    Box::pin(async move { store.get_range(&location, start..end).await });
+                (start, pending_read)
+            });
+            match pending_read.as_mut().poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    this.block_start = *start;
+                    this.pending_read = None;
+                    this.block = result.map_err(to_io_error)?;
+                    if this.block.is_empty() {
+                        // Object is shorter than expected
+                        return Poll::Ready(Ok(()));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl AsyncSeek for ObjectStoreReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        let this = self.get_mut();
+        let new_position = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => this.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => this.position.checked_add_signed(offset),
+        };
+        match new_position {
+            Some(new_position) => {
+                this.position = new_position;
+                Ok(())
+            }
+            None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Poll::Ready(Ok(self.position))
+    }
+}
+
+fn to_io_error(error: object_store::Error) -> Error {
+    match error {
+        object_store::Error::NotFound { .. } => Error::new(ErrorKind::NotFound, error),
+        _ => Error::other(error),
+    }
+}
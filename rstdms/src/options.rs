@@ -0,0 +1,130 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// Capacity of the buffer used for reading when not otherwise specified
+const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
+
+/// Options that control how TDMS files are read
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    pub(crate) lossy_utf8: bool,
+    pub(crate) buffer_capacity: Option<usize>,
+    pub(crate) unbuffered_data_reads: bool,
+    pub(crate) progress_callback: Option<ProgressCallback>,
+    pub(crate) memory_limit: Option<u64>,
+    pub(crate) merge_tdms_1_0_object_lists: bool,
+    pub(crate) property_history: bool,
+    #[cfg(feature = "encoding")]
+    pub(crate) fallback_encoding: Option<&'static encoding_rs::Encoding>,
+}
+
+impl ReadOptions {
+    /// Create options with the default settings
+    pub fn new() -> ReadOptions {
+        ReadOptions::default()
+    }
+
+    /// Replace invalid UTF-8 sequences in object paths and property strings with the
+    /// Unicode replacement character instead of returning an error.
+    /// A warning is recorded for each string that could not be decoded.
+    pub fn lossy_utf8(mut self, lossy_utf8: bool) -> ReadOptions {
+        self.lossy_utf8 = lossy_utf8;
+        self
+    }
+
+    /// Set an encoding to decode object paths and property strings with when they
+    /// are not valid UTF-8, eg. `encoding_rs::WINDOWS_1252` for files written by older
+    /// versions of LabVIEW. This takes precedence over lossy UTF-8 decoding.
+    #[cfg(feature = "encoding")]
+    pub fn fallback_encoding(mut self, encoding: &'static encoding_rs::Encoding) -> ReadOptions {
+        self.fallback_encoding = Some(encoding);
+        self
+    }
+
+    /// Set the capacity in bytes of the buffer used when reading from the underlying reader.
+    /// The default is 8 KiB. A larger buffer reduces the number of reads made when parsing
+    /// files with many small segments.
+    pub fn buffer_capacity(mut self, capacity: usize) -> ReadOptions {
+        self.buffer_capacity = Some(capacity);
+        self
+    }
+
+    /// Read channel data directly from the underlying reader rather than through the buffer.
+    /// Metadata is still read through the buffer. This avoids an extra copy when reading
+    /// large contiguous chunks of data, for example from a file that is already in the
+    /// operating system's page cache.
+    pub fn unbuffered_data_reads(mut self, unbuffered_data_reads: bool) -> ReadOptions {
+        self.unbuffered_data_reads = unbuffered_data_reads;
+        self
+    }
+
+    /// Set a callback that is called after the metadata of each segment is read when
+    /// opening a file, to allow reporting progress when opening large files
+    pub fn progress_callback<F>(mut self, callback: F) -> ReadOptions
+    where
+        F: Fn(&ReadProgress) + Send + Sync + 'static,
+    {
+        self.progress_callback = Some(ProgressCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Set the maximum number of bytes of memory that may be allocated to hold channel data
+    /// when reading data into newly allocated memory, for example with
+    /// `Channel::read_data`, `Channel::read_data_slice` or `Group::read_all_channels`,
+    /// or to hold the raw data of a segment when streaming. Reads that would exceed this
+    /// limit return a `MemoryLimitExceeded` error instead. Caller provided buffers are not
+    /// limited, but temporary buffers used while reading into them are.
+    pub fn memory_limit(mut self, bytes: u64) -> ReadOptions {
+        self.memory_limit = Some(bytes);
+        self
+    }
+
+    /// Ignore the new object list flag in TDMS 1.0 (version 4712) segments and always merge
+    /// their objects with those of the previous segment. Some legacy writers set the flag
+    /// without writing the full list of objects, which otherwise drops channels from
+    /// later segments.
+    pub fn merge_tdms_1_0_object_lists(mut self, merge: bool) -> ReadOptions {
+        self.merge_tdms_1_0_object_lists = merge;
+        self
+    }
+
+    /// Keep every value each property is set to when properties are redefined in later
+    /// segments, so they can be retrieved with `property_history`.
+    /// By default only the most recently set value of each property is kept.
+    pub fn property_history(mut self, property_history: bool) -> ReadOptions {
+        self.property_history = property_history;
+        self
+    }
+
+    pub(crate) fn reader_buffer_capacity(&self) -> usize {
+        self.buffer_capacity.unwrap_or(DEFAULT_BUFFER_CAPACITY)
+    }
+}
+
+/// Progress of reading the metadata of a TDMS file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadProgress {
+    /// Number of segments read so far
+    pub segments_read: usize,
+    /// Number of bytes of the file processed so far
+    pub bytes_processed: u64,
+    /// Position in the file of the next segment to read
+    pub position: u64,
+    /// Total length of the file in bytes
+    pub file_length: u64,
+}
+
+#[derive(Clone)]
+pub(crate) struct ProgressCallback(Arc<dyn Fn(&ReadProgress) + Send + Sync>);
+
+impl ProgressCallback {
+    pub fn call(&self, progress: &ReadProgress) {
+        (self.0)(progress)
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
@@ -0,0 +1,96 @@
+use crate::error::{Result, TdmsReadError};
+use crate::read_at::ReadAt;
+use crate::segment::DataChunk;
+use crate::types::NativeType;
+use crate::{Channel, TdmsFile};
+use rayon::prelude::*;
+use std::io::{BufReader, Read, Seek};
+
+impl<R: Read + Seek> TdmsFile<R> {
+    /// Read all data for multiple channels concurrently using rayon.
+    ///
+    /// Each channel is read with an independent reader created by `open_reader`,
+    /// eg. `|| File::open(path)`, so that reads are not serialized on this file's reader.
+    /// Each buffer must be at least as long as the corresponding channel.
+    pub fn read_channels_parallel<T, S, F>(
+        &self,
+        channels: &[Channel<'_, R>],
+        buffers: &mut [&mut [T]],
+        open_reader: F,
+    ) -> Result<()>
+    where
+        T: NativeType + Send,
+        S: Read + Seek,
+        F: Fn() -> std::io::Result<S> + Sync,
+    {
+        let read_plans = self.plan_channel_reads(channels, buffers)?;
+        let tdms_reader = &self.tdms_reader;
+
+        buffers
+            .par_iter_mut()
+            .zip(read_plans.into_par_iter())
+            .try_for_each(|(buffer, chunks)| {
+                if chunks.is_empty() {
+                    return Ok(());
+                }
+                let mut reader = BufReader::new(open_reader()?);
+                let mut offset = 0;
+                for chunk in chunks {
+                    tdms_reader.chunk_decoded();
+                    offset += chunk.read_values(&mut reader, &mut buffer[offset..])?;
+                }
+                Ok(())
+            })
+    }
+
+    /// Read all data for multiple channels concurrently using rayon, with positioned reads
+    /// from a single shared source, such as a `File` on platforms that support `pread`.
+    /// The source must contain the same data as this TDMS file.
+    /// Each buffer must be at least as long as the corresponding channel.
+    pub fn read_channels_parallel_at<T, S>(
+        &self,
+        channels: &[Channel<'_, R>],
+        buffers: &mut [&mut [T]],
+        source: &S,
+    ) -> Result<()>
+    where
+        T: NativeType + Send,
+        S: ReadAt + Sync + ?Sized,
+    {
+        let read_plans = self.plan_channel_reads(channels, buffers)?;
+        let tdms_reader = &self.tdms_reader;
+
+        buffers
+            .par_iter_mut()
+            .zip(read_plans.into_par_iter())
+            .try_for_each(|(buffer, chunks)| {
+                let mut offset = 0;
+                for chunk in chunks {
+                    tdms_reader.chunk_decoded();
+                    offset += chunk.read_values_at(source, &mut buffer[offset..])?;
+                }
+                Ok(())
+            })
+    }
+
+    fn plan_channel_reads<T: NativeType>(
+        &self,
+        channels: &[Channel<'_, R>],
+        buffers: &[&mut [T]],
+    ) -> Result<Vec<Vec<DataChunk>>> {
+        if channels.len() != buffers.len() {
+            return Err(TdmsReadError::BufferCountMismatch {
+                buffers: buffers.len(),
+                channels: channels.len(),
+            });
+        }
+        channels
+            .iter()
+            .zip(buffers.iter())
+            .map(|(channel, buffer)| {
+                self.tdms_reader
+                    .plan_channel_read::<T>(channel.object_id, buffer.len())
+            })
+            .collect()
+    }
+}
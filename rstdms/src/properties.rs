@@ -1,11 +1,14 @@
 use crate::error::{Result, TdmsReadError};
 use crate::timestamp::Timestamp;
 use byteorder::ReadBytesExt;
+use indexmap::IndexMap;
+use std::fmt;
 use std::io::Read;
 
-use crate::types::{read_string, read_timestamp, ByteOrderExt, TdsType};
+use crate::types::{read_string, read_timestamp, ByteOrderExt, StringDecoder, TdsType};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TdmsValue {
     Int8(i8),
     Int16(i16),
@@ -21,13 +24,36 @@ pub enum TdmsValue {
     Timestamp(Timestamp),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TdmsProperty {
     pub name: String,
     pub value: TdmsValue,
 }
 
-fn read_value<R: Read, O: ByteOrderExt>(type_id: TdsType, reader: &mut R) -> Result<TdmsValue> {
+/// A value a property was set to, with the index of the segment it was set in
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PropertyRevision {
+    pub segment_index: usize,
+    pub value: TdmsValue,
+}
+
+/// Create a map from property names to values, in the order properties were first defined.
+/// If a name is repeated the last value is used.
+pub(crate) fn properties_map(properties: &[TdmsProperty]) -> IndexMap<&str, &TdmsValue> {
+    let mut map = IndexMap::with_capacity(properties.len());
+    for property in properties {
+        map.insert(property.name.as_str(), &property.value);
+    }
+    map
+}
+
+fn read_value<R: Read, O: ByteOrderExt>(
+    type_id: TdsType,
+    reader: &mut R,
+    decoder: &mut StringDecoder,
+) -> Result<TdmsValue> {
     match type_id {
         TdsType::I8 => Ok(TdmsValue::Int8(reader.read_i8()?)),
         TdsType::I16 => Ok(TdmsValue::Int16(reader.read_i16::<O>()?)),
@@ -39,21 +65,107 @@ fn read_value<R: Read, O: ByteOrderExt>(type_id: TdsType, reader: &mut R) -> Res
         TdsType::U64 => Ok(TdmsValue::Uint64(reader.read_u64::<O>()?)),
         TdsType::SingleFloat => Ok(TdmsValue::Float32(reader.read_f32::<O>()?)),
         TdsType::DoubleFloat => Ok(TdmsValue::Float64(reader.read_f64::<O>()?)),
-        TdsType::String => Ok(TdmsValue::String(read_string::<R, O>(reader)?)),
+        TdsType::String => Ok(TdmsValue::String(read_string::<R, O>(reader, decoder)?)),
         TdsType::TimeStamp => Ok(TdmsValue::Timestamp(read_timestamp::<R, O>(reader)?)),
-        _ => Err(TdmsReadError::TdmsError(format!(
-            "Unsupported property type {:?}",
-            type_id
-        ))),
+        _ => Err(TdmsReadError::UnknownPropertyType(type_id)),
+    }
+}
+
+impl TdmsValue {
+    /// Get the TDMS data type of the value
+    pub fn data_type(&self) -> TdsType {
+        match self {
+            TdmsValue::Int8(_) => TdsType::I8,
+            TdmsValue::Int16(_) => TdsType::I16,
+            TdmsValue::Int32(_) => TdsType::I32,
+            TdmsValue::Int64(_) => TdsType::I64,
+            TdmsValue::Uint8(_) => TdsType::U8,
+            TdmsValue::Uint16(_) => TdsType::U16,
+            TdmsValue::Uint32(_) => TdsType::U32,
+            TdmsValue::Uint64(_) => TdsType::U64,
+            TdmsValue::Float32(_) => TdsType::SingleFloat,
+            TdmsValue::Float64(_) => TdsType::DoubleFloat,
+            TdmsValue::String(_) => TdsType::String,
+            TdmsValue::Timestamp(_) => TdsType::TimeStamp,
+        }
+    }
+
+    /// Get a numeric value as an f64
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            TdmsValue::Int8(v) => Some(*v as f64),
+            TdmsValue::Int16(v) => Some(*v as f64),
+            TdmsValue::Int32(v) => Some(*v as f64),
+            TdmsValue::Int64(v) => Some(*v as f64),
+            TdmsValue::Uint8(v) => Some(*v as f64),
+            TdmsValue::Uint16(v) => Some(*v as f64),
+            TdmsValue::Uint32(v) => Some(*v as f64),
+            TdmsValue::Uint64(v) => Some(*v as f64),
+            TdmsValue::Float32(v) => Some(*v as f64),
+            TdmsValue::Float64(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Get an integer value as an i64, or `None` if it doesn't fit
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            TdmsValue::Int8(v) => Some(*v as i64),
+            TdmsValue::Int16(v) => Some(*v as i64),
+            TdmsValue::Int32(v) => Some(*v as i64),
+            TdmsValue::Int64(v) => Some(*v),
+            TdmsValue::Uint8(v) => Some(*v as i64),
+            TdmsValue::Uint16(v) => Some(*v as i64),
+            TdmsValue::Uint32(v) => Some(*v as i64),
+            TdmsValue::Uint64(v) => i64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TdmsValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_timestamp(&self) -> Option<Timestamp> {
+        match self {
+            TdmsValue::Timestamp(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+/// Formats the value without its type, with timestamps in ISO 8601 format
+impl fmt::Display for TdmsValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TdmsValue::Int8(v) => write!(f, "{}", v),
+            TdmsValue::Int16(v) => write!(f, "{}", v),
+            TdmsValue::Int32(v) => write!(f, "{}", v),
+            TdmsValue::Int64(v) => write!(f, "{}", v),
+            TdmsValue::Uint8(v) => write!(f, "{}", v),
+            TdmsValue::Uint16(v) => write!(f, "{}", v),
+            TdmsValue::Uint32(v) => write!(f, "{}", v),
+            TdmsValue::Uint64(v) => write!(f, "{}", v),
+            TdmsValue::Float32(v) => write!(f, "{}", v),
+            TdmsValue::Float64(v) => write!(f, "{}", v),
+            TdmsValue::String(v) => write!(f, "{}", v),
+            TdmsValue::Timestamp(v) => write!(f, "{}", v),
+        }
     }
 }
 
 impl TdmsProperty {
-    pub fn read<R: Read, O: ByteOrderExt>(reader: &mut R) -> Result<TdmsProperty> {
-        let name = read_string::<R, O>(reader)?;
+    pub fn read<R: Read, O: ByteOrderExt>(
+        reader: &mut R,
+        decoder: &mut StringDecoder,
+    ) -> Result<TdmsProperty> {
+        let name = read_string::<R, O>(reader, decoder)?;
         let type_id_raw = reader.read_u32::<O>()?;
         let type_id = TdsType::from_u32(type_id_raw)?;
-        let value = read_value::<R, O>(type_id, reader)?;
+        let value = read_value::<R, O>(type_id, reader, decoder)?;
         Ok(TdmsProperty { name, value })
     }
 }
@@ -80,7 +192,9 @@ mod test {
             0A 00 00 00
             "
         ));
-        let property = TdmsProperty::read::<_, LittleEndian>(&mut reader).unwrap();
+        let property =
+            TdmsProperty::read::<_, LittleEndian>(&mut reader, &mut StringDecoder::default())
+                .unwrap();
 
         assert_eq!(property.name, "property name");
         assert_eq!(property.value, TdmsValue::Int32(10i32));
@@ -97,7 +211,9 @@ mod test {
             70 72 6F 70 65 72 74 79 20 76 61 6C 75 65
             "
         ));
-        let property = TdmsProperty::read::<_, LittleEndian>(&mut reader).unwrap();
+        let property =
+            TdmsProperty::read::<_, LittleEndian>(&mut reader, &mut StringDecoder::default())
+                .unwrap();
 
         assert_eq!(property.name, "property name");
         assert_eq!(
@@ -117,7 +233,9 @@ mod test {
             7B 63 14 D2 00 00 00 00
             "
         ));
-        let property = TdmsProperty::read::<_, LittleEndian>(&mut reader).unwrap();
+        let property =
+            TdmsProperty::read::<_, LittleEndian>(&mut reader, &mut StringDecoder::default())
+                .unwrap();
 
         assert_eq!(property.name, "property name");
         assert_eq!(
@@ -127,14 +245,42 @@ mod test {
 
         if let TdmsValue::Timestamp(ts) = property.value {
             let expected_time = Utc
-                .ymd(2015, 9, 8)
-                .and_hms(10, 5, 47)
+                .with_ymd_and_hms(2015, 9, 8, 10, 5, 47)
+                .unwrap()
                 .checked_add_signed(Duration::nanoseconds(669260594))
                 .unwrap();
             assert_eq!(ts.to_datetime(), Some(expected_time));
         }
     }
 
+    #[test]
+    pub fn value_display() {
+        assert_eq!(TdmsValue::Int16(-3).to_string(), "-3");
+        assert_eq!(TdmsValue::Float64(0.25).to_string(), "0.25");
+        assert_eq!(TdmsValue::String("abc".to_owned()).to_string(), "abc");
+        assert_eq!(
+            TdmsValue::Timestamp(Timestamp::new(0, 1 << 63)).to_string(),
+            "1904-01-01T00:00:00.500000000Z"
+        );
+        assert_eq!(TdmsValue::Int16(-3).data_type(), TdsType::I16);
+    }
+
+    #[test]
+    pub fn value_conversions() {
+        assert_eq!(TdmsValue::Uint16(3).as_f64(), Some(3.0));
+        assert_eq!(TdmsValue::Float32(0.5).as_f64(), Some(0.5));
+        assert_eq!(TdmsValue::Int8(-2).as_i64(), Some(-2));
+        assert_eq!(TdmsValue::Uint64(u64::MAX).as_i64(), None);
+        assert_eq!(TdmsValue::Float64(1.0).as_i64(), None);
+        assert_eq!(TdmsValue::String("V".to_owned()).as_str(), Some("V"));
+        assert_eq!(TdmsValue::Int32(1).as_str(), None);
+        let timestamp = Timestamp::new(1, 0);
+        assert_eq!(
+            TdmsValue::Timestamp(timestamp).as_timestamp(),
+            Some(timestamp)
+        );
+    }
+
     #[test]
     pub fn unexpected_end_of_data() {
         let mut reader = Cursor::new(hex!(
@@ -143,7 +289,9 @@ mod test {
             70 72 6F 70 65 72
             "
         ));
-        let error = TdmsProperty::read::<_, LittleEndian>(&mut reader).unwrap_err();
+        let error =
+            TdmsProperty::read::<_, LittleEndian>(&mut reader, &mut StringDecoder::default())
+                .unwrap_err();
 
         match error {
             TdmsReadError::IoError(_) => {}
@@ -159,7 +307,9 @@ mod test {
             FF FF FF FF FF FF FF FF FF FF FF FF FF
             "
         ));
-        let error = TdmsProperty::read::<_, LittleEndian>(&mut reader).unwrap_err();
+        let error =
+            TdmsProperty::read::<_, LittleEndian>(&mut reader, &mut StringDecoder::default())
+                .unwrap_err();
 
         match error {
             TdmsReadError::Utf8Error(_) => {}
@@ -0,0 +1,38 @@
+//! Names of standard properties written by NI software.
+//!
+//! Property values can be read with `property` on a file, group or channel,
+//! and converted with the `TdmsValue` accessors, eg.
+//! `channel.property(property_names::WF_INCREMENT).and_then(TdmsValue::as_f64)`.
+
+/// Name of the file, set on the root object
+pub const NAME: &str = "name";
+/// Description of a file, group or channel
+pub const DESCRIPTION: &str = "description";
+/// Title of the file, set on the root object
+pub const TITLE: &str = "title";
+/// Author of the file, set on the root object
+pub const AUTHOR: &str = "author";
+/// Time the file was created, set on the root object
+pub const DATETIME: &str = "datetime";
+
+/// Time of the first sample in a waveform channel
+pub const WF_START_TIME: &str = "wf_start_time";
+/// Offset of the first sample in a waveform channel from the start time, in seconds
+pub const WF_START_OFFSET: &str = "wf_start_offset";
+/// Time between samples in a waveform channel, in seconds
+pub const WF_INCREMENT: &str = "wf_increment";
+/// Number of samples in each waveform written
+pub const WF_SAMPLES: &str = "wf_samples";
+/// Name of the x axis of a waveform channel
+pub const WF_XNAME: &str = "wf_xname";
+/// Unit of the x axis of a waveform channel
+pub const WF_XUNIT_STRING: &str = "wf_xunit_string";
+
+/// Unit of the values in a channel
+pub const UNIT_STRING: &str = "unit_string";
+/// Name of the physical or virtual channel data was acquired from
+pub const NI_CHANNEL_NAME: &str = "NI_ChannelName";
+/// Number of scales defined for a channel
+pub const NI_NUMBER_OF_SCALES: &str = "NI_Number_Of_Scales";
+/// Whether the channel data has been scaled, either "scaled" or "unscaled"
+pub const NI_SCALING_STATUS: &str = "NI_Scaling_Status";
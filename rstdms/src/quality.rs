@@ -0,0 +1,134 @@
+use crate::error::{Result, TdmsReadError};
+use crate::types::NativeTypeId;
+use crate::Channel;
+use std::io::{Read, Seek};
+use std::ops::Range;
+
+/// Options that control how the data quality of a channel is checked
+#[derive(Debug, Clone, Default)]
+pub struct QualityOptions {
+    sentinel_values: Vec<f64>,
+    min_constant_run: u64,
+}
+
+impl QualityOptions {
+    /// Create options with the default settings
+    pub fn new() -> QualityOptions {
+        QualityOptions::default()
+    }
+
+    /// Count values equal to a sentinel value that marks missing data, eg. -9999.
+    /// Can be called multiple times to check for several sentinel values.
+    pub fn sentinel(mut self, value: f64) -> QualityOptions {
+        self.sentinel_values.push(value);
+        self
+    }
+
+    /// Report runs of at least this many repeated values, which can indicate a stuck
+    /// sensor. Runs are not reported by default.
+    pub fn min_constant_run(mut self, min_constant_run: u64) -> QualityOptions {
+        self.min_constant_run = min_constant_run;
+        self
+    }
+}
+
+/// Summary of problems found in the data of a floating point channel
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QualityReport {
+    /// Total number of values checked
+    pub len: u64,
+    /// Number of NaN values
+    pub nan_count: u64,
+    /// Number of positive or negative infinite values
+    pub infinite_count: u64,
+    /// Number of values equal to one of the sentinel values
+    pub sentinel_count: u64,
+    /// Length of the longest run of repeated values
+    pub longest_constant_run: u64,
+    /// Index ranges of runs of repeated values at least as long as the minimum run length
+    pub constant_runs: Vec<Range<u64>>,
+}
+
+impl QualityReport {
+    /// Whether any NaN, infinite or sentinel values or long constant runs were found
+    pub fn has_issues(&self) -> bool {
+        self.nan_count > 0
+            || self.infinite_count > 0
+            || self.sentinel_count > 0
+            || !self.constant_runs.is_empty()
+    }
+}
+
+/// Accumulates a quality report one value at a time
+struct QualityScanner<'o> {
+    options: &'o QualityOptions,
+    report: QualityReport,
+    run_start: u64,
+    previous: Option<f64>,
+}
+
+impl<'o> QualityScanner<'o> {
+    fn new(options: &'o QualityOptions) -> QualityScanner<'o> {
+        QualityScanner {
+            options,
+            report: QualityReport::default(),
+            run_start: 0,
+            previous: None,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        let index = self.report.len;
+        if value.is_nan() {
+            self.report.nan_count += 1;
+        } else if value.is_infinite() {
+            self.report.infinite_count += 1;
+        }
+        if self.options.sentinel_values.contains(&value) {
+            self.report.sentinel_count += 1;
+        }
+        // NaN is never equal to the previous value so always starts a new run
+        if self.previous != Some(value) {
+            self.end_run(index);
+            self.run_start = index;
+        }
+        self.previous = Some(value);
+        self.report.len += 1;
+    }
+
+    fn end_run(&mut self, end: u64) {
+        let run_length = end - self.run_start;
+        self.report.longest_constant_run = self.report.longest_constant_run.max(run_length);
+        if self.options.min_constant_run > 0 && run_length >= self.options.min_constant_run {
+            self.report.constant_runs.push(self.run_start..end);
+        }
+    }
+
+    fn finish(mut self) -> QualityReport {
+        self.end_run(self.report.len);
+        self.report
+    }
+}
+
+impl<'a, R: Read + Seek> Channel<'a, R> {
+    /// Check the data of a floating point channel for NaN and infinite values,
+    /// sentinel values and runs of repeated values.
+    /// Data is processed incrementally so the full channel is never held in memory.
+    pub fn scan_quality(&self, options: &QualityOptions) -> Result<QualityReport> {
+        let mut scanner = QualityScanner::new(options);
+        match self.data_type().native_type() {
+            Some(NativeTypeId::F32) => {
+                for value in self.iter_values::<f32>()? {
+                    scanner.push(value? as f64);
+                }
+            }
+            Some(NativeTypeId::F64) => {
+                for value in self.iter_values::<f64>()? {
+                    scanner.push(value?);
+                }
+            }
+            _ => return Err(TdmsReadError::UnsupportedDataType(self.data_type())),
+        }
+        Ok(scanner.finish())
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs::File;
+use std::io::{Error, ErrorKind, Result};
+
+/// A source of bytes that can be read from at an arbitrary position without
+/// requiring mutable access or changing a shared seek position, so that
+/// multiple reads may happen concurrently.
+pub trait ReadAt {
+    /// Read bytes starting at the given position into the buffer,
+    /// returning the number of bytes read. Returns zero at the end of the data.
+    fn read_at(&self, position: u64, buf: &mut [u8]) -> Result<usize>;
+
+    /// Read the exact number of bytes required to fill the buffer
+    fn read_exact_at(&self, mut position: u64, mut buf: &mut [u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.read_at(position, buf) {
+                Ok(0) => {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    ))
+                }
+                Ok(n) => {
+                    buf = &mut buf[n..];
+                    position += n as u64;
+                }
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ReadAt for [u8] {
+    fn read_at(&self, position: u64, buf: &mut [u8]) -> Result<usize> {
+        if position >= self.len() as u64 {
+            return Ok(0);
+        }
+        let available = &self[position as usize..];
+        let length = available.len().min(buf.len());
+        buf[..length].copy_from_slice(&available[..length]);
+        Ok(length)
+    }
+}
+
+impl ReadAt for Vec<u8> {
+    fn read_at(&self, position: u64, buf: &mut [u8]) -> Result<usize> {
+        self.as_slice().read_at(position, buf)
+    }
+}
+
+impl<T: ReadAt + ?Sized> ReadAt for &T {
+    fn read_at(&self, position: u64, buf: &mut [u8]) -> Result<usize> {
+        (**self).read_at(position, buf)
+    }
+}
+
+#[cfg(unix)]
+impl ReadAt for File {
+    fn read_at(&self, position: u64, buf: &mut [u8]) -> Result<usize> {
+        std::os::unix::fs::FileExt::read_at(self, buf, position)
+    }
+}
+
+#[cfg(windows)]
+impl ReadAt for File {
+    fn read_at(&self, position: u64, buf: &mut [u8]) -> Result<usize> {
+        // Note that unlike pread, this does update the file's seek position
+        std::os::windows::fs::FileExt::seek_read(self, buf, position)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn read_from_slice() {
+        let bytes = vec![0u8, 1, 2, 3, 4, 5];
+        let mut buf = [0u8; 3];
+
+        let result = bytes.read_at(2, &mut buf);
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(buf, [2, 3, 4]);
+    }
+
+    #[test]
+    fn read_past_end_of_slice() {
+        let bytes = vec![0u8, 1, 2, 3, 4, 5];
+        let mut buf = [0u8; 4];
+
+        assert_eq!(bytes.read_at(4, &mut buf).unwrap(), 2);
+        assert_eq!(bytes.read_at(6, &mut buf).unwrap(), 0);
+        assert_eq!(bytes.read_at(10, &mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_exact_past_end_of_slice() {
+        let bytes = vec![0u8, 1, 2, 3, 4, 5];
+        let mut buf = [0u8; 4];
+
+        let result = bytes.read_exact_at(4, &mut buf);
+
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -2,16 +2,20 @@ use crate::error::{Result, TdmsReadError};
 use crate::interleaved::InterleavedReader;
 use crate::object_map::ObjectMap;
 use crate::object_path::ObjectPathId;
+use crate::read_at::ReadAt;
+use crate::tdms_reader::LeadIn;
 use crate::toc::{TocFlag, TocMask};
 use crate::types::{ByteOrderExt, NativeType, TdsType};
 use byteorder::{BigEndian, LittleEndian};
 use id_arena::{Arena, Id};
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom, Write};
 
 #[derive(Debug)]
 pub struct TdmsSegment {
     pub next_segment_position: u64,
     pub objects: Vec<SegmentObject>,
+    position: u64,
+    version: u32,
     toc_mask: TocMask,
     data_position: u64,
     data_size: u64,
@@ -20,7 +24,8 @@ pub struct TdmsSegment {
 
 impl TdmsSegment {
     pub fn new(
-        toc_mask: TocMask,
+        position: u64,
+        lead_in: &LeadIn,
         data_position: u64,
         next_segment_position: u64,
         objects: Vec<SegmentObject>,
@@ -29,7 +34,9 @@ impl TdmsSegment {
     ) -> TdmsSegment {
         // Compute expected datasize
         TdmsSegment {
-            toc_mask,
+            position,
+            version: lead_in.version,
+            toc_mask: lead_in.toc_mask,
             data_position,
             next_segment_position,
             objects,
@@ -38,130 +45,353 @@ impl TdmsSegment {
         }
     }
 
-    pub fn read_channel_data<R: Read + Seek, T: NativeType>(
-        &self,
-        reader: &mut R,
-        channel_id: ObjectPathId,
-        buffer: &mut [T],
-        raw_data_indexes: &Arena<RawDataIndex>,
-    ) -> Result<usize> {
-        let interleaved = self.toc_mask.has_flag(TocFlag::InterleavedData);
-        let big_endian = self.toc_mask.has_flag(TocFlag::BigEndian);
-        match (interleaved, big_endian) {
-            (false, false) => self.read_contiguous_channel_data::<_, _, LittleEndian>(
-                reader,
-                channel_id,
-                buffer,
-                raw_data_indexes,
-            ),
-            (false, true) => self.read_contiguous_channel_data::<_, _, BigEndian>(
-                reader,
-                channel_id,
-                buffer,
-                raw_data_indexes,
-            ),
-            (true, false) => self.read_interleaved_channel_data::<_, _, LittleEndian>(
-                reader,
-                channel_id,
-                buffer,
-                raw_data_indexes,
-            ),
-            (true, true) => self.read_interleaved_channel_data::<_, _, BigEndian>(
-                reader,
-                channel_id,
-                buffer,
-                raw_data_indexes,
-            ),
-        }
+    /// Position of the start of the segment lead in within the file
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// The TDMS format version number from the segment lead in
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Position of the start of the raw data within the file
+    pub fn data_position(&self) -> u64 {
+        self.data_position
     }
 
-    fn read_contiguous_channel_data<R: Read + Seek, T: NativeType, O: ByteOrderExt>(
+    /// Number of times the segment's data is repeated before the next segment
+    pub fn repetitions(&self) -> u64 {
+        self.repetitions
+    }
+
+    pub fn toc_mask(&self) -> TocMask {
+        self.toc_mask
+    }
+
+    /// Get the location of data for each channel with data in this segment,
+    /// given the index of this segment within the file
+    pub fn channel_data_locations(
         &self,
-        reader: &mut R,
-        channel_id: ObjectPathId,
-        buffer: &mut [T],
+        segment_index: usize,
         raw_data_indexes: &Arena<RawDataIndex>,
-    ) -> Result<usize> {
-        let mut channel_offset = 0;
+    ) -> Vec<(ObjectPathId, ChannelDataLocation)> {
+        let interleaved = self.toc_mask.has_flag(TocFlag::InterleavedData);
+        let mut data_offset = 0;
+        let mut locations = Vec::new();
         for obj in self.objects.iter() {
             if let Some(raw_data_index_id) = obj.raw_data_index {
                 let raw_data_index = raw_data_indexes.get(raw_data_index_id).unwrap();
-                if obj.object_id == channel_id {
-                    for repeat_idx in 0..self.repetitions {
-                        let data_offset = repeat_idx * self.data_size;
-                        let buffer_offset = (repeat_idx * raw_data_index.number_of_values) as usize;
-                        reader.seek(SeekFrom::Start(self.data_position + data_offset + channel_offset))?;
-                        T::read_values::<_, O>(
-                            &mut buffer[buffer_offset..],
-                            reader,
-                            raw_data_index.number_of_values as usize,
-                        )?;
-                    }
-                    return Ok((self.repetitions * raw_data_index.number_of_values) as usize);
+                locations.push((
+                    obj.object_id,
+                    ChannelDataLocation {
+                        segment_index,
+                        data_offset,
+                        number_of_values: raw_data_index.number_of_values,
+                        first_value_index: 0,
+                        raw_data_index: raw_data_index_id,
+                    },
+                ));
+                // Interleaved data with an unsized type is an error when reading,
+                // so the offset used in that case doesn't matter
+                data_offset += if interleaved {
+                    raw_data_index.data_type.size().unwrap_or(0) as u64
                 } else {
-                    channel_offset += raw_data_index.data_size;
-                }
+                    raw_data_index.data_size
+                };
             }
         }
-        Ok(0)
+        locations
     }
 
-    fn read_interleaved_channel_data<R: Read + Seek, T: NativeType, O: ByteOrderExt>(
+    /// Get the chunks of data for a channel within this segment, one per repetition of the
+    /// segment's data
+    pub fn channel_chunks(
         &self,
-        reader: &mut R,
-        channel_id: ObjectPathId,
-        buffer: &mut [T],
+        location: &ChannelDataLocation,
         raw_data_indexes: &Arena<RawDataIndex>,
-    ) -> Result<usize> {
+    ) -> Result<Vec<DataChunk>> {
+        let first_chunk = self.first_channel_chunk(location, raw_data_indexes)?;
+        Ok((0..self.repetitions)
+            .map(|repeat_idx| self.repeat_chunk(&first_chunk, repeat_idx))
+            .collect())
+    }
+
+    /// Get the chunk of data for a channel within a single repetition of the segment's data
+    pub fn channel_chunk(
+        &self,
+        location: &ChannelDataLocation,
+        repeat_idx: u64,
+        raw_data_indexes: &Arena<RawDataIndex>,
+    ) -> Result<DataChunk> {
+        let first_chunk = self.first_channel_chunk(location, raw_data_indexes)?;
+        Ok(self.repeat_chunk(&first_chunk, repeat_idx))
+    }
+
+    fn repeat_chunk(&self, first_chunk: &DataChunk, repeat_idx: u64) -> DataChunk {
+        DataChunk {
+            position: first_chunk.position + repeat_idx * self.data_size,
+            ..first_chunk.clone()
+        }
+    }
+
+    fn first_channel_chunk(
+        &self,
+        location: &ChannelDataLocation,
+        raw_data_indexes: &Arena<RawDataIndex>,
+    ) -> Result<DataChunk> {
+        let raw_data_index = raw_data_indexes.get(location.raw_data_index).unwrap();
+        let big_endian = self.toc_mask.has_flag(TocFlag::BigEndian);
+        if self.toc_mask.has_flag(TocFlag::InterleavedData) {
+            let type_size = raw_data_index
+                .data_type
+                .size()
+                .ok_or(TdmsReadError::UnsupportedDataType(raw_data_index.data_type))?;
+            let (chunk_width, length) = self.interleaved_dimensions(raw_data_indexes)?;
+            Ok(DataChunk {
+                position: self.data_position,
+                byte_length: length * chunk_width,
+                number_of_values: length,
+                big_endian,
+                layout: ChunkLayout::Interleaved {
+                    chunk_width: chunk_width as usize,
+                    type_size: type_size as usize,
+                    offset: location.data_offset as usize,
+                },
+            })
+        } else {
+            Ok(DataChunk {
+                position: self.data_position + location.data_offset,
+                byte_length: raw_data_index.data_size,
+                number_of_values: location.number_of_values,
+                big_endian,
+                layout: ChunkLayout::Contiguous,
+            })
+        }
+    }
+
+    /// Get the width of each row of interleaved data and the number of rows,
+    /// checking that all channels have sized types and the same length
+    fn interleaved_dimensions(&self, raw_data_indexes: &Arena<RawDataIndex>) -> Result<(u64, u64)> {
         let mut length = None;
-        let mut channel_params = None;
         let mut chunk_width = 0;
 
         for obj in self.objects.iter() {
             if let Some(raw_data_index_id) = obj.raw_data_index {
                 let raw_data_index = raw_data_indexes.get(raw_data_index_id).unwrap();
-                let type_size = raw_data_index.data_type.size().ok_or_else(|| {
-                    TdmsReadError::TdmsError(format!(
-                        "Cannot read unsized data type {:?} in interleaved data chunk",
-                        raw_data_index.data_type
-                    ))
-                })?;
+                let type_size = raw_data_index
+                    .data_type
+                    .size()
+                    .ok_or(TdmsReadError::UnsupportedDataType(raw_data_index.data_type))?;
                 match length {
                     None => length = Some(raw_data_index.number_of_values),
                     Some(length) => {
                         if raw_data_index.number_of_values != length {
-                            return Err(TdmsReadError::TdmsError(format!(
-                                "Different data lengths in interleaved data segment. Expected length {} but got {}",
-                                length, raw_data_index.number_of_values)));
+                            return Err(TdmsReadError::InterleavedLengthMismatch {
+                                expected: length,
+                                actual: raw_data_index.number_of_values,
+                            });
                         }
                     }
                 }
-                if obj.object_id == channel_id {
-                    channel_params = Some((type_size, chunk_width));
+                chunk_width += type_size as u64;
+            }
+        }
+        Ok((chunk_width, length.unwrap_or(0)))
+    }
+}
+
+/// The location of a channel's data within a segment
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelDataLocation {
+    /// Index of the segment within the file's segments
+    pub segment_index: usize,
+    /// Offset in bytes to the channel's data from the start of each chunk of segment data,
+    /// or from the start of each row for interleaved data
+    pub data_offset: u64,
+    /// Number of values for the channel in each chunk
+    pub number_of_values: u64,
+    /// Index within the channel of the first value in this segment
+    pub first_value_index: u64,
+    pub raw_data_index: RawDataIndexId,
+}
+
+/// How a channel's values are laid out within a chunk of raw data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkLayout {
+    /// Channel values are stored consecutively
+    Contiguous,
+    /// Values from all channels in the segment are interleaved
+    Interleaved {
+        /// Overall width of all channels in the chunk
+        chunk_width: usize,
+        /// Width in bytes of the channel data type
+        type_size: usize,
+        /// Offset within each row to the start of this channel's value
+        offset: usize,
+    },
+}
+
+/// The location of a contiguous region of raw data containing values for a channel
+#[derive(Debug, Clone)]
+pub struct DataChunk {
+    /// Absolute position of the start of the chunk within the file
+    pub position: u64,
+    /// Number of bytes that need to be read to decode the channel values
+    pub byte_length: u64,
+    /// Number of values for the channel within this chunk
+    pub number_of_values: u64,
+    pub big_endian: bool,
+    pub layout: ChunkLayout,
+}
+
+impl DataChunk {
+    /// Number of bytes between consecutive values of the channel in this chunk,
+    /// given the size of the channel's data type
+    pub fn value_spacing(&self, type_size: u64) -> u64 {
+        match self.layout {
+            ChunkLayout::Contiguous => type_size,
+            ChunkLayout::Interleaved { chunk_width, .. } => chunk_width as u64,
+        }
+    }
+
+    /// Get a chunk containing only the value at the given index within this chunk,
+    /// given the size of the channel's data type
+    pub fn value_chunk(&self, index: u64, type_size: u64) -> DataChunk {
+        self.sub_chunk(index, 1, type_size)
+    }
+
+    /// Get a chunk containing a range of values within this chunk,
+    /// given the size of the channel's data type
+    pub fn sub_chunk(&self, start: u64, number_of_values: u64, type_size: u64) -> DataChunk {
+        let value_spacing = self.value_spacing(type_size);
+        DataChunk {
+            position: self.position + start * value_spacing,
+            byte_length: number_of_values * value_spacing,
+            number_of_values,
+            big_endian: self.big_endian,
+            layout: self.layout,
+        }
+    }
+
+    /// Split this chunk into chunks with at most `max_values` values each,
+    /// given the size of the channel's data type
+    pub fn split(&self, max_values: u64, type_size: u64) -> impl Iterator<Item = DataChunk> + '_ {
+        (0..self.number_of_values)
+            .step_by(max_values as usize)
+            .map(move |start| {
+                let number_of_values = max_values.min(self.number_of_values - start);
+                self.sub_chunk(start, number_of_values, type_size)
+            })
+    }
+
+    /// Read the channel values in this chunk from a seekable reader into the start of the buffer,
+    /// returning the number of values read
+    pub fn read_values<R: Read + Seek, T: NativeType>(
+        &self,
+        reader: &mut R,
+        buffer: &mut [T],
+    ) -> Result<usize> {
+        reader.seek(SeekFrom::Start(self.position))?;
+        match self.layout {
+            ChunkLayout::Contiguous => {
+                if self.big_endian {
+                    T::read_values::<_, BigEndian>(buffer, reader, self.number_of_values as usize)?;
+                } else {
+                    T::read_values::<_, LittleEndian>(
+                        buffer,
+                        reader,
+                        self.number_of_values as usize,
+                    )?;
                 }
-                chunk_width += type_size;
+                Ok(self.number_of_values as usize)
+            }
+            ChunkLayout::Interleaved { .. } => {
+                let mut bytes = vec![0; self.byte_length as usize];
+                reader.read_exact(&mut bytes)?;
+                self.decode_values(&bytes, buffer)
             }
         }
+    }
 
-        if let (Some((type_size, channel_offset)), Some(length)) = (channel_params, length) {
-            for repeat_idx in 0..self.repetitions {
-                let data_offset = repeat_idx * self.data_size;
-                let buffer_offset = (repeat_idx * length) as usize;
-                let mut chunk = vec![0; (length as usize) * (chunk_width as usize)];
-                reader.seek(SeekFrom::Start(self.data_position + data_offset))?;
-                reader.read_exact(&mut chunk)?;
-                let mut interleaved_reader = InterleavedReader::new(
-                    &chunk,
-                    chunk_width as usize,
-                    type_size as usize,
-                    channel_offset as usize,
-                );
-                T::read_values::<_, O>(&mut buffer[buffer_offset..], &mut interleaved_reader, length as usize)?;
+    /// Copy the undecoded bytes of the channel values in this chunk from a seekable reader
+    /// to a writer, returning the number of bytes written. For interleaved data, only the
+    /// bytes of this channel's values are copied.
+    pub fn copy_raw_bytes<R: Read + Seek, W: Write>(
+        &self,
+        reader: &mut R,
+        writer: &mut W,
+    ) -> Result<u64> {
+        reader.seek(SeekFrom::Start(self.position))?;
+        match self.layout {
+            ChunkLayout::Contiguous => {
+                let copied = std::io::copy(&mut reader.take(self.byte_length), writer)?;
+                if copied < self.byte_length {
+                    return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+                }
+                Ok(copied)
             }
-            Ok((length * self.repetitions) as usize)
+            ChunkLayout::Interleaved {
+                chunk_width,
+                type_size,
+                offset,
+            } => {
+                let mut bytes = vec![0; self.byte_length as usize];
+                reader.read_exact(&mut bytes)?;
+                let values = bytes
+                    .chunks_exact(chunk_width)
+                    .flat_map(|row| &row[offset..offset + type_size])
+                    .copied()
+                    .collect::<Vec<u8>>();
+                writer.write_all(&values)?;
+                Ok(values.len() as u64)
+            }
+        }
+    }
+
+    /// Read the channel values in this chunk from a positioned reader into the start of the
+    /// buffer, returning the number of values read
+    pub fn read_values_at<S: ReadAt + ?Sized, T: NativeType>(
+        &self,
+        source: &S,
+        buffer: &mut [T],
+    ) -> Result<usize> {
+        let mut bytes = vec![0; self.byte_length as usize];
+        source.read_exact_at(self.position, &mut bytes)?;
+        self.decode_values(&bytes, buffer)
+    }
+
+    /// Decode the channel values in this chunk from the chunk's raw bytes into the start of the
+    /// buffer, returning the number of values decoded
+    pub fn decode_values<T: NativeType>(&self, bytes: &[u8], buffer: &mut [T]) -> Result<usize> {
+        if self.big_endian {
+            self.decode_values_with_order::<T, BigEndian>(bytes, buffer)
         } else {
-            Ok(0)
+            self.decode_values_with_order::<T, LittleEndian>(bytes, buffer)
+        }
+    }
+
+    fn decode_values_with_order<T: NativeType, O: ByteOrderExt>(
+        &self,
+        bytes: &[u8],
+        buffer: &mut [T],
+    ) -> Result<usize> {
+        let num_values = self.number_of_values as usize;
+        match self.layout {
+            ChunkLayout::Contiguous => {
+                T::decode_values::<O>(buffer, bytes, num_values)?;
+            }
+            ChunkLayout::Interleaved {
+                chunk_width,
+                type_size,
+                offset,
+            } => {
+                let mut interleaved_reader =
+                    InterleavedReader::new(bytes, chunk_width, type_size, offset);
+                T::read_values::<_, O>(buffer, &mut interleaved_reader, num_values)?;
+            }
         }
+        Ok(num_values)
     }
 }
 
@@ -189,7 +419,10 @@ impl SegmentObject {
 
 #[derive(Debug)]
 pub struct RawDataIndex {
+    /// Total number of values, including all elements of array values
     pub number_of_values: u64,
+    /// Number of elements in each array value, which is 1 for scalar values
+    pub dimension: u32,
     pub data_type: TdsType,
     pub data_size: u64,
 }
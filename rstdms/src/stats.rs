@@ -0,0 +1,70 @@
+/// Statistics describing how data is laid out in a TDMS file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileStats {
+    /// Number of segments in the file
+    pub segment_count: usize,
+    /// Number of segments with interleaved raw data
+    pub interleaved_segment_count: usize,
+    /// Number of segments with contiguous raw data
+    pub contiguous_segment_count: usize,
+    /// Total bytes used by segment lead ins and metadata
+    pub metadata_bytes: u64,
+    /// Total bytes of raw data
+    pub data_bytes: u64,
+    /// Statistics for each channel with data
+    pub channels: Vec<ChannelStats>,
+    /// A measure of how fragmented channel data is, between 0 and 1.
+    /// This is zero when the data for each channel is stored in a single chunk,
+    /// and approaches one as channel data is split into many small chunks.
+    pub fragmentation: f64,
+}
+
+/// Statistics for the data of a single channel
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelStats {
+    pub group_name: String,
+    pub channel_name: String,
+    /// Number of separate chunks the channel data is split into
+    pub chunk_count: usize,
+    /// Total number of values in the channel
+    pub number_of_values: u64,
+}
+
+/// Describes the layout of a single segment of a TDMS file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentInfo {
+    /// Position of the start of the segment lead in within the file
+    pub position: u64,
+    /// Position of the start of the raw data within the file
+    pub data_position: u64,
+    /// Position of the start of the next segment, or the end of the file
+    /// for an incomplete final segment
+    pub next_segment_position: u64,
+    /// The TDMS format version number from the segment lead in
+    pub version: u32,
+    /// The table of contents flags from the segment lead in
+    pub toc_flags: u32,
+    /// Number of objects in the segment's object list, including objects without data
+    pub object_count: usize,
+    /// Number of times the segment's data is repeated
+    pub repetitions: u64,
+}
+
+/// Describes the chunks of data for a channel within a single segment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentChunks {
+    /// Index of the segment within the file
+    pub segment_index: usize,
+    /// Position of the start of the segment within the file
+    pub segment_position: u64,
+    /// Position of the first chunk of channel data within the file
+    pub data_position: u64,
+    /// Number of values for the channel in each chunk
+    pub number_of_values: u64,
+    /// Number of chunks, which is the number of times the segment's data is repeated
+    pub repetitions: u64,
+    /// Whether the data is stored in big endian byte order
+    pub big_endian: bool,
+    /// Whether the data is interleaved with data from other channels
+    pub interleaved: bool,
+}
@@ -0,0 +1,186 @@
+use crate::channel_data::ChannelData;
+use crate::error::{Result, TdmsReadError};
+use crate::object_path::{path_from_channel, path_from_group, ObjectPath};
+use crate::options::ReadOptions;
+use crate::properties::TdmsProperty;
+use crate::tdms_reader::{read_lead_in, TdmsReader, LEAD_IN_LENGTH};
+use crate::types::read_bytes_into;
+use std::collections::VecDeque;
+use std::io::{BufReader, Cursor, Read};
+
+/// Reads TDMS data from a forward-only stream such as a socket or stdin,
+/// which does not need to support seeking.
+///
+/// Segments are processed in order, and data for each channel in a segment is returned
+/// as a [`ChannelDataChunk`] from the iterator implementation. The raw data of each segment
+/// is read into memory before being decoded.
+pub struct TdmsStreamReader<R: Read> {
+    reader: BufReader<R>,
+    position: u64,
+    tdms_reader: TdmsReader,
+    metadata_bytes: Vec<u8>,
+    segment_data: Vec<u8>,
+    pending_chunks: VecDeque<ChannelDataChunk>,
+    finished: bool,
+}
+
+/// Data for a channel read from a single segment
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelDataChunk {
+    pub group_name: String,
+    pub channel_name: String,
+    pub data: ChannelData,
+}
+
+impl<R: Read> TdmsStreamReader<R> {
+    /// Create a new stream reader. No data is read until the iterator is advanced.
+    pub fn new(reader: R) -> TdmsStreamReader<R> {
+        TdmsStreamReader::with_options(reader, &ReadOptions::default())
+    }
+
+    /// Create a new stream reader with the given options
+    pub fn with_options(reader: R, options: &ReadOptions) -> TdmsStreamReader<R> {
+        TdmsStreamReader {
+            reader: BufReader::with_capacity(options.reader_buffer_capacity(), reader),
+            position: 0,
+            tdms_reader: TdmsReader::new(options),
+            metadata_bytes: Vec::new(),
+            segment_data: Vec::new(),
+            pending_chunks: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Get properties of the root object read so far
+    pub fn properties(&self) -> &Vec<TdmsProperty> {
+        self.tdms_reader.root_properties()
+    }
+
+    /// Get warnings about problems that were recovered from when reading metadata so far
+    pub fn warnings(&self) -> &[String] {
+        self.tdms_reader.warnings()
+    }
+
+    /// Get properties of a group read so far, if the group has been found
+    pub fn group_properties(&self, group_name: &str) -> Option<&Vec<TdmsProperty>> {
+        self.tdms_reader
+            .get_object_id(&path_from_group(group_name))
+            .map(|object_id| self.tdms_reader.get_properties(object_id))
+    }
+
+    /// Get properties of a channel read so far, if the channel has been found
+    pub fn channel_properties(
+        &self,
+        group_name: &str,
+        channel_name: &str,
+    ) -> Option<&Vec<TdmsProperty>> {
+        self.tdms_reader
+            .get_object_id(&path_from_channel(group_name, channel_name))
+            .map(|object_id| self.tdms_reader.get_properties(object_id))
+    }
+
+    /// Read the next segment, queueing data chunks for all channels in the segment.
+    /// Returns false if the end of the stream has been reached.
+    fn read_next_segment(&mut self) -> Result<bool> {
+        let position = self.position;
+        let lead_in = match read_lead_in(&mut self.reader, position)? {
+            Some(lead_in) => lead_in,
+            None => return Ok(false),
+        };
+
+        // Read metadata into memory so that the length of the raw data is known before parsing
+        // it when the segment is incomplete and the data extends to the end of the stream
+        // The metadata size comes from the stream so isn't trusted, and the metadata buffer only
+        // grows as bytes are actually read
+        read_bytes_into(
+            &mut self.reader,
+            lead_in.raw_data_offset,
+            &mut self.metadata_bytes,
+        )?;
+
+        let raw_data_position = position + LEAD_IN_LENGTH as u64 + lead_in.raw_data_offset;
+        if lead_in.is_incomplete() {
+            self.segment_data.clear();
+            match self.tdms_reader.memory_limit() {
+                Some(limit) => {
+                    // Read at most one byte more than the limit to detect exceeding it
+                    (&mut self.reader)
+                        .take(limit.saturating_add(1))
+                        .read_to_end(&mut self.segment_data)?;
+                    self.tdms_reader
+                        .check_memory_limit(self.segment_data.len() as u64)?;
+                }
+                None => {
+                    self.reader.read_to_end(&mut self.segment_data)?;
+                }
+            }
+        } else {
+            let raw_data_length = lead_in
+                .next_segment_offset
+                .saturating_sub(lead_in.raw_data_offset);
+            self.tdms_reader.check_memory_limit(raw_data_length)?;
+            read_bytes_into(&mut self.reader, raw_data_length, &mut self.segment_data)?;
+        }
+        let stream_length = raw_data_position + self.segment_data.len() as u64;
+
+        self.position = self.tdms_reader.add_segment(
+            &lead_in,
+            position,
+            &mut Cursor::new(&self.metadata_bytes),
+            stream_length,
+        )?;
+        self.tdms_reader.retain_last_segment();
+
+        for (channel_id, chunks) in self.tdms_reader.last_segment_chunks()? {
+            let (group_name, channel_name) = match self.tdms_reader.get_object_path(channel_id) {
+                Some(ObjectPath::Channel(group_name, channel_name)) => {
+                    (group_name.clone(), channel_name.clone())
+                }
+                _ => continue,
+            };
+            let num_values = chunks.iter().map(|c| c.number_of_values).sum::<u64>();
+            let mut data = ChannelData::with_len(
+                self.tdms_reader.channel_data_type(channel_id),
+                num_values as usize,
+            )?;
+            let mut offset = 0;
+            for chunk in chunks {
+                let chunk_start = (chunk.position - raw_data_position) as usize;
+                let chunk_end = chunk_start + chunk.byte_length as usize;
+                if chunk_end > self.segment_data.len() {
+                    return Err(TdmsReadError::SegmentDataSizeMismatch {
+                        position,
+                        data_size: chunk_end as u64,
+                        segment_data_size: self.segment_data.len() as u64,
+                    });
+                }
+                offset +=
+                    data.decode_chunk(&chunk, &self.segment_data[chunk_start..chunk_end], offset)?;
+            }
+            self.pending_chunks.push_back(ChannelDataChunk {
+                group_name,
+                channel_name,
+                data,
+            });
+        }
+        Ok(true)
+    }
+}
+
+impl<R: Read> Iterator for TdmsStreamReader<R> {
+    type Item = Result<ChannelDataChunk>;
+
+    fn next(&mut self) -> Option<Result<ChannelDataChunk>> {
+        while self.pending_chunks.is_empty() && !self.finished {
+            match self.read_next_segment() {
+                Ok(true) => {}
+                Ok(false) => self.finished = true,
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        self.pending_chunks.pop_front().map(Ok)
+    }
+}
@@ -1,31 +1,128 @@
+use crate::channel_data::ChannelData;
 use crate::error::{Result, TdmsReadError};
 use crate::object_map::ObjectMap;
 use crate::object_path::{ObjectPath, ObjectPathCache, ObjectPathId};
-use crate::properties::TdmsProperty;
-use crate::segment::{RawDataIndex, RawDataIndexCache, SegmentObject, TdmsSegment};
-use crate::toc::{TocFlag, TocMask};
-use crate::types::{read_string, ByteOrderExt, NativeType, TdsType};
-use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use crate::options::{ProgressCallback, ReadOptions, ReadProgress};
+use crate::properties::{PropertyRevision, TdmsProperty, TdmsValue};
+use crate::property_names::{WF_INCREMENT, WF_START_TIME};
+use crate::read_at::ReadAt;
+use crate::segment::{
+    ChannelDataLocation, ChunkLayout, DataChunk, RawDataIndex, RawDataIndexCache, SegmentObject,
+    TdmsSegment,
+};
+use crate::stats::{ChannelStats, FileStats, SegmentChunks, SegmentInfo};
+use crate::timestamp::Timestamp;
+use crate::toc::{FormatInfo, TocFlag, TocMask, TDMS_VERSION_1_0, TDMS_VERSION_2_0};
+use crate::types::{read_string, ByteOrderExt, NativeType, NativeTypeId, StringDecoder, TdsType};
+use crate::waveform::WaveformTiming;
+use byteorder::{BigEndian, ByteOrder, LittleEndian, ReadBytesExt};
 use id_arena::Arena;
-use std::collections::HashMap;
-use std::io::{Read, Seek, SeekFrom};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Seek, SeekFrom, Take, Write};
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-const RAW_DATA_INDEX_NO_DATA: u32 = 0xFFFFFFFF;
+pub const RAW_DATA_INDEX_NO_DATA: u32 = 0xFFFFFFFF;
 const RAW_DATA_INDEX_MATCHES_PREVIOUS: u32 = 0x00000000;
 const FORMAT_CHANGING_SCALER: u32 = 0x00001269;
 const DIGITAL_LINE_SCALER: u32 = 0x0000126A;
 
-pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<TdmsReader> {
-    let mut tdms_reader = TdmsReader::new();
+/// Minimum metadata size of an object: path length, raw data index header and number of properties
+const MIN_OBJECT_SIZE: u64 = 12;
+/// Minimum metadata size of a property: name length and type id
+const MIN_PROPERTY_SIZE: u64 = 8;
+/// Minimum number of bytes between values for a decimated read to read values individually
+/// rather than reading all data in a chunk
+const MIN_DECIMATION_SKIP_BYTES: u64 = 4096;
+/// Maximum number of values of a fixed size type to read into memory at once
+/// when processing channel data incrementally
+const MAX_VALUES_PER_READ: u64 = 64 * 1024;
+/// Maximum number of bytes of raw data to read into memory at once when reading
+/// multiple chunks together, unless a single contiguous chunk is larger
+const MAX_READ_SPAN_BYTES: u64 = 16 * 1024 * 1024;
+
+static EMPTY_PROPERTIES: Vec<TdmsProperty> = Vec::new();
+
+pub const LEAD_IN_LENGTH: usize = 28;
+
+/// Tag at the start of each segment of a TDMS file
+pub const SEGMENT_TAG: &[u8; 4] = b"TDSm";
+/// Tag at the start of each segment of a TDMS index file
+pub const INDEX_SEGMENT_TAG: &[u8; 4] = b"TDSh";
+
+/// Next segment offset written by LabVIEW before a segment is finalized,
+/// which remains if the writer crashed before the lead in could be updated
+const INCOMPLETE_SEGMENT_OFFSET: u64 = 0xFFFFFFFFFFFFFFFF;
+
+pub fn read_metadata<R: Read + Seek>(reader: &mut R, options: &ReadOptions) -> Result<TdmsReader> {
+    let mut tdms_reader = TdmsReader::new(options);
     match tdms_reader.read_segments(reader) {
         Ok(()) => Ok(tdms_reader),
         Err(e) => Err(e),
     }
 }
 
+/// Read metadata from a TDMS index file, for a data file of the given length
+pub fn read_index_metadata<R: Read + Seek>(
+    index_reader: &mut R,
+    file_length: u64,
+    options: &ReadOptions,
+) -> Result<TdmsReader> {
+    let mut tdms_reader = TdmsReader::new(options);
+    tdms_reader.read_index_segments(index_reader, file_length)?;
+    Ok(tdms_reader)
+}
+
+/// The fixed size lead in at the start of each segment
+pub struct LeadIn {
+    pub toc_mask: TocMask,
+    pub version: u32,
+    pub next_segment_offset: u64,
+    pub raw_data_offset: u64,
+}
+
+impl LeadIn {
+    /// Parse the lead in bytes of a segment starting at the given position in the file,
+    /// checking that it starts with the expected tag
+    pub fn parse(bytes: &[u8; LEAD_IN_LENGTH], position: u64, tag: &[u8; 4]) -> Result<LeadIn> {
+        // Check segment header
+        if &bytes[0..4] != tag {
+            return Err(TdmsReadError::InvalidSegmentHeader {
+                position,
+                header: [bytes[0], bytes[1], bytes[2], bytes[3]],
+            });
+        }
+
+        // The ToC mask is always little endian
+        let toc_mask = TocMask::from_flags(LittleEndian::read_u32(&bytes[4..8]));
+
+        if toc_mask.has_flag(TocFlag::BigEndian) {
+            Ok(LeadIn::parse_remaining::<BigEndian>(bytes, toc_mask))
+        } else {
+            Ok(LeadIn::parse_remaining::<LittleEndian>(bytes, toc_mask))
+        }
+    }
+
+    /// Whether the segment was not finalized, so the next segment offset is unknown
+    /// and the segment extends to the end of the file
+    pub fn is_incomplete(&self) -> bool {
+        self.next_segment_offset == INCOMPLETE_SEGMENT_OFFSET
+    }
+
+    fn parse_remaining<O: ByteOrder>(bytes: &[u8; LEAD_IN_LENGTH], toc_mask: TocMask) -> LeadIn {
+        LeadIn {
+            toc_mask,
+            version: O::read_u32(&bytes[8..12]),
+            next_segment_offset: O::read_u64(&bytes[12..20]),
+            raw_data_offset: O::read_u64(&bytes[20..28]),
+        }
+    }
+}
+
 pub struct ChannelDataIndex {
     pub number_of_values: u64,
     pub data_type: TdsType,
+    pub dimension: u32,
 }
 
 impl ChannelDataIndex {
@@ -33,6 +130,7 @@ impl ChannelDataIndex {
         ChannelDataIndex {
             data_type: index.data_type,
             number_of_values: index.number_of_values * repetitions,
+            dimension: index.dimension,
         }
     }
 
@@ -40,10 +138,16 @@ impl ChannelDataIndex {
         // We have data in this segment for an object that already had data in a
         // previous segment, check the raw data index is compatible.
         if index.data_type != self.data_type {
-            return Err(TdmsReadError::TdmsError(format!(
-                "Data type {:?} does not match existing data type {:?}",
-                index.data_type, self.data_type
-            )));
+            return Err(TdmsReadError::DataIndexMismatch {
+                data_type: index.data_type,
+                existing_data_type: self.data_type,
+            });
+        }
+        if index.dimension != self.dimension {
+            return Err(TdmsReadError::DimensionMismatch {
+                dimension: index.dimension,
+                existing_dimension: self.dimension,
+            });
         }
         self.number_of_values += index.number_of_values * repetitions;
         Ok(())
@@ -54,25 +158,67 @@ type ChannelDataIndexMap = ObjectMap<ChannelDataIndex>;
 
 pub struct TdmsReader {
     pub properties: HashMap<ObjectPathId, Vec<TdmsProperty>>,
+    /// Index of each property of an object within its properties, by property name
+    property_indexes: HashMap<ObjectPathId, HashMap<String, usize>>,
+    /// Every value each property of an object has been set to, by property name,
+    /// if property history is enabled
+    property_history: Option<HashMap<ObjectPathId, HashMap<String, Vec<PropertyRevision>>>>,
     object_paths: ObjectPathCache,
     data_indexes: Arena<RawDataIndex>,
     raw_data_index_cache: RawDataIndexCache,
     segments: Vec<TdmsSegment>,
     channel_data_index_map: ChannelDataIndexMap,
+    channel_segments: ObjectMap<Vec<ChannelDataLocation>>,
+    object_merger: ObjectMerger,
+    string_decoder: StringDecoder,
+    num_segments_read: usize,
+    /// Position of the segment following the most recently read segment
+    next_segment_position: u64,
+    /// Whether a segment without a next segment offset has been read, which extends to
+    /// the end of the file so that no later segments can be found
+    incomplete_segment_read: bool,
+    warnings: Vec<String>,
+    progress_callback: Option<ProgressCallback>,
+    memory_limit: Option<u64>,
+    /// Whether to ignore the new object list flag in TDMS 1.0 segments
+    merge_tdms_1_0_object_lists: bool,
+    /// Changes to the waveform start time of each channel, with the index of the
+    /// first value the start time applies to
+    waveform_starts: ObjectMap<Vec<(u64, Timestamp)>>,
+    chunks_decoded: AtomicU64,
 }
 
 impl TdmsReader {
-    fn new() -> TdmsReader {
+    pub fn new(options: &ReadOptions) -> TdmsReader {
         TdmsReader {
             properties: HashMap::new(),
+            property_indexes: HashMap::new(),
+            property_history: options.property_history.then(HashMap::new),
             object_paths: ObjectPathCache::new(),
             data_indexes: Arena::<RawDataIndex>::new(),
             raw_data_index_cache: RawDataIndexCache::new(),
             segments: Vec::new(),
             channel_data_index_map: ChannelDataIndexMap::new(),
+            channel_segments: ObjectMap::new(),
+            object_merger: ObjectMerger::new(),
+            string_decoder: StringDecoder::new(options),
+            num_segments_read: 0,
+            next_segment_position: 0,
+            incomplete_segment_read: false,
+            warnings: Vec::new(),
+            progress_callback: options.progress_callback.clone(),
+            memory_limit: options.memory_limit,
+            merge_tdms_1_0_object_lists: options.merge_tdms_1_0_object_lists,
+            waveform_starts: ObjectMap::new(),
+            chunks_decoded: AtomicU64::new(0),
         }
     }
 
+    /// Warnings about problems that were recovered from while reading metadata
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     pub fn get_object_id(&self, path: &str) -> Option<ObjectPathId> {
         self.object_paths.get_id(path)
     }
@@ -81,6 +227,13 @@ impl TdmsReader {
         self.object_paths.get_path(object_path_id)
     }
 
+    /// Get the path of an object as a string, as used in error context
+    pub fn object_path_string(&self, object_path_id: ObjectPathId) -> String {
+        self.get_object_path(object_path_id)
+            .map(|path| path.to_string())
+            .unwrap_or_default()
+    }
+
     pub fn objects(&self) -> impl Iterator<Item = (ObjectPathId, &ObjectPath)> {
         self.object_paths.objects()
     }
@@ -89,106 +242,1132 @@ impl TdmsReader {
         self.channel_data_index_map.get(object_id)
     }
 
-    pub fn read_channel_data<R: Read + Seek, T: NativeType>(
+    /// Get the properties of the root object
+    pub fn root_properties(&self) -> &Vec<TdmsProperty> {
+        match self.get_object_id("/") {
+            Some(object_id) => self.get_properties(object_id),
+            None => &EMPTY_PROPERTIES,
+        }
+    }
+
+    /// Get the data type of a channel, or Void if the channel has no data
+    pub fn channel_data_type(&self, object_id: ObjectPathId) -> TdsType {
+        match self.get_channel_data_index(object_id) {
+            Some(channel_data_index) => channel_data_index.data_type,
+            None => TdsType::Void,
+        }
+    }
+
+    /// Get the number of elements in each value of a channel, which is 1 for scalar values
+    pub fn channel_dimension(&self, object_id: ObjectPathId) -> u32 {
+        match self.get_channel_data_index(object_id) {
+            Some(channel_data) => channel_data.dimension,
+            None => 1,
+        }
+    }
+
+    /// Get the total number of values in a channel
+    pub fn channel_len(&self, object_id: ObjectPathId) -> u64 {
+        match self.get_channel_data_index(object_id) {
+            Some(channel_data) => channel_data.number_of_values,
+            None => 0,
+        }
+    }
+
+    /// Estimate the number of bytes of memory required to hold all data for a channel
+    pub fn channel_memory_size(&self, object_id: ObjectPathId) -> u64 {
+        self.values_memory_size(object_id, self.channel_len(object_id))
+    }
+
+    /// Estimate the number of bytes of memory required to hold `len` values of a channel.
+    /// For strings, the values are assumed to have the average length of the channel's strings.
+    pub fn values_memory_size(&self, object_id: ObjectPathId, len: u64) -> u64 {
+        let native_type = match self.channel_data_type(object_id).native_type() {
+            Some(native_type) => native_type,
+            None => return 0,
+        };
+        let value_size = len.saturating_mul(native_type.size_in_memory() as u64);
+        if native_type == NativeTypeId::String {
+            // String contents are allocated separately, and the raw data
+            // includes a 4 byte offset for each string
+            let channel_len = self.channel_len(object_id);
+            let string_bytes = self
+                .channel_raw_data_size(object_id)
+                .saturating_sub(channel_len.saturating_mul(4));
+            let string_bytes = if len >= channel_len {
+                string_bytes
+            } else {
+                (string_bytes as u128 * len as u128 / channel_len as u128) as u64
+            };
+            value_size.saturating_add(string_bytes)
+        } else {
+            value_size
+        }
+    }
+
+    /// Check that allocating `len` values of a channel is within the memory limit
+    pub fn check_values_memory_limit(&self, object_id: ObjectPathId, len: u64) -> Result<()> {
+        self.check_memory_limit(self.values_memory_size(object_id, len))
+    }
+
+    /// Check that a range of `len` values starting at `start` is within a channel
+    pub fn check_channel_range(
+        &self,
+        channel_id: ObjectPathId,
+        start: u64,
+        len: u64,
+    ) -> Result<()> {
+        let length = self.channel_len(channel_id);
+        match start.checked_add(len) {
+            Some(end) if end <= length => Ok(()),
+            end => Err(TdmsReadError::RangeOutOfBounds {
+                start,
+                end: end.unwrap_or(u64::MAX),
+                length,
+            }),
+        }
+    }
+
+    /// Get the total size in bytes of the raw data for a channel within the file
+    pub fn channel_raw_data_size(&self, object_id: ObjectPathId) -> u64 {
+        self.channel_data_locations(object_id)
+            .iter()
+            .map(|location| {
+                let segment = &self.segments[location.segment_index];
+                let raw_data_index = &self.data_indexes[location.raw_data_index];
+                raw_data_index
+                    .data_size
+                    .saturating_mul(segment.repetitions())
+            })
+            .fold(0, u64::saturating_add)
+    }
+
+    /// The maximum number of bytes of memory that may be allocated for data, if limited
+    pub fn memory_limit(&self) -> Option<u64> {
+        self.memory_limit
+    }
+
+    /// Record that a chunk of channel data has been read and decoded
+    pub fn chunk_decoded(&self) {
+        self.chunks_decoded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the number of chunks of channel data that have been read and decoded
+    pub fn chunks_decoded(&self) -> u64 {
+        self.chunks_decoded.load(Ordering::Relaxed)
+    }
+
+    /// Get the number of segments whose metadata has been read
+    pub fn segments_read(&self) -> usize {
+        self.num_segments_read
+    }
+
+    /// Check that allocating the given number of bytes is within the memory limit
+    pub fn check_memory_limit(&self, required: u64) -> Result<()> {
+        match self.memory_limit {
+            Some(limit) if required > limit => {
+                Err(TdmsReadError::MemoryLimitExceeded { required, limit })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the properties of an object
+    pub fn get_properties(&self, object_id: ObjectPathId) -> &Vec<TdmsProperty> {
+        match self.properties.get(&object_id) {
+            Some(properties) => properties,
+            None => &EMPTY_PROPERTIES,
+        }
+    }
+
+    /// Get the name of a group object
+    pub fn group_name(&self, object_id: ObjectPathId) -> &str {
+        let group_path = self.get_object_path(object_id).unwrap();
+        match group_path {
+            ObjectPath::Group(ref group_name) => group_name,
+            _ => panic!(
+                "Expected a group path for object id {:?}, got {:?}",
+                object_id, group_path
+            ),
+        }
+    }
+
+    /// Get the name of a channel object
+    pub fn channel_name(&self, object_id: ObjectPathId) -> &str {
+        let channel_path = self.get_object_path(object_id).unwrap();
+        match channel_path {
+            ObjectPath::Channel(_, ref channel_name) => channel_name,
+            _ => panic!(
+                "Expected a channel path for object id {:?}, got {:?}",
+                object_id, channel_path
+            ),
+        }
+    }
+
+    /// Get the ids of all group objects
+    pub fn group_ids(&self) -> Vec<ObjectPathId> {
+        self.objects()
+            .filter(|(_, path)| matches!(path, ObjectPath::Group(_)))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Get the ids of all channel objects within a group
+    pub fn channel_ids(&self, group_name: &str) -> Vec<ObjectPathId> {
+        self.objects()
+            .filter(|(_, path)| matches!(path, ObjectPath::Channel(g, _) if g == group_name))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Get the number of channel objects within a group
+    pub fn channel_count(&self, group_name: &str) -> usize {
+        self.objects()
+            .filter(|(_, path)| matches!(path, ObjectPath::Channel(g, _) if g == group_name))
+            .count()
+    }
+
+    /// Get the format versions and the union of ToC flags of all segments
+    pub fn format_info(&self) -> FormatInfo {
+        let mut info = FormatInfo {
+            versions: Vec::new(),
+            big_endian: false,
+            interleaved: false,
+            daqmx: false,
+        };
+        for segment in self.segments.iter() {
+            if !info.versions.contains(&segment.version()) {
+                info.versions.push(segment.version());
+            }
+            let toc_mask = segment.toc_mask();
+            info.big_endian |= toc_mask.has_flag(TocFlag::BigEndian);
+            info.interleaved |= toc_mask.has_flag(TocFlag::InterleavedData);
+            info.daqmx |= toc_mask.has_flag(TocFlag::DaqMxRawData);
+        }
+        info.versions.sort_unstable();
+        info
+    }
+
+    /// Compute statistics describing the segments and layout of channel data
+    pub fn stats(&self) -> FileStats {
+        let mut stats = FileStats {
+            segment_count: self.segments.len(),
+            interleaved_segment_count: 0,
+            contiguous_segment_count: 0,
+            metadata_bytes: 0,
+            data_bytes: 0,
+            channels: Vec::new(),
+            fragmentation: 0.0,
+        };
+        let mut chunk_counts = ObjectMap::<usize>::new();
+        for segment in self.segments.iter() {
+            if segment.toc_mask().has_flag(TocFlag::InterleavedData) {
+                stats.interleaved_segment_count += 1;
+            } else {
+                stats.contiguous_segment_count += 1;
+            }
+            stats.metadata_bytes += segment.data_position() - segment.position();
+            stats.data_bytes += segment.next_segment_position - segment.data_position();
+            for obj in segment.objects.iter() {
+                if let Some(raw_data_index_id) = obj.raw_data_index {
+                    if self.data_indexes[raw_data_index_id].number_of_values == 0 {
+                        continue;
+                    }
+                    let repetitions = segment.repetitions() as usize;
+                    match chunk_counts.get_mut(obj.object_id) {
+                        Some(chunk_count) => *chunk_count += repetitions,
+                        None => chunk_counts.set(obj.object_id, repetitions),
+                    }
+                }
+            }
+        }
+        for (object_id, path) in self.objects() {
+            if let (ObjectPath::Channel(group_name, channel_name), Some(chunk_count)) =
+                (path, chunk_counts.get(object_id))
+            {
+                stats.channels.push(ChannelStats {
+                    group_name: group_name.clone(),
+                    channel_name: channel_name.clone(),
+                    chunk_count: *chunk_count,
+                    number_of_values: self.channel_len(object_id),
+                });
+            }
+        }
+        let total_chunks: usize = stats.channels.iter().map(|c| c.chunk_count).sum();
+        if total_chunks > 0 {
+            stats.fragmentation = 1.0 - stats.channels.len() as f64 / total_chunks as f64;
+        }
+        stats
+    }
+
+    /// Describe the layout of each segment
+    pub fn segments(&self) -> Vec<SegmentInfo> {
+        self.segments
+            .iter()
+            .map(|segment| SegmentInfo {
+                position: segment.position(),
+                data_position: segment.data_position(),
+                next_segment_position: segment.next_segment_position,
+                version: segment.version(),
+                toc_flags: segment.toc_mask().flags(),
+                object_count: segment.objects.len(),
+                repetitions: segment.repetitions(),
+            })
+            .collect()
+    }
+
+    /// Describe the layout of the data for a channel in each segment containing data for it
+    pub fn channel_segment_chunks(&self, channel_id: ObjectPathId) -> Vec<SegmentChunks> {
+        self.channel_data_locations(channel_id)
+            .iter()
+            .map(|location| {
+                let segment = &self.segments[location.segment_index];
+                let toc_mask = segment.toc_mask();
+                let interleaved = toc_mask.has_flag(TocFlag::InterleavedData);
+                SegmentChunks {
+                    segment_index: location.segment_index,
+                    segment_position: segment.position(),
+                    data_position: if interleaved {
+                        segment.data_position()
+                    } else {
+                        segment.data_position() + location.data_offset
+                    },
+                    number_of_values: location.number_of_values,
+                    repetitions: segment.repetitions(),
+                    big_endian: toc_mask.has_flag(TocFlag::BigEndian),
+                    interleaved,
+                }
+            })
+            .collect()
+    }
+
+    /// Get the ranges of bytes within the file containing the raw data for a channel,
+    /// with adjacent ranges merged
+    pub fn channel_byte_ranges(&self, channel_id: ObjectPathId) -> Result<Vec<Range<u64>>> {
+        let mut ranges: Vec<Range<u64>> = Vec::new();
+        for chunk in self.channel_chunks(channel_id)? {
+            let chunk_end = chunk.position + chunk.byte_length;
+            match ranges.last_mut() {
+                Some(range) if range.end == chunk.position => range.end = chunk_end,
+                _ => ranges.push(chunk.position..chunk_end),
+            }
+        }
+        Ok(ranges)
+    }
+
+    /// Get the chunks of data that need to be read to fill a buffer with all data for a channel,
+    /// after checking the buffer type and size are valid for the channel.
+    pub fn plan_channel_read<T: NativeType>(
+        &self,
+        channel_id: ObjectPathId,
+        buffer_length: usize,
+    ) -> Result<Vec<DataChunk>> {
+        let channel_data_index = match self.get_channel_data_index(channel_id) {
+            Some(channel_data_index) => channel_data_index,
+            None => return Ok(Vec::new()),
+        };
+        if channel_data_index.number_of_values > buffer_length as u64 {
+            return Err(TdmsReadError::BufferSizeMismatch {
+                required: channel_data_index.number_of_values,
+                actual: buffer_length,
+            });
+        }
+        self.check_channel_type::<T>(channel_id)?;
+        self.channel_chunks(channel_id)
+    }
+
+    /// Check that the data for a channel can be read as values of type T
+    pub fn check_channel_type<T: NativeType>(&self, channel_id: ObjectPathId) -> Result<()> {
+        let tdms_type = self.channel_data_type(channel_id);
+        match tdms_type.native_type() {
+            Some(expected_native_type) if expected_native_type == T::native_type() => Ok(()),
+            Some(expected_native_type) => Err(TdmsReadError::BufferTypeMismatch {
+                channel_path: self.object_path_string(channel_id),
+                data_type: tdms_type,
+                requested: T::native_type(),
+                expected: expected_native_type,
+            }),
+            None => Err(TdmsReadError::UnsupportedDataType(tdms_type)),
+        }
+    }
+
+    /// Read a single value from a channel, returning None if the index is out of range
+    pub fn read_channel_value<R: Read + Seek, T: NativeType + Default + Clone>(
         &self,
         reader: &mut R,
         channel_id: ObjectPathId,
+        index: u64,
+    ) -> Result<Option<T>> {
+        if index >= self.channel_len(channel_id) {
+            return Ok(None);
+        }
+        let mut value = [T::default()];
+        self.read_channel_range(reader, channel_id, index, &mut value)?;
+        let [value] = value;
+        Ok(Some(value))
+    }
+
+    /// Read a range of values from a channel starting at the given index to fill the buffer.
+    /// Only the chunks containing the requested values are read, and for fixed size types
+    /// only the requested values within those chunks.
+    pub fn read_channel_range<R: Read + Seek, T: NativeType + Default + Clone>(
+        &self,
+        reader: &mut R,
+        channel_id: ObjectPathId,
+        start: u64,
         buffer: &mut [T],
     ) -> Result<()> {
+        self.check_channel_range(channel_id, start, buffer.len() as u64)?;
+        let end = start + buffer.len() as u64;
+        if start == end {
+            return Ok(());
+        }
+        self.check_channel_type::<T>(channel_id)?;
+        let type_size = self.channel_data_type(channel_id).size().map(u64::from);
+        let locations = self.channel_data_locations(channel_id);
+        let first_location = locations.partition_point(|l| l.first_value_index <= start) - 1;
         let mut offset = 0;
-        for segment in self.segments.iter() {
-            if segment
-                .objects
-                .iter()
-                .any(|o| o.object_id == channel_id && o.raw_data_index.is_some())
-            {
-                offset += segment.read_channel_data(
+        for location in &locations[first_location..] {
+            let segment = &self.segments[location.segment_index];
+            let values_per_chunk = location.number_of_values;
+            let segment_start = location.first_value_index;
+            let segment_end = segment_start + values_per_chunk * segment.repetitions();
+            if segment_start >= end {
+                break;
+            }
+            if values_per_chunk == 0 || segment_end <= start {
+                continue;
+            }
+            let first_repeat = (start.max(segment_start) - segment_start) / values_per_chunk;
+            let last_repeat = (end.min(segment_end) - segment_start - 1) / values_per_chunk;
+            for repeat_idx in first_repeat..=last_repeat {
+                let chunk = segment.channel_chunk(location, repeat_idx, &self.data_indexes)?;
+                let chunk_start = segment_start + repeat_idx * values_per_chunk;
+                let range_start = start.max(chunk_start) - chunk_start;
+                let range_end = end.min(chunk_start + values_per_chunk) - chunk_start;
+                let num_values = (range_end - range_start) as usize;
+                let target = &mut buffer[offset..offset + num_values];
+                self.chunk_decoded();
+                let result = match type_size {
+                    Some(type_size) => chunk
+                        .sub_chunk(range_start, num_values as u64, type_size)
+                        .read_values(reader, target),
+                    None => {
+                        // Variable size values can't be located without reading the whole chunk
+                        self.check_values_memory_limit(channel_id, chunk.number_of_values)?;
+                        let mut values = vec![T::default(); chunk.number_of_values as usize];
+                        chunk.read_values(reader, &mut values).map(|_| {
+                            target.clone_from_slice(
+                                &values[range_start as usize..range_end as usize],
+                            );
+                            num_values
+                        })
+                    }
+                };
+                result.map_err(|e| e.in_object(self.object_path_string(channel_id)))?;
+                offset += num_values;
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the index of the value nearest to the given time in a channel of timestamps
+    /// sorted in ascending order, using a binary search
+    pub fn find_channel_time_index<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        channel_id: ObjectPathId,
+        time: Timestamp,
+    ) -> Result<Option<u64>> {
+        let channel_len = self.channel_len(channel_id);
+        if channel_len == 0 {
+            return Ok(None);
+        }
+        let mut value_at = |index| -> Result<Timestamp> {
+            // Index is always in range so the value is present
+            Ok(self
+                .read_channel_value::<_, Timestamp>(reader, channel_id, index)?
+                .unwrap())
+        };
+        // Find the first index with a time not before the requested time
+        let (mut low, mut high) = (0, channel_len);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if value_at(mid)? < time {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        if low == channel_len {
+            return Ok(Some(channel_len - 1));
+        }
+        if low == 0 {
+            return Ok(Some(0));
+        }
+        let before = time.seconds_since(&value_at(low - 1)?);
+        let after = value_at(low)?.seconds_since(&time);
+        Ok(Some(if before <= after { low - 1 } else { low }))
+    }
+
+    /// Get the most recently set value of a property of an object
+    pub fn get_property(&self, object_id: ObjectPathId, name: &str) -> Option<&TdmsValue> {
+        self.get_properties(object_id)
+            .iter()
+            .find(|property| property.name == name)
+            .map(|property| &property.value)
+    }
+
+    /// Get all values a property of an object has been set to, in segment order,
+    /// or an empty list if property history is not enabled
+    pub fn property_history(&self, object_id: ObjectPathId, name: &str) -> Vec<PropertyRevision> {
+        self.property_history
+            .as_ref()
+            .and_then(|history| history.get(&object_id))
+            .and_then(|history| history.get(name))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Set the value of a property, replacing any previous value with the same name
+    /// so that the most recently set value wins
+    fn set_property(&mut self, object_id: ObjectPathId, property: TdmsProperty) {
+        if let Some(history) = &mut self.property_history {
+            history
+                .entry(object_id)
+                .or_default()
+                .entry(property.name.clone())
+                .or_default()
+                .push(PropertyRevision {
+                    segment_index: self.num_segments_read,
+                    value: property.value.clone(),
+                });
+        }
+        let properties = self.properties.entry(object_id).or_default();
+        let indexes = self.property_indexes.entry(object_id).or_default();
+        match indexes.get(&property.name) {
+            Some(&index) => properties[index].value = property.value,
+            None => {
+                indexes.insert(property.name.clone(), properties.len());
+                properties.push(property);
+            }
+        }
+    }
+
+    /// Get the timing of a waveform channel from its first start time and time increment
+    pub fn waveform_timing(&self, channel_id: ObjectPathId) -> Result<WaveformTiming> {
+        Ok(WaveformTiming {
+            start_time: self.waveform_starts(channel_id)?[0].1,
+            increment: self.waveform_increment(channel_id)?,
+            len: self.channel_len(channel_id),
+        })
+    }
+
+    pub fn waveform_increment(&self, channel_id: ObjectPathId) -> Result<f64> {
+        match self.get_property(channel_id, WF_INCREMENT) {
+            Some(TdmsValue::Float64(increment)) if *increment > 0.0 => Ok(*increment),
+            _ => Err(TdmsReadError::MissingProperty {
+                path: self.object_path_string(channel_id),
+                name: WF_INCREMENT,
+            }),
+        }
+    }
+
+    fn waveform_starts(&self, channel_id: ObjectPathId) -> Result<&[(u64, Timestamp)]> {
+        match self.waveform_starts.get(channel_id) {
+            Some(waveform_starts) => Ok(waveform_starts),
+            None => Err(TdmsReadError::MissingProperty {
+                path: self.object_path_string(channel_id),
+                name: WF_START_TIME,
+            }),
+        }
+    }
+
+    /// Read the values of a waveform channel with times in the range [start, end).
+    /// Times are computed from the wf_start_time and wf_increment properties.
+    /// If the start time changes part way through the channel, the acquisition is assumed
+    /// to have restarted and times for following values are relative to the new start time.
+    pub fn read_channel_between<R: Read + Seek, T: NativeType + Default + Clone>(
+        &self,
+        reader: &mut R,
+        channel_id: ObjectPathId,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<T>> {
+        let increment = self.waveform_increment(channel_id)?;
+        let waveform_starts = self.waveform_starts(channel_id)?;
+        let channel_len = self.channel_len(channel_id);
+        let mut values = Vec::new();
+        for (i, (first_index, start_time)) in waveform_starts.iter().enumerate() {
+            // Values before the start time is first set use the first start time
+            let first_index = if i == 0 { 0 } else { *first_index };
+            let last_index = match waveform_starts.get(i + 1) {
+                Some((next_index, _)) => *next_index,
+                None => channel_len,
+            };
+            let timing = WaveformTiming {
+                start_time: *start_time,
+                increment,
+                len: last_index - first_index,
+            };
+            let range_start = first_index + timing.index_at(&start);
+            let range_end = first_index + timing.index_at(&end);
+            if range_end > range_start {
+                let values_start = values.len();
+                self.check_values_memory_limit(
+                    channel_id,
+                    values_start as u64 + (range_end - range_start),
+                )?;
+                values.resize(
+                    values_start + (range_end - range_start) as usize,
+                    T::default(),
+                );
+                self.read_channel_range(
                     reader,
                     channel_id,
-                    &mut buffer[offset..],
-                    &self.data_indexes,
+                    range_start,
+                    &mut values[values_start..],
                 )?;
             }
         }
+        Ok(values)
+    }
+
+    /// Get all chunks of data for a channel across all segments
+    pub fn channel_chunks(&self, channel_id: ObjectPathId) -> Result<Vec<DataChunk>> {
+        let mut chunks = Vec::new();
+        for location in self.channel_data_locations(channel_id) {
+            let segment = &self.segments[location.segment_index];
+            chunks.extend(segment.channel_chunks(location, &self.data_indexes)?);
+        }
+        Ok(chunks)
+    }
+
+    /// Get the locations of data for a channel in each segment that contains data for it
+    pub fn channel_data_locations(&self, channel_id: ObjectPathId) -> &[ChannelDataLocation] {
+        match self.channel_segments.get(channel_id) {
+            Some(locations) => locations,
+            None => &[],
+        }
+    }
+
+    /// Copy the undecoded raw data for a channel from all segments to a writer,
+    /// returning the number of bytes written
+    pub fn copy_channel_raw_data<R: Read + Seek, W: Write>(
+        &self,
+        reader: &mut R,
+        channel_id: ObjectPathId,
+        writer: &mut W,
+    ) -> Result<u64> {
+        let mut bytes_written = 0;
+        for chunk in self.channel_chunks(channel_id)? {
+            bytes_written += chunk
+                .copy_raw_bytes(reader, writer)
+                .map_err(|e| e.in_object(self.object_path_string(channel_id)))?;
+        }
+        Ok(bytes_written)
+    }
+
+    pub fn read_channel_data<R: Read + Seek, T: NativeType>(
+        &self,
+        reader: &mut R,
+        channel_id: ObjectPathId,
+        buffer: &mut [T],
+    ) -> Result<()> {
+        let mut offset = 0;
+        for chunk in self.plan_channel_read::<T>(channel_id, buffer.len())? {
+            self.chunk_decoded();
+            offset += chunk
+                .read_values(reader, &mut buffer[offset..])
+                .map_err(|e| e.in_object(self.object_path_string(channel_id)))?;
+        }
         Ok(())
     }
 
+    /// Read every `stride`th value of a channel, starting with the first value.
+    /// When values are far enough apart, each value is read individually so that
+    /// data between them is skipped.
+    pub fn read_channel_data_decimated<R: Read + Seek, T: NativeType + Default + Clone>(
+        &self,
+        reader: &mut R,
+        channel_id: ObjectPathId,
+        stride: usize,
+    ) -> Result<Vec<T>> {
+        if stride == 0 {
+            return Err(TdmsReadError::InvalidStride(stride));
+        }
+        let stride = stride as u64;
+        let channel_len = self.channel_len(channel_id);
+        let type_size = self.channel_data_type(channel_id).size().map(u64::from);
+        let num_values = channel_len.div_ceil(stride);
+        self.check_values_memory_limit(channel_id, num_values)?;
+        let mut values = Vec::with_capacity(num_values as usize);
+        let mut chunk_buffer = Vec::new();
+        let mut chunk_start = 0;
+        for chunk in self.plan_channel_read::<T>(channel_id, channel_len as usize)? {
+            // Index within this chunk of the first value to be read
+            let first_index = (stride - chunk_start % stride) % stride;
+            chunk_start += chunk.number_of_values;
+            if first_index >= chunk.number_of_values {
+                continue;
+            }
+            self.chunk_decoded();
+            let result = match type_size {
+                Some(type_size)
+                    if stride * chunk.value_spacing(type_size) >= MIN_DECIMATION_SKIP_BYTES =>
+                {
+                    (first_index..chunk.number_of_values)
+                        .step_by(stride as usize)
+                        .try_for_each(|index| {
+                            let mut value = [T::default()];
+                            chunk
+                                .value_chunk(index, type_size)
+                                .read_values(reader, &mut value)?;
+                            values.push(std::mem::take(&mut value[0]));
+                            Ok(())
+                        })
+                }
+                _ => {
+                    // The whole chunk is read into a buffer as well as the decimated values
+                    self.check_values_memory_limit(
+                        channel_id,
+                        num_values.saturating_add(chunk.number_of_values),
+                    )?;
+                    chunk_buffer.resize(chunk.number_of_values as usize, T::default());
+                    chunk.read_values(reader, &mut chunk_buffer).map(|_| {
+                        values.extend(
+                            chunk_buffer
+                                .drain(..)
+                                .skip(first_index as usize)
+                                .step_by(stride as usize),
+                        )
+                    })
+                }
+            };
+            result.map_err(|e| e.in_object(self.object_path_string(channel_id)))?;
+        }
+        Ok(values)
+    }
+
+    /// Read the data for a channel incrementally, calling `f` with each block of values read.
+    /// Large chunks of fixed size data are split so that memory use is bounded.
+    pub fn read_channel_data_blocks<R, T, F>(
+        &self,
+        reader: &mut R,
+        channel_id: ObjectPathId,
+        mut f: F,
+    ) -> Result<()>
+    where
+        R: Read + Seek,
+        T: NativeType + Default + Clone,
+        F: FnMut(&[T]),
+    {
+        let mut buffer = Vec::new();
+        for block in self.plan_channel_blocks::<T>(channel_id)? {
+            self.check_values_memory_limit(channel_id, block.number_of_values)?;
+            buffer.resize(block.number_of_values as usize, T::default());
+            self.chunk_decoded();
+            block
+                .read_values(reader, &mut buffer)
+                .map_err(|e| e.in_object(self.object_path_string(channel_id)))?;
+            f(&buffer);
+        }
+        Ok(())
+    }
+
+    /// Get the chunks of data that need to be read to read all data for a channel
+    /// incrementally, with large chunks of fixed size data split so that memory use is bounded
+    pub fn plan_channel_blocks<T: NativeType>(
+        &self,
+        channel_id: ObjectPathId,
+    ) -> Result<Vec<DataChunk>> {
+        let chunks =
+            self.plan_channel_read::<T>(channel_id, self.channel_len(channel_id) as usize)?;
+        match self.channel_data_type(channel_id).size() {
+            Some(type_size) if type_size > 0 => Ok(chunks
+                .iter()
+                .flat_map(|chunk| chunk.split(MAX_VALUES_PER_READ, type_size as u64))
+                .collect()),
+            _ => Ok(chunks),
+        }
+    }
+
+    /// Compute the minimum and maximum values within equal width windows of a channel's data,
+    /// reading the data incrementally. If the channel has fewer values than the number of
+    /// windows, each value is in its own window.
+    pub fn read_channel_min_max<R, T>(
+        &self,
+        reader: &mut R,
+        channel_id: ObjectPathId,
+        num_windows: usize,
+    ) -> Result<Vec<(T, T)>>
+    where
+        R: Read + Seek,
+        T: NativeType + Default + Clone + PartialOrd,
+    {
+        let channel_len = self.channel_len(channel_id);
+        let num_windows = (num_windows as u64).min(channel_len);
+        let mut windows: Vec<(T, T)> = Vec::with_capacity(num_windows as usize);
+        let mut index: u64 = 0;
+        self.read_channel_data_blocks(reader, channel_id, |values: &[T]| {
+            for value in values {
+                let window = (index as u128 * num_windows as u128 / channel_len as u128) as usize;
+                index += 1;
+                if window == windows.len() {
+                    windows.push((value.clone(), value.clone()));
+                    continue;
+                }
+                let (min, max) = windows.last_mut().unwrap();
+                if value < min {
+                    *min = value.clone();
+                }
+                if value > max {
+                    *max = value.clone();
+                }
+            }
+        })?;
+        Ok(windows)
+    }
+
+    /// Read all data for a channel using positioned reads, without requiring mutable access
+    /// to the source
+    pub fn read_channel_data_at<S: ReadAt + ?Sized, T: NativeType>(
+        &self,
+        source: &S,
+        channel_id: ObjectPathId,
+        buffer: &mut [T],
+    ) -> Result<()> {
+        let mut offset = 0;
+        for chunk in self.plan_channel_read::<T>(channel_id, buffer.len())? {
+            self.chunk_decoded();
+            offset += chunk
+                .read_values_at(source, &mut buffer[offset..])
+                .map_err(|e| e.in_object(self.object_path_string(channel_id)))?;
+        }
+        Ok(())
+    }
+
+    /// Read all data for multiple channels, reading the required raw data from each segment
+    /// only once rather than once per channel. Only the byte ranges of the channels' chunks
+    /// are read, with nearby chunks read together in spans of bounded size.
+    pub fn read_channels_data<R: Read + Seek>(
+        &self,
+        reader: &mut R,
+        channel_ids: &[ObjectPathId],
+    ) -> Result<Vec<ChannelData>> {
+        let data_memory = channel_ids
+            .iter()
+            .map(|&channel_id| self.channel_memory_size(channel_id))
+            .fold(0, u64::saturating_add);
+        self.check_memory_limit(data_memory)?;
+        let mut channel_data = channel_ids
+            .iter()
+            .map(|&channel_id| {
+                ChannelData::with_len(
+                    self.channel_data_type(channel_id),
+                    self.channel_len(channel_id) as usize,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // Group the channel data locations by segment, so that segments are read in order
+        let mut segment_locations = BTreeMap::<usize, Vec<_>>::new();
+        for (channel_idx, &channel_id) in channel_ids.iter().enumerate() {
+            for location in self.channel_data_locations(channel_id) {
+                segment_locations
+                    .entry(location.segment_index)
+                    .or_default()
+                    .push((channel_idx, location));
+            }
+        }
+        let mut offsets = vec![0; channel_ids.len()];
+        let mut span_bytes = Vec::new();
+        for (segment_index, locations) in segment_locations {
+            let segment = &self.segments[segment_index];
+            let mut segment_chunks = Vec::new();
+            for (channel_idx, location) in locations {
+                for chunk in segment.channel_chunks(location, &self.data_indexes)? {
+                    match chunk.layout {
+                        ChunkLayout::Interleaved {
+                            chunk_width,
+                            type_size,
+                            ..
+                        } => {
+                            // Interleaved chunks span whole rows, so are split to bound
+                            // the number of bytes read at once
+                            let max_rows = (MAX_READ_SPAN_BYTES / chunk_width as u64).max(1);
+                            for sub_chunk in chunk.split(max_rows, type_size as u64) {
+                                segment_chunks.push((channel_idx, sub_chunk));
+                            }
+                        }
+                        ChunkLayout::Contiguous => segment_chunks.push((channel_idx, chunk)),
+                    }
+                }
+            }
+            // A stable sort keeps each channel's chunks in order
+            segment_chunks.sort_by_key(|(_, chunk)| chunk.position);
+            for span in read_spans(&segment_chunks) {
+                if let [(channel_idx, chunk)] = span {
+                    if chunk.layout == ChunkLayout::Contiguous {
+                        // A single contiguous chunk is decoded as it is read without buffering
+                        self.chunk_decoded();
+                        offsets[*channel_idx] += channel_data[*channel_idx].read_chunk(
+                            chunk,
+                            reader,
+                            offsets[*channel_idx],
+                        )?;
+                        continue;
+                    }
+                }
+                let span_start = span[0].1.position;
+                let span_end = span
+                    .iter()
+                    .map(|(_, chunk)| chunk.position + chunk.byte_length)
+                    .max()
+                    .unwrap_or(span_start);
+                let span_length = span_end - span_start;
+                self.check_memory_limit(data_memory.saturating_add(span_length))?;
+                span_bytes.resize(span_length as usize, 0);
+                reader.seek(SeekFrom::Start(span_start))?;
+                reader.read_exact(&mut span_bytes)?;
+                for (channel_idx, chunk) in span {
+                    let chunk_start = (chunk.position - span_start) as usize;
+                    let chunk_bytes =
+                        &span_bytes[chunk_start..chunk_start + chunk.byte_length as usize];
+                    self.chunk_decoded();
+                    offsets[*channel_idx] += channel_data[*channel_idx].decode_chunk(
+                        chunk,
+                        chunk_bytes,
+                        offsets[*channel_idx],
+                    )?;
+                }
+            }
+        }
+        Ok(channel_data)
+    }
+
     fn read_segments<R: Read + Seek>(&mut self, reader: &mut R) -> Result<()> {
-        let mut object_merger = ObjectMerger::new();
+        let start_position = reader.stream_position()?;
+        self.next_segment_position = start_position;
+        let file_length = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(start_position))?;
         loop {
-            let position = reader.seek(SeekFrom::Current(0))?;
-            match self.read_segment(reader, position, &mut object_merger) {
-                Err(e) => return Err(e),
-                Ok(None) => {
+            let position = reader.stream_position()?;
+            match read_lead_in(reader, position)? {
+                None => {
                     // Reached end of file
                     break;
                 }
-                Ok(Some(segment)) => {
+                Some(lead_in) => {
+                    let next_segment_position =
+                        self.add_segment(&lead_in, position, reader, file_length)?;
+                    self.report_progress(start_position, next_segment_position, file_length);
                     // Seek to the start of the next segment
-                    reader.seek(SeekFrom::Start(segment.next_segment_position))?;
-                    self.segments.push(segment);
+                    reader.seek(SeekFrom::Start(next_segment_position))?;
                 }
             }
         }
         Ok(())
     }
 
-    fn read_segment<R: Read + Seek>(
+    /// Read segment metadata from a TDMS index file, where each segment contains only
+    /// the lead in and metadata of the corresponding segment in the data file.
+    /// Segment positions are tracked in the data file, which has the given length.
+    fn read_index_segments<R: Read + Seek>(
         &mut self,
-        reader: &mut R,
-        position: u64,
-        object_merger: &mut ObjectMerger,
-    ) -> Result<Option<TdmsSegment>> {
-        let mut header_bytes = [0u8; 4];
-        let mut bytes_read = 0;
-        while bytes_read < 4 {
-            match reader.read(&mut header_bytes[bytes_read..])? {
-                0 => return Ok(None),
-                n => bytes_read += n,
+        index_reader: &mut R,
+        file_length: u64,
+    ) -> Result<()> {
+        self.next_segment_position = 0;
+        let mut index_position = index_reader.seek(SeekFrom::Start(0))?;
+        while let Some(lead_in) =
+            read_lead_in_with_tag(index_reader, index_position, INDEX_SEGMENT_TAG)?
+        {
+            let position = self.next_segment_position;
+            let next_segment_position =
+                self.add_segment(&lead_in, position, index_reader, file_length)?;
+            self.report_progress(0, next_segment_position, file_length);
+            if lead_in.is_incomplete() {
+                break;
             }
+            index_position = index_position
+                .saturating_add(LEAD_IN_LENGTH as u64)
+                .saturating_add(lead_in.raw_data_offset);
+            index_reader.seek(SeekFrom::Start(index_position))?;
         }
+        Ok(())
+    }
 
-        // Check segment header
-        let expected_header = [0x54, 0x44, 0x53, 0x6d];
-        if header_bytes != expected_header {
-            return Err(TdmsReadError::TdmsError(format!(
-                "Invalid segment header at position {}: {:?}",
-                position, header_bytes,
-            )));
+    /// Read any segments appended to the file since metadata was last read, returning the
+    /// number of new segments. Reading stops at a segment that has not been completely
+    /// written yet, so that it can be read by a later call.
+    pub fn read_appended_segments<R: Read + Seek>(&mut self, reader: &mut R) -> Result<usize> {
+        if self.incomplete_segment_read {
+            return Ok(0);
+        }
+        let file_length = reader.seek(SeekFrom::End(0))?;
+        let segments_read = self.num_segments_read;
+        while file_length.saturating_sub(self.next_segment_position) >= LEAD_IN_LENGTH as u64 {
+            let position = self.next_segment_position;
+            reader.seek(SeekFrom::Start(position))?;
+            let lead_in = match read_lead_in(reader, position)? {
+                Some(lead_in) => lead_in,
+                None => break,
+            };
+            let segment_end =
+                (position + LEAD_IN_LENGTH as u64).checked_add(lead_in.next_segment_offset);
+            if lead_in.is_incomplete() || matches!(segment_end, Some(end) if end > file_length) {
+                // The segment is still being written
+                break;
+            }
+            self.add_segment(&lead_in, position, reader, file_length)?;
         }
+        Ok(self.num_segments_read - segments_read)
+    }
 
-        let toc_mask = TocMask::from_flags(reader.read_u32::<LittleEndian>()?);
+    /// Call the progress callback if set, after reading a segment
+    pub fn report_progress(&self, start_position: u64, position: u64, file_length: u64) {
+        if let Some(progress_callback) = &self.progress_callback {
+            progress_callback.call(&ReadProgress {
+                segments_read: self.num_segments_read,
+                bytes_processed: position - start_position,
+                position,
+                file_length,
+            });
+        }
+    }
 
-        if toc_mask.has_flag(TocFlag::BigEndian) {
-            self.read_segment_metadata::<R, BigEndian>(reader, toc_mask, position, object_merger)
+    /// Get the chunks of data for each channel with data in the most recently added segment
+    pub fn last_segment_chunks(&self) -> Result<Vec<(ObjectPathId, Vec<DataChunk>)>> {
+        let segment = match self.segments.last() {
+            Some(segment) => segment,
+            None => return Ok(Vec::new()),
+        };
+        segment
+            .channel_data_locations(self.segments.len() - 1, &self.data_indexes)
+            .iter()
+            .map(|(object_id, location)| {
+                segment
+                    .channel_chunks(location, &self.data_indexes)
+                    .map(|chunks| (*object_id, chunks))
+            })
+            .collect()
+    }
+
+    /// Discard all segments apart from the most recent, which is still required
+    /// to interpret the metadata of the next segment.
+    /// The per-channel segment index is also discarded, so whole channels can no longer be read.
+    pub fn retain_last_segment(&mut self) {
+        let num_segments = self.segments.len();
+        if num_segments > 1 {
+            self.segments.drain(0..num_segments - 1);
+        }
+        self.channel_segments.clear();
+    }
+
+    /// Add a segment given its lead in and a reader positioned at the start of the segment
+    /// metadata, returning the position of the next segment.
+    /// The file length is used to validate the segment and to determine the extent
+    /// of an incomplete segment.
+    pub fn add_segment<R: Read>(
+        &mut self,
+        lead_in: &LeadIn,
+        position: u64,
+        metadata_reader: &mut R,
+        file_length: u64,
+    ) -> Result<u64> {
+        let segment_index = self.num_segments_read;
+        let segment = if lead_in.toc_mask.has_flag(TocFlag::BigEndian) {
+            self.read_segment_metadata::<R, BigEndian>(
+                metadata_reader,
+                lead_in,
+                position,
+                file_length,
+            )
         } else {
-            self.read_segment_metadata::<R, LittleEndian>(reader, toc_mask, position, object_merger)
+            self.read_segment_metadata::<R, LittleEndian>(
+                metadata_reader,
+                lead_in,
+                position,
+                file_length,
+            )
         }
+        .map_err(|e| e.in_segment(segment_index, position))?;
+        self.warnings.extend(self.string_decoder.take_warnings());
+        let next_segment_position = segment.next_segment_position;
+        let locations = segment.channel_data_locations(self.segments.len(), &self.data_indexes);
+        for (object_id, mut location) in locations {
+            // Channel lengths have already been updated to include this segment
+            location.first_value_index =
+                self.channel_len(object_id) - location.number_of_values * segment.repetitions();
+            match self.channel_segments.get_mut(object_id) {
+                Some(channel_locations) => channel_locations.push(location),
+                None => self.channel_segments.set(object_id, vec![location]),
+            }
+        }
+        self.segments.push(segment);
+        self.num_segments_read += 1;
+        self.next_segment_position = next_segment_position;
+        self.incomplete_segment_read |= lead_in.is_incomplete();
+        Ok(next_segment_position)
     }
 
-    fn read_segment_metadata<R: Read + Seek, O: ByteOrderExt>(
+    fn read_segment_metadata<R: Read, O: ByteOrderExt>(
         &mut self,
         reader: &mut R,
-        toc_mask: TocMask,
+        lead_in: &LeadIn,
         position: u64,
-        object_merger: &mut ObjectMerger,
-    ) -> Result<Option<TdmsSegment>> {
-        let _version = reader.read_i32::<O>()?;
-        let next_segment_offset = reader.read_u64::<O>()?;
-        let raw_data_offset = reader.read_u64::<O>()?;
-
-        let lead_in_length = 28;
-        let next_segment_position = position + lead_in_length + next_segment_offset;
-        let raw_data_position = position + lead_in_length + raw_data_offset;
+        file_length: u64,
+    ) -> Result<TdmsSegment> {
+        let toc_mask = lead_in.toc_mask;
+        if lead_in.version != TDMS_VERSION_1_0 && lead_in.version != TDMS_VERSION_2_0 {
+            self.warnings.push(format!(
+                "Segment at position {} has unrecognised version {}",
+                position, lead_in.version
+            ));
+        }
+        let data_start = position + LEAD_IN_LENGTH as u64;
+        let raw_data_position = data_start
+            .checked_add(lead_in.raw_data_offset)
+            .filter(|&raw_data_position| raw_data_position <= file_length)
+            .ok_or(TdmsReadError::InvalidSegmentOffset {
+                position,
+                offset: lead_in.raw_data_offset,
+            })?;
+        // The next segment must start after the raw data, and so after this segment's
+        // lead in, which guarantees that reading never loops over the same segment
+        let next_segment_position = if lead_in.is_incomplete() {
+            file_length
+        } else {
+            data_start
+                .checked_add(lead_in.next_segment_offset)
+                .filter(|&next_segment_position| {
+                    next_segment_position >= raw_data_position
+                        && next_segment_position <= file_length
+                })
+                .ok_or(TdmsReadError::InvalidSegmentOffset {
+                    position,
+                    offset: lead_in.next_segment_offset,
+                })?
+        };
 
         let segment_objects = if toc_mask.has_flag(TocFlag::MetaData) {
-            let this_segment_objects = self.read_object_metadata::<R, O>(reader)?;
-            if toc_mask.has_flag(TocFlag::NewObjList) {
+            // Limit reads to the metadata so that corrupt object and property counts
+            // or string lengths are detected before allocating
+            let mut metadata_reader = reader.take(lead_in.raw_data_offset);
+            let this_segment_objects =
+                self.read_object_metadata::<_, O>(&mut metadata_reader, position)?;
+            // Some TDMS 1.0 writers do not reliably use the new object list flag,
+            // so objects can optionally be merged with those of the previous segment
+            let legacy_merge =
+                self.merge_tdms_1_0_object_lists && lead_in.version == TDMS_VERSION_1_0;
+            let new_object_list = toc_mask.has_flag(TocFlag::NewObjList) && !legacy_merge;
+            if new_object_list {
                 this_segment_objects
             } else {
                 // Not a new object list so merge with previous segment objects
                 let prev_objs = self.segments.last().map(|segment| &segment.objects);
-                object_merger.merge_objects(prev_objs, this_segment_objects)
+                self.object_merger
+                    .merge_objects(prev_objs, this_segment_objects)
             }
         } else {
             // No meta data in this segment, re-use metadata from the previous segment
@@ -199,80 +1378,155 @@ impl TdmsReader {
             }
         };
 
-        let (data_size, repetitions) = self.compute_repetitions(&segment_objects, raw_data_position, next_segment_position);
+        // Segments without the raw data flag have no data, even if objects have data
+        // in previous segments, for example when only properties are changed
+        let (data_size, repetitions) = if toc_mask.has_flag(TocFlag::RawData) {
+            self.compute_repetitions(
+                &segment_objects,
+                raw_data_position,
+                next_segment_position,
+                lead_in.is_incomplete(),
+            )
+        } else {
+            (0, 0)
+        };
+        if data_size > next_segment_position - raw_data_position && !lead_in.is_incomplete() {
+            return Err(TdmsReadError::SegmentDataSizeMismatch {
+                position,
+                data_size,
+                segment_data_size: next_segment_position - raw_data_position,
+            });
+        }
         self.update_data_indexes(&segment_objects, repetitions)?;
 
-        Ok(Some(TdmsSegment::new(
-            toc_mask,
+        Ok(TdmsSegment::new(
+            position,
+            lead_in,
             raw_data_position,
             next_segment_position,
             segment_objects,
             data_size,
             repetitions,
-        )))
+        ))
     }
 
-    /// Determines the segment data size and number of times segment data is repeated before the next segment
-    fn compute_repetitions(&self, objects: &Vec<SegmentObject>, raw_data_position: u64, next_segment_position: u64) -> (u64, u64) {
+    /// Determines the segment data size and number of times segment data is repeated before the next segment.
+    /// Only complete chunks of data are included for an incomplete segment, as data may have
+    /// been only partially written.
+    fn compute_repetitions(
+        &self,
+        objects: &Vec<SegmentObject>,
+        raw_data_position: u64,
+        next_segment_position: u64,
+        incomplete: bool,
+    ) -> (u64, u64) {
         let mut segment_size: u64 = 0;
         for obj in objects {
             if let Some(data_index_id) = obj.raw_data_index {
                 segment_size += self.data_indexes.get(data_index_id).unwrap().data_size;
             }
         }
-        let repetitions = (next_segment_position - raw_data_position + segment_size - 1) / segment_size;
+        let total_data_size = next_segment_position - raw_data_position;
+        let repetitions = if segment_size == 0 {
+            0
+        } else if incomplete {
+            total_data_size / segment_size
+        } else {
+            total_data_size.div_ceil(segment_size)
+        };
         (segment_size, repetitions)
     }
 
     fn read_object_metadata<R: Read, O: ByteOrderExt>(
         &mut self,
-        reader: &mut R,
+        reader: &mut Take<R>,
+        position: u64,
     ) -> Result<Vec<SegmentObject>> {
         let num_objects = reader.read_u32::<O>()?;
+        check_count_fits(num_objects, MIN_OBJECT_SIZE, reader, "objects", position)?;
         let mut segment_objects = Vec::with_capacity(num_objects as usize);
         for _ in 0..num_objects {
-            let object_path = read_string::<R, O>(reader)?;
-            let object_id = self.object_paths.get_or_create_id(object_path)?;
-            let raw_data_index_header = reader.read_u32::<O>()?;
-            let segment_object = match raw_data_index_header {
-                RAW_DATA_INDEX_NO_DATA => SegmentObject::no_data(object_id),
-                RAW_DATA_INDEX_MATCHES_PREVIOUS => match self.raw_data_index_cache.get(object_id) {
-                    Some(raw_data_index_id) => {
-                        SegmentObject::with_data(object_id, *raw_data_index_id)
-                    }
-                    None => {
-                        return Err(TdmsReadError::TdmsError(String::from(
-                            "Object has no previous raw data index",
-                        )))
-                    }
-                },
-                FORMAT_CHANGING_SCALER => unimplemented!(),
-                DIGITAL_LINE_SCALER => unimplemented!(),
-                _ => {
-                    // Raw data index header gives length of index information
-                    let raw_data_index = self
-                        .data_indexes
-                        .alloc(read_raw_data_index::<R, O>(reader)?);
-                    self.raw_data_index_cache.set(object_id, raw_data_index);
-                    SegmentObject::with_data(object_id, raw_data_index)
-                }
-            };
+            let object_path = read_string::<_, O>(reader, &mut self.string_decoder)?;
+            let object_id = self
+                .object_paths
+                .get_or_create_id(object_path.clone())
+                .map_err(|e| e.in_object(object_path.clone()))?;
+            let segment_object = self
+                .read_segment_object::<_, O>(reader, object_id, position)
+                .map_err(|e| e.in_object(object_path))?;
             segment_objects.push(segment_object);
-            let num_properties = reader.read_u32::<O>()?;
-            for _ in 0..num_properties {
-                let property = TdmsProperty::read::<_, O>(reader)?;
-                self.properties
-                    .entry(object_id)
-                    .or_insert_with(Vec::new)
-                    .push(property);
-            }
         }
 
         Ok(segment_objects)
     }
 
+    /// Read the raw data index and properties of an object in the segment metadata
+    fn read_segment_object<R: Read, O: ByteOrderExt>(
+        &mut self,
+        reader: &mut Take<R>,
+        object_id: ObjectPathId,
+        position: u64,
+    ) -> Result<SegmentObject> {
+        let raw_data_index_header = reader.read_u32::<O>()?;
+        let segment_object = match raw_data_index_header {
+            RAW_DATA_INDEX_NO_DATA => SegmentObject::no_data(object_id),
+            RAW_DATA_INDEX_MATCHES_PREVIOUS => match self.raw_data_index_cache.get(object_id) {
+                Some(raw_data_index_id) => SegmentObject::with_data(object_id, *raw_data_index_id),
+                None => return Err(TdmsReadError::NoPreviousDataIndex),
+            },
+            FORMAT_CHANGING_SCALER => unimplemented!(),
+            DIGITAL_LINE_SCALER => unimplemented!(),
+            _ => {
+                // Raw data index header gives length of index information
+                let raw_data_index = self
+                    .data_indexes
+                    .alloc(read_raw_data_index::<_, O>(reader)?);
+                self.raw_data_index_cache.set(object_id, raw_data_index);
+                SegmentObject::with_data(object_id, raw_data_index)
+            }
+        };
+        let num_properties = reader.read_u32::<O>()?;
+        check_count_fits(
+            num_properties,
+            MIN_PROPERTY_SIZE,
+            reader,
+            "properties",
+            position,
+        )?;
+        for _ in 0..num_properties {
+            let property = TdmsProperty::read::<_, O>(reader, &mut self.string_decoder)?;
+            if let (WF_START_TIME, TdmsValue::Timestamp(start_time)) =
+                (property.name.as_str(), &property.value)
+            {
+                self.add_waveform_start(object_id, *start_time);
+            }
+            self.set_property(object_id, property);
+        }
+        Ok(segment_object)
+    }
+
+    /// Record the waveform start time of a channel, which applies from the next value
+    /// to be read. Repeated start times are ignored.
+    fn add_waveform_start(&mut self, object_id: ObjectPathId, start_time: Timestamp) {
+        let first_index = self.channel_len(object_id);
+        match self.waveform_starts.get_mut(object_id) {
+            Some(waveform_starts) => {
+                if waveform_starts.last().map(|(_, t)| *t) != Some(start_time) {
+                    waveform_starts.push((first_index, start_time));
+                }
+            }
+            None => self
+                .waveform_starts
+                .set(object_id, vec![(first_index, start_time)]),
+        }
+    }
+
     /// Update the channel data indexes with data indexes for the current objects in a segment
-    fn update_data_indexes(&mut self, segment_objects: &[SegmentObject], repetitions: u64) -> Result<()> {
+    fn update_data_indexes(
+        &mut self,
+        segment_objects: &[SegmentObject],
+        repetitions: u64,
+    ) -> Result<()> {
         for segment_obj in segment_objects {
             if let Some(segment_data_index_id) = segment_obj.raw_data_index {
                 // If we have a valid raw data index id it must correspond to a raw data index
@@ -282,11 +1536,14 @@ impl TdmsReader {
                     self.channel_data_index_map.get_mut(segment_obj.object_id);
                 match existing_data_index {
                     Some(existing_data_index) => {
-                        existing_data_index.update_with_segment_index(segment_raw_data_index, repetitions)?;
+                        existing_data_index
+                            .update_with_segment_index(segment_raw_data_index, repetitions)?;
                     }
                     None => {
-                        let new_data_index =
-                            ChannelDataIndex::from_segment_index(segment_raw_data_index, repetitions);
+                        let new_data_index = ChannelDataIndex::from_segment_index(
+                            segment_raw_data_index,
+                            repetitions,
+                        );
                         self.channel_data_index_map
                             .set(segment_obj.object_id, new_data_index);
                     }
@@ -297,6 +1554,46 @@ impl TdmsReader {
     }
 }
 
+/// Read a segment lead in, returning None if the reader is at the end of the file
+pub fn read_lead_in<R: Read>(reader: &mut R, position: u64) -> Result<Option<LeadIn>> {
+    read_lead_in_with_tag(reader, position, SEGMENT_TAG)
+}
+
+fn read_lead_in_with_tag<R: Read>(
+    reader: &mut R,
+    position: u64,
+    tag: &[u8; 4],
+) -> Result<Option<LeadIn>> {
+    let mut lead_in_bytes = [0u8; LEAD_IN_LENGTH];
+    let mut bytes_read = 0;
+    while bytes_read < 4 {
+        match reader.read(&mut lead_in_bytes[bytes_read..4])? {
+            0 => return Ok(None),
+            n => bytes_read += n,
+        }
+    }
+    reader.read_exact(&mut lead_in_bytes[4..])?;
+    LeadIn::parse(&lead_in_bytes, position, tag).map(Some)
+}
+
+/// Check that a count of items read from metadata could fit in the remaining metadata bytes
+fn check_count_fits<R: Read>(
+    count: u32,
+    min_item_size: u64,
+    reader: &Take<R>,
+    item_name: &'static str,
+    position: u64,
+) -> Result<()> {
+    if count as u64 * min_item_size > reader.limit() {
+        return Err(TdmsReadError::MetadataCountTooLarge {
+            item_name,
+            count,
+            position,
+        });
+    }
+    Ok(())
+}
+
 struct ObjectMerger {
     object_indexes: ObjectMap<usize>,
 }
@@ -340,34 +1637,81 @@ impl ObjectMerger {
     }
 }
 
+/// Split chunks sorted by position into spans of overlapping or adjacent chunks
+/// that can be read together, limiting spans to at most `MAX_READ_SPAN_BYTES` unless
+/// they contain only one chunk
+fn read_spans(chunks: &[(usize, DataChunk)]) -> Vec<&[(usize, DataChunk)]> {
+    let mut spans = Vec::new();
+    let mut span_start_idx = 0;
+    let mut span_start = 0;
+    let mut span_end = 0;
+    for (idx, (_, chunk)) in chunks.iter().enumerate() {
+        let chunk_end = chunk.position + chunk.byte_length;
+        let joins_span = idx > span_start_idx
+            && chunk.position <= span_end
+            && chunk_end.max(span_end) - span_start <= MAX_READ_SPAN_BYTES;
+        if joins_span {
+            span_end = span_end.max(chunk_end);
+        } else {
+            if idx > span_start_idx {
+                spans.push(&chunks[span_start_idx..idx]);
+            }
+            span_start_idx = idx;
+            span_start = chunk.position;
+            span_end = chunk_end;
+        }
+    }
+    if span_start_idx < chunks.len() {
+        spans.push(&chunks[span_start_idx..]);
+    }
+    spans
+}
+
 fn read_raw_data_index<R: Read, O: ByteOrderExt>(reader: &mut R) -> Result<RawDataIndex> {
     let data_type = reader.read_u32::<O>()?;
     let data_type = TdsType::from_u32(data_type)?;
     let dimension = reader.read_u32::<O>()?;
     let number_of_values = reader.read_u64::<O>()?;
 
-    if dimension != 1 {
-        return Err(TdmsReadError::TdmsError(format!(
-            "Dimension must be 1, got {}",
-            dimension
-        )));
+    if dimension == 0 {
+        return Err(TdmsReadError::UnsupportedDimension(dimension));
     }
+    // Some legacy writers store array values with a dimension greater than one,
+    // which are read as a flat sequence of elements
+    let number_of_values =
+        number_of_values
+            .checked_mul(dimension as u64)
+            .ok_or(TdmsReadError::TooManyValues {
+                number_of_values,
+                data_type,
+            })?;
 
     let data_size = match data_type.size() {
-        Some(type_size) => (type_size as u64) * number_of_values,
+        Some(type_size) => (type_size as u64).checked_mul(number_of_values).ok_or(
+            TdmsReadError::TooManyValues {
+                number_of_values,
+                data_type,
+            },
+        )?,
         None => {
             if data_type == TdsType::String {
-                reader.read_u64::<O>()?
+                let data_size = reader.read_u64::<O>()?;
+                // Each string value has at least a 4 byte offset
+                if number_of_values > data_size / 4 {
+                    return Err(TdmsReadError::TooManyValues {
+                        number_of_values,
+                        data_type,
+                    });
+                }
+                data_size
             } else {
-                return Err(TdmsReadError::TdmsError(format!(
-                    "Unsupported data type: {:?}",
-                    data_type
-                )));
+                return Err(TdmsReadError::UnsupportedDataType(data_type));
             }
         }
     };
     Ok(RawDataIndex {
         number_of_values,
+        dimension,
         data_type,
         data_size,
     })
@@ -1,12 +1,59 @@
 use chrono::{DateTime, Duration, TimeZone, Utc};
+use std::cmp::Ordering;
+use std::fmt;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Timestamp {
     pub second_fractions: u64,
     pub seconds: i64,
 }
 
-const FRACTIONS_PER_NS: u64 = 18446744073; // 2 ** 64 / 10 ** 9;
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Timestamp) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Timestamp) -> Ordering {
+        (self.seconds, self.second_fractions).cmp(&(other.seconds, other.second_fractions))
+    }
+}
+
+/// Formats the timestamp as an ISO 8601 date and time in UTC.
+/// Fractional seconds are written with nine digits by default, which can be changed by
+/// specifying a precision of up to 19 digits, eg. `format!("{:.3}", timestamp)`.
+/// The fraction is truncated rather than rounded so that the date and time are not changed.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let epoch = Utc.with_ymd_and_hms(1904, 1, 1, 0, 0, 0).unwrap();
+        let datetime = match Duration::try_seconds(self.seconds)
+            .and_then(|duration| epoch.checked_add_signed(duration))
+        {
+            Some(datetime) => datetime,
+            None => {
+                return write!(
+                    f,
+                    "1904-01-01T00:00:00Z + {} s + {} / 2^64 s",
+                    self.seconds, self.second_fractions
+                )
+            }
+        };
+        write!(f, "{}", datetime.format("%Y-%m-%dT%H:%M:%S"))?;
+        let digits = f.precision().unwrap_or(9).min(19);
+        if digits > 0 {
+            let fraction = (self.second_fractions as u128 * 10_u128.pow(digits as u32)) >> 64;
+            write!(f, ".{:0width$}", fraction, width = digits)?;
+        }
+        write!(f, "Z")
+    }
+}
+
+/// Number of seconds from the TDMS epoch of 1904-01-01 to the Unix epoch of 1970-01-01
+const UNIX_EPOCH_OFFSET: i64 = 2_082_844_800;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
 
 impl Timestamp {
     pub fn new(seconds: i64, second_fractions: u64) -> Timestamp {
@@ -16,13 +63,277 @@ impl Timestamp {
         }
     }
 
+    /// Get the number of seconds from another timestamp to this one
+    pub(crate) fn seconds_since(&self, other: &Timestamp) -> f64 {
+        let fraction_scale = 2.0_f64.powi(64);
+        (self.seconds - other.seconds) as f64
+            + (self.second_fractions as f64 - other.second_fractions as f64) / fraction_scale
+    }
+
+    /// Get the timestamp a number of seconds after this one
+    pub(crate) fn add_seconds(&self, seconds: f64) -> Timestamp {
+        let whole_seconds = seconds.floor();
+        let fractions = (seconds - whole_seconds) * 2.0_f64.powi(64);
+        let (second_fractions, carry) = self.second_fractions.overflowing_add(fractions as u64);
+        Timestamp {
+            seconds: self.seconds + whole_seconds as i64 + carry as i64,
+            second_fractions,
+        }
+    }
+
+    /// Get the number of nanoseconds since the Unix epoch, truncating any fraction of a
+    /// nanosecond, or `None` if the time is outside the range representable as an `i64`
+    pub fn to_unix_nanos(&self) -> Option<i64> {
+        let seconds = self.seconds.checked_sub(UNIX_EPOCH_OFFSET)?;
+        seconds
+            .checked_mul(NANOS_PER_SECOND)?
+            .checked_add(self.subsec_nanos() as i64)
+    }
+
+    /// Create a timestamp from a number of nanoseconds since the Unix epoch
+    pub fn from_unix_nanos(nanos: i64) -> Timestamp {
+        let seconds = nanos.div_euclid(NANOS_PER_SECOND) + UNIX_EPOCH_OFFSET;
+        let nanos = nanos.rem_euclid(NANOS_PER_SECOND) as u64;
+        Timestamp::new(seconds, nanos_to_fractions(nanos))
+    }
+
+    /// Get the number of seconds since the Unix epoch
+    pub fn to_unix_seconds(&self) -> f64 {
+        self.seconds_since(&Timestamp::new(UNIX_EPOCH_OFFSET, 0))
+    }
+
+    /// Create a timestamp from a number of seconds since the Unix epoch
+    pub fn from_unix_seconds(seconds: f64) -> Timestamp {
+        Timestamp::new(UNIX_EPOCH_OFFSET, 0).add_seconds(seconds)
+    }
+
+    /// Convert to a chrono `DateTime`, truncating any fraction of a nanosecond,
+    /// or `None` if the time is outside the range supported by chrono
     pub fn to_datetime(&self) -> Option<DateTime<Utc>> {
-        let seconds_duration = Duration::seconds(self.seconds);
-        let fractions_duration =
-            Duration::nanoseconds((self.second_fractions / FRACTIONS_PER_NS) as i64);
-        let epoch = Utc.ymd(1904, 1, 1).and_hms(0, 0, 0);
+        let seconds_duration = Duration::try_seconds(self.seconds)?;
+        let fractions_duration = Duration::nanoseconds(self.subsec_nanos() as i64);
+        let epoch = Utc.with_ymd_and_hms(1904, 1, 1, 0, 0, 0).unwrap();
         epoch
             .checked_add_signed(seconds_duration)
             .and_then(|dt| dt.checked_add_signed(fractions_duration))
     }
+
+    /// Create a timestamp from a chrono `DateTime`. This is the inverse of `to_datetime`,
+    /// with the fraction of a second rounded up to the nearest 2^-64 seconds so that
+    /// converting back gives the original time.
+    pub fn from_datetime(datetime: DateTime<Utc>) -> Timestamp {
+        // Nanoseconds may exceed one second during a leap second
+        let nanos = datetime.timestamp_subsec_nanos() as i64;
+        let seconds = datetime.timestamp() + UNIX_EPOCH_OFFSET + nanos / NANOS_PER_SECOND;
+        Timestamp::new(
+            seconds,
+            nanos_to_fractions((nanos % NANOS_PER_SECOND) as u64),
+        )
+    }
+
+    /// Get the whole number of nanoseconds in the fractional part of the second
+    pub fn subsec_nanos(&self) -> u32 {
+        self.scaled_fraction(1_000_000_000) as u32
+    }
+
+    /// Get the whole number of picoseconds in the fractional part of the second
+    pub fn subsec_picos(&self) -> u64 {
+        self.scaled_fraction(1_000_000_000_000)
+    }
+
+    /// Get the whole number of attoseconds in the fractional part of the second
+    pub fn subsec_attos(&self) -> u64 {
+        self.scaled_fraction(1_000_000_000_000_000_000)
+    }
+
+    /// Get the fractional part of the second as an exact decimal string, eg. "0.5".
+    /// As the fraction is a multiple of 2^-64 seconds, at most 64 digits are
+    /// needed to represent it without any loss of precision.
+    pub fn subsec_decimal(&self) -> String {
+        let mut decimal = String::from("0.");
+        let mut remainder = self.second_fractions as u128;
+        loop {
+            remainder *= 10;
+            decimal.push(char::from(b'0' + (remainder >> 64) as u8));
+            remainder &= u64::MAX as u128;
+            if remainder == 0 {
+                break;
+            }
+        }
+        decimal
+    }
+
+    fn scaled_fraction(&self, scale: u64) -> u64 {
+        ((self.second_fractions as u128 * scale as u128) >> 64) as u64
+    }
+}
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(datetime: DateTime<Utc>) -> Timestamp {
+        Timestamp::from_datetime(datetime)
+    }
+}
+
+/// Convert a number of nanoseconds less than one second to fractions of a second,
+/// rounding up so that converting back to nanoseconds gives the original value
+fn nanos_to_fractions(nanos: u64) -> u64 {
+    ((nanos as u128) << 64).div_ceil(NANOS_PER_SECOND as u128) as u64
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn unix_nanos_round_trip() {
+        for nanos in [
+            0,
+            1,
+            999_999_999,
+            1_000_000_000,
+            -1,
+            1_441_706_747_669_260_594,
+        ] {
+            let timestamp = Timestamp::from_unix_nanos(nanos);
+            assert_eq!(timestamp.to_unix_nanos(), Some(nanos));
+        }
+    }
+
+    #[test]
+    pub fn unix_epoch() {
+        let timestamp = Timestamp::from_unix_nanos(0);
+        assert_eq!(timestamp, Timestamp::new(2_082_844_800, 0));
+        assert_eq!(
+            timestamp.to_datetime(),
+            Some(Utc.with_ymd_and_hms(1970, 1, 1, 0, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    pub fn before_unix_epoch() {
+        let timestamp = Timestamp::from_unix_nanos(-500_000_000);
+        assert_eq!(timestamp, Timestamp::new(2_082_844_799, 1 << 63));
+        assert_eq!(timestamp.to_unix_seconds(), -0.5);
+    }
+
+    #[test]
+    pub fn unix_seconds() {
+        let timestamp = Timestamp::from_unix_seconds(1.25);
+        assert_eq!(timestamp, Timestamp::new(2_082_844_801, 1 << 62));
+        assert_eq!(timestamp.to_unix_seconds(), 1.25);
+        assert_eq!(timestamp.to_unix_nanos(), Some(1_250_000_000));
+    }
+
+    #[test]
+    pub fn datetime_round_trip() {
+        let datetime = Utc
+            .with_ymd_and_hms(2015, 9, 8, 10, 5, 47)
+            .unwrap()
+            .checked_add_signed(Duration::nanoseconds(669260594))
+            .unwrap();
+        let timestamp = Timestamp::from_datetime(datetime);
+        assert_eq!(timestamp.seconds, 3524551547);
+        assert_eq!(timestamp.to_datetime(), Some(datetime));
+        assert_eq!(Timestamp::from(datetime), timestamp);
+
+        for nanos in [0, 1, 500_000_000, 999_999_999] {
+            let datetime = Utc
+                .with_ymd_and_hms(1850, 1, 1, 0, 0, 0)
+                .unwrap()
+                .checked_add_signed(Duration::nanoseconds(nanos))
+                .unwrap();
+            assert_eq!(
+                Timestamp::from_datetime(datetime).to_datetime(),
+                Some(datetime)
+            );
+        }
+    }
+
+    #[test]
+    pub fn subsecond_accessors() {
+        let timestamp = Timestamp::new(0, 1 << 63);
+        assert_eq!(timestamp.subsec_nanos(), 500_000_000);
+        assert_eq!(timestamp.subsec_picos(), 500_000_000_000);
+        assert_eq!(timestamp.subsec_attos(), 500_000_000_000_000_000);
+        assert_eq!(timestamp.subsec_decimal(), "0.5");
+
+        let timestamp = Timestamp::new(0, 0);
+        assert_eq!(timestamp.subsec_attos(), 0);
+        assert_eq!(timestamp.subsec_decimal(), "0.0");
+
+        let timestamp = Timestamp::new(0, 1);
+        assert_eq!(timestamp.subsec_nanos(), 0);
+        assert_eq!(timestamp.subsec_attos(), 0);
+        assert_eq!(
+            timestamp.subsec_decimal(),
+            "0.0000000000000000000542101086242752217003726400434970855712890625"
+        );
+
+        let timestamp = Timestamp::new(0, u64::MAX);
+        assert_eq!(timestamp.subsec_nanos(), 999_999_999);
+        assert_eq!(timestamp.subsec_picos(), 999_999_999_999);
+        assert_eq!(timestamp.subsec_attos(), 999_999_999_999_999_999);
+        assert!(timestamp
+            .subsec_decimal()
+            .starts_with("0.99999999999999999994"));
+        assert_eq!(timestamp.subsec_decimal().len(), 66);
+    }
+
+    #[test]
+    pub fn display() {
+        let timestamp = Timestamp::from_unix_nanos(1_441_706_747_669_260_594);
+        assert_eq!(timestamp.to_string(), "2015-09-08T10:05:47.669260594Z");
+        assert_eq!(format!("{:.3}", timestamp), "2015-09-08T10:05:47.669Z");
+        assert_eq!(format!("{:.0}", timestamp), "2015-09-08T10:05:47Z");
+        assert_eq!(
+            format!("{:.19}", Timestamp::new(0, 1 << 63)),
+            "1904-01-01T00:00:00.5000000000000000000Z"
+        );
+    }
+
+    #[test]
+    pub fn out_of_range() {
+        for seconds in [i64::MAX, i64::MIN, 1 << 60, 1 << 50] {
+            let timestamp = Timestamp::new(seconds, 1 << 63);
+            assert_eq!(timestamp.to_datetime(), None);
+            assert_eq!(
+                timestamp.to_string(),
+                format!(
+                    "1904-01-01T00:00:00Z + {} s + {} / 2^64 s",
+                    seconds,
+                    1_u64 << 63
+                )
+            );
+        }
+    }
+
+    #[test]
+    pub fn ordering_and_hashing() {
+        let mut timestamps = vec![
+            Timestamp::new(1, 0),
+            Timestamp::new(0, 1 << 63),
+            Timestamp::new(-1, u64::MAX),
+        ];
+        timestamps.sort();
+        assert_eq!(
+            timestamps,
+            vec![
+                Timestamp::new(-1, u64::MAX),
+                Timestamp::new(0, 1 << 63),
+                Timestamp::new(1, 0),
+            ]
+        );
+
+        let set: std::collections::HashSet<Timestamp> = timestamps.into_iter().collect();
+        assert!(set.contains(&Timestamp::new(0, 1 << 63)));
+    }
+
+    #[test]
+    pub fn unix_nanos_out_of_range() {
+        assert_eq!(Timestamp::new(i64::MAX, 0).to_unix_nanos(), None);
+        assert_eq!(
+            Timestamp::new(0, 0).to_unix_nanos(),
+            Some(-2_082_844_800 * NANOS_PER_SECOND)
+        );
+    }
 }
@@ -11,7 +11,7 @@ pub enum TocFlag {
     DaqMxRawData = 1 << 7,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct TocMask {
     flags: u32,
 }
@@ -21,6 +21,10 @@ impl TocMask {
         TocMask { flags }
     }
 
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
     pub fn has_flag(&self, flag: TocFlag) -> bool {
         let flag_val: u32 = flag.into();
         (self.flags & flag_val) == flag_val
@@ -33,6 +37,25 @@ impl std::fmt::Display for TocMask {
     }
 }
 
+/// TDMS format version number of files written with TDMS 1.0
+pub const TDMS_VERSION_1_0: u32 = 4712;
+/// TDMS format version number of files written with TDMS 2.0
+pub const TDMS_VERSION_2_0: u32 = 4713;
+
+/// Describes the format of a TDMS file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatInfo {
+    /// Distinct format version numbers of the segments in the file,
+    /// eg. 4712 for TDMS 1.0 or 4713 for TDMS 2.0
+    pub versions: Vec<u32>,
+    /// Whether any segment has big endian data
+    pub big_endian: bool,
+    /// Whether any segment has interleaved data
+    pub interleaved: bool,
+    /// Whether any segment has DAQmx raw data
+    pub daqmx: bool,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -41,12 +64,12 @@ mod test {
     pub fn can_check_flags() {
         let toc_mask = TocMask::from_flags(14);
 
-        assert_eq!(toc_mask.has_flag(TocFlag::MetaData), true);
-        assert_eq!(toc_mask.has_flag(TocFlag::NewObjList), true);
-        assert_eq!(toc_mask.has_flag(TocFlag::RawData), true);
+        assert!(toc_mask.has_flag(TocFlag::MetaData));
+        assert!(toc_mask.has_flag(TocFlag::NewObjList));
+        assert!(toc_mask.has_flag(TocFlag::RawData));
 
-        assert_eq!(toc_mask.has_flag(TocFlag::InterleavedData), false);
-        assert_eq!(toc_mask.has_flag(TocFlag::BigEndian), false);
-        assert_eq!(toc_mask.has_flag(TocFlag::DaqMxRawData), false);
+        assert!(!toc_mask.has_flag(TocFlag::InterleavedData));
+        assert!(!toc_mask.has_flag(TocFlag::BigEndian));
+        assert!(!toc_mask.has_flag(TocFlag::DaqMxRawData));
     }
 }
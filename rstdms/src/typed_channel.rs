@@ -0,0 +1,80 @@
+use crate::error::Result;
+use crate::object_path::ObjectPathId;
+use crate::types::NativeType;
+use crate::value_iter::ChannelValues;
+use crate::{Channel, TdmsFile};
+use std::io::{Read, Seek};
+use std::marker::PhantomData;
+
+/// A channel whose data type has been checked to match the native type `T`,
+/// created with `Channel::typed`
+pub struct TypedChannel<'a, R: Read + Seek, T> {
+    file: &'a TdmsFile<R>,
+    object_id: ObjectPathId,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, R: Read + Seek, T: NativeType + Default + Clone> TypedChannel<'a, R, T> {
+    pub(crate) fn new(channel: &Channel<'a, R>) -> Result<TypedChannel<'a, R, T>> {
+        if !channel.is_empty() {
+            channel
+                .file
+                .tdms_reader
+                .check_channel_type::<T>(channel.object_id)?;
+        }
+        Ok(TypedChannel {
+            file: channel.file,
+            object_id: channel.object_id,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Get the untyped channel
+    pub fn channel(&self) -> Channel<'a, R> {
+        Channel::new(self.file, self.object_id)
+    }
+
+    /// Get the total number of values in this channel
+    pub fn len(&self) -> u64 {
+        self.file.tdms_reader.channel_len(self.object_id)
+    }
+
+    /// Whether this channel has no data
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Read all data for this channel
+    pub fn read_all(&self) -> Result<Vec<T>> {
+        self.file
+            .tdms_reader
+            .check_values_memory_limit(self.object_id, self.len())?;
+        let mut values = vec![T::default(); self.len() as usize];
+        self.channel().read_all_data(&mut values)?;
+        Ok(values)
+    }
+
+    /// Read `count` values from this channel starting at the given index
+    pub fn read_range(&self, start: u64, count: usize) -> Result<Vec<T>> {
+        let tdms_reader = &self.file.tdms_reader;
+        tdms_reader.check_channel_range(self.object_id, start, count as u64)?;
+        tdms_reader.check_values_memory_limit(self.object_id, count as u64)?;
+        let mut values = vec![T::default(); count];
+        self.channel().read_data_range(start, &mut values)?;
+        Ok(values)
+    }
+
+    /// Get an iterator over the values in this channel
+    pub fn iter(&self) -> ChannelValues<'a, R, T> {
+        let blocks = if self.is_empty() {
+            Vec::new()
+        } else {
+            // Data type was checked when creating this channel, so planning can't fail
+            self.file
+                .tdms_reader
+                .plan_channel_blocks::<T>(self.object_id)
+                .unwrap_or_default()
+        };
+        ChannelValues::new(self.file, self.object_id, blocks)
+    }
+}
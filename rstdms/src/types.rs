@@ -1,4 +1,5 @@
 use crate::error::{Result, TdmsReadError};
+use crate::options::ReadOptions;
 use crate::timestamp::Timestamp;
 use byteorder::{BigEndian, ByteOrder, LittleEndian, ReadBytesExt};
 use num_enum::TryFromPrimitive;
@@ -6,6 +7,7 @@ use std::convert::TryFrom;
 use std::io::Read;
 
 #[derive(Clone, Copy, TryFromPrimitive, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum TdsType {
     Void = 0,
@@ -34,8 +36,7 @@ pub enum TdsType {
 
 impl TdsType {
     pub fn from_u32(type_id_raw: u32) -> Result<TdsType> {
-        TdsType::try_from(type_id_raw)
-            .map_err(|_| TdmsReadError::TdmsError(format!("Invalid type id: {}", type_id_raw)))
+        TdsType::try_from(type_id_raw).map_err(|_| TdmsReadError::InvalidTypeId(type_id_raw))
     }
 
     pub fn size(&self) -> Option<u32> {
@@ -65,6 +66,27 @@ impl TdsType {
         }
     }
 
+    /// Whether this is an integer or real floating point type
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            *self,
+            TdsType::I8
+                | TdsType::I16
+                | TdsType::I32
+                | TdsType::I64
+                | TdsType::U8
+                | TdsType::U16
+                | TdsType::U32
+                | TdsType::U64
+                | TdsType::SingleFloat
+                | TdsType::DoubleFloat
+                | TdsType::ExtendedFloat
+                | TdsType::SingleFloatWithUnit
+                | TdsType::DoubleFloatWithUnit
+                | TdsType::ExtendedFloatWithUnit
+        )
+    }
+
     pub fn native_type(&self) -> Option<NativeTypeId> {
         match *self {
             TdsType::Void => None,
@@ -110,6 +132,27 @@ pub enum NativeTypeId {
     Timestamp,
 }
 
+impl NativeTypeId {
+    /// Size in bytes of a single value of this type in memory, not including
+    /// any heap allocated string data
+    pub fn size_in_memory(&self) -> usize {
+        match self {
+            NativeTypeId::I8 => std::mem::size_of::<i8>(),
+            NativeTypeId::I16 => std::mem::size_of::<i16>(),
+            NativeTypeId::I32 => std::mem::size_of::<i32>(),
+            NativeTypeId::I64 => std::mem::size_of::<i64>(),
+            NativeTypeId::U8 => std::mem::size_of::<u8>(),
+            NativeTypeId::U16 => std::mem::size_of::<u16>(),
+            NativeTypeId::U32 => std::mem::size_of::<u32>(),
+            NativeTypeId::U64 => std::mem::size_of::<u64>(),
+            NativeTypeId::F32 => std::mem::size_of::<f32>(),
+            NativeTypeId::F64 => std::mem::size_of::<f64>(),
+            NativeTypeId::String => std::mem::size_of::<String>(),
+            NativeTypeId::Timestamp => std::mem::size_of::<Timestamp>(),
+        }
+    }
+}
+
 /// A native rust type that TDMS channel data can be read as.
 /// This is a sealed trait that cannot be implemented outside this crate.
 pub trait NativeType: private::SealedNativeType + Sized {
@@ -122,6 +165,43 @@ pub trait NativeType: private::SealedNativeType + Sized {
         reader: &mut R,
         num_values: usize,
     ) -> Result<()>;
+
+    /// Decode values from a buffer of raw bytes that has already been read into memory
+    #[doc(hidden)]
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        Self::read_values::<_, O>(target_buffer, &mut &bytes[..], num_values)
+    }
+}
+
+/// Copy raw bytes directly into a slice of primitive values and then convert
+/// the byte order of the whole slice in place. Converting a full slice at once
+/// rather than value by value allows the compiler to vectorize the byte swapping.
+fn decode_primitive_values<T: Copy>(
+    target_buffer: &mut [T],
+    bytes: &[u8],
+    num_values: usize,
+    convert_byte_order: fn(&mut [T]),
+) -> Result<()> {
+    let target_buffer = &mut target_buffer[0..num_values];
+    let byte_length = std::mem::size_of_val(target_buffer);
+    if bytes.len() < byte_length {
+        return Err(TdmsReadError::IoError(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "Not enough bytes to decode the expected number of values",
+        )));
+    }
+    // Safety: This is only used with primitive integer and floating point types,
+    // for which any bit pattern is a valid value.
+    let target_bytes = unsafe {
+        std::slice::from_raw_parts_mut(target_buffer.as_mut_ptr() as *mut u8, byte_length)
+    };
+    target_bytes.copy_from_slice(&bytes[0..byte_length]);
+    convert_byte_order(target_buffer);
+    Ok(())
 }
 
 impl NativeType for i8 {
@@ -137,6 +217,14 @@ impl NativeType for i8 {
         reader.read_i8_into(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, |_| {})
+    }
 }
 
 impl NativeType for i16 {
@@ -152,6 +240,14 @@ impl NativeType for i16 {
         reader.read_i16_into::<O>(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, O::from_slice_i16)
+    }
 }
 
 impl NativeType for i32 {
@@ -167,6 +263,14 @@ impl NativeType for i32 {
         reader.read_i32_into::<O>(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, O::from_slice_i32)
+    }
 }
 
 impl NativeType for i64 {
@@ -182,6 +286,14 @@ impl NativeType for i64 {
         reader.read_i64_into::<O>(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, O::from_slice_i64)
+    }
 }
 
 impl NativeType for u8 {
@@ -197,6 +309,14 @@ impl NativeType for u8 {
         reader.read_exact(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, |_| {})
+    }
 }
 
 impl NativeType for u16 {
@@ -212,6 +332,14 @@ impl NativeType for u16 {
         reader.read_u16_into::<O>(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, O::from_slice_u16)
+    }
 }
 
 impl NativeType for u32 {
@@ -227,6 +355,14 @@ impl NativeType for u32 {
         reader.read_u32_into::<O>(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, O::from_slice_u32)
+    }
 }
 
 impl NativeType for u64 {
@@ -242,6 +378,14 @@ impl NativeType for u64 {
         reader.read_u64_into::<O>(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, O::from_slice_u64)
+    }
 }
 
 impl NativeType for f32 {
@@ -257,6 +401,14 @@ impl NativeType for f32 {
         reader.read_f32_into::<O>(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, O::from_slice_f32)
+    }
 }
 
 impl NativeType for f64 {
@@ -272,6 +424,14 @@ impl NativeType for f64 {
         reader.read_f64_into::<O>(&mut target_buffer[0..num_values])?;
         Ok(())
     }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        decode_primitive_values(target_buffer, bytes, num_values, O::from_slice_f64)
+    }
 }
 
 impl NativeType for String {
@@ -284,14 +444,22 @@ impl NativeType for String {
         reader: &mut R,
         num_values: usize,
     ) -> Result<()> {
-        let mut string_lengths: Vec<u32> = Vec::new();
+        // String data starts with the offset of the end of each string,
+        // relative to the start of the string contents
+        let mut end_offsets: Vec<u32> = Vec::new();
         for _ in 0..num_values {
-            string_lengths.push(reader.read_u32::<O>()?);
+            end_offsets.push(reader.read_u32::<O>()?);
         }
+        let mut previous = 0;
         for i in 0..num_values {
-            let mut string_bytes = vec![0; string_lengths[i] as usize];
-            reader.read_exact(&mut string_bytes)?;
+            let offset = end_offsets[i];
+            if offset < previous {
+                return Err(TdmsReadError::InvalidStringOffset { offset, previous });
+            }
+            let mut string_bytes = Vec::new();
+            read_bytes_into(reader, (offset - previous) as u64, &mut string_bytes)?;
             target_buffer[i] = String::from_utf8(string_bytes)?;
+            previous = offset;
         }
         Ok(())
     }
@@ -307,19 +475,115 @@ impl NativeType for Timestamp {
         reader: &mut R,
         num_values: usize,
     ) -> Result<()> {
-        for i in 0..num_values {
-            target_buffer[i] = read_timestamp::<_, O>(reader)?;
+        for value in target_buffer.iter_mut().take(num_values) {
+            *value = read_timestamp::<_, O>(reader)?;
+        }
+        Ok(())
+    }
+
+    fn decode_values<O: ByteOrderExt>(
+        target_buffer: &mut [Self],
+        bytes: &[u8],
+        num_values: usize,
+    ) -> Result<()> {
+        if bytes.len() < num_values * 16 {
+            return Err(TdmsReadError::IoError(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "Not enough bytes to decode the expected number of values",
+            )));
+        }
+        for (value, value_bytes) in target_buffer
+            .iter_mut()
+            .take(num_values)
+            .zip(bytes.chunks_exact(16))
+        {
+            *value = O::read_timestamp(value_bytes);
         }
         Ok(())
     }
 }
 
-pub fn read_string<R: Read, O: ByteOrder>(reader: &mut R) -> Result<String> {
+pub fn read_string<R: Read, O: ByteOrder>(
+    reader: &mut R,
+    decoder: &mut StringDecoder,
+) -> Result<String> {
     let string_length = reader.read_u32::<O>()?;
+    let mut string_bytes = Vec::new();
+    read_bytes_into(reader, string_length as u64, &mut string_bytes)?;
+    decoder.decode(string_bytes)
+}
+
+/// Decodes strings read from metadata, recording warnings for strings that are not valid UTF-8
+#[derive(Debug, Default)]
+pub struct StringDecoder {
+    lossy_utf8: bool,
+    #[cfg(feature = "encoding")]
+    fallback_encoding: Option<&'static encoding_rs::Encoding>,
+    warnings: Vec<String>,
+}
+
+impl StringDecoder {
+    pub fn new(options: &ReadOptions) -> StringDecoder {
+        StringDecoder {
+            lossy_utf8: options.lossy_utf8,
+            #[cfg(feature = "encoding")]
+            fallback_encoding: options.fallback_encoding,
+            warnings: Vec::new(),
+        }
+    }
 
-    let mut string_bytes = vec![0; string_length as usize];
-    reader.read_exact(&mut string_bytes)?;
-    Ok(String::from_utf8(string_bytes)?)
+    pub fn decode(&mut self, bytes: Vec<u8>) -> Result<String> {
+        let error = match String::from_utf8(bytes) {
+            Ok(string) => return Ok(string),
+            Err(error) => error,
+        };
+        #[cfg(feature = "encoding")]
+        if let Some(encoding) = self.fallback_encoding {
+            let (string, had_errors) = encoding.decode_without_bom_handling(error.as_bytes());
+            if had_errors {
+                self.warnings.push(format!(
+                    "Replaced invalid {} data in string {:?}",
+                    encoding.name(),
+                    string
+                ));
+            }
+            return Ok(string.into_owned());
+        }
+        if self.lossy_utf8 {
+            let string = String::from_utf8_lossy(error.as_bytes()).into_owned();
+            self.warnings.push(format!(
+                "Replaced invalid UTF-8 in string {:?}: {}",
+                string,
+                error.utf8_error()
+            ));
+            return Ok(string);
+        }
+        Err(error.into())
+    }
+
+    /// Take the warnings recorded since warnings were last taken
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+/// Read an exact number of bytes into a buffer, replacing its contents.
+/// The buffer grows only as data is read, so a corrupt length that is larger than
+/// the available data does not cause a large allocation.
+pub fn read_bytes_into<R: Read>(
+    reader: &mut R,
+    length: u64,
+    buffer: &mut Vec<u8>,
+) -> std::io::Result<()> {
+    buffer.clear();
+    reader.take(length).read_to_end(buffer)?;
+    if (buffer.len() as u64) < length {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "failed to fill whole buffer",
+        ));
+    }
+    Ok(())
 }
 
 pub fn read_timestamp<R: Read, O: ByteOrderExt>(reader: &mut R) -> std::io::Result<Timestamp> {
@@ -376,10 +640,49 @@ mod test {
 
     use super::*;
 
+    #[test]
+    pub fn decode_values_le() {
+        let bytes = hex!("01 00 00 00 02 00 00 00 FF FF FF FF");
+        let mut values = vec![0i32; 3];
+        i32::decode_values::<LittleEndian>(&mut values, &bytes, 3).unwrap();
+
+        assert_eq!(values, vec![1, 2, -1]);
+    }
+
+    #[test]
+    pub fn decode_values_be() {
+        let bytes = hex!("00 01 00 02 FF FE");
+        let mut values = vec![0u16; 3];
+        u16::decode_values::<BigEndian>(&mut values, &bytes, 3).unwrap();
+
+        assert_eq!(values, vec![1, 2, 0xFFFE]);
+    }
+
+    #[test]
+    pub fn decode_float_values_be() {
+        let mut bytes = Vec::new();
+        bytes.extend(1.5f64.to_be_bytes());
+        bytes.extend((-2.25f64).to_be_bytes());
+        let mut values = vec![0.0f64; 2];
+        f64::decode_values::<BigEndian>(&mut values, &bytes, 2).unwrap();
+
+        assert_eq!(values, vec![1.5, -2.25]);
+    }
+
+    #[test]
+    pub fn decode_values_with_too_few_bytes() {
+        let bytes = hex!("00 01 00 02 FF");
+        let mut values = vec![0u16; 3];
+        let result = u16::decode_values::<BigEndian>(&mut values, &bytes, 3);
+
+        assert!(result.is_err());
+    }
+
     #[test]
     pub fn can_read_string_le() {
         let mut reader = Cursor::new(hex!("05 00 00 00 68 65 6C 6C 6F"));
-        let value = read_string::<_, LittleEndian>(&mut reader).unwrap();
+        let value =
+            read_string::<_, LittleEndian>(&mut reader, &mut StringDecoder::default()).unwrap();
 
         assert_eq!(value, "hello");
     }
@@ -387,8 +690,38 @@ mod test {
     #[test]
     pub fn can_read_string_be() {
         let mut reader = Cursor::new(hex!("00 00 00 05 68 65 6C 6C 6F"));
-        let value = read_string::<_, BigEndian>(&mut reader).unwrap();
+        let value =
+            read_string::<_, BigEndian>(&mut reader, &mut StringDecoder::default()).unwrap();
 
         assert_eq!(value, "hello");
     }
+
+    #[test]
+    pub fn decode_invalid_utf8_lossy() {
+        let mut decoder = StringDecoder::new(&ReadOptions::new().lossy_utf8(true));
+        let value = decoder.decode(vec![0x61, 0xFF, 0x62]).unwrap();
+
+        assert_eq!(value, "a\u{FFFD}b");
+        assert_eq!(decoder.take_warnings().len(), 1);
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    pub fn decode_with_fallback_encoding() {
+        let options = ReadOptions::new().fallback_encoding(encoding_rs::WINDOWS_1252);
+        let mut decoder = StringDecoder::new(&options);
+
+        assert_eq!(decoder.decode(vec![0x43, 0xB0]).unwrap(), "C\u{B0}");
+        assert_eq!(decoder.decode("C\u{B0}".into()).unwrap(), "C\u{B0}");
+        assert!(decoder.take_warnings().is_empty());
+    }
+
+    #[test]
+    pub fn decode_invalid_utf8_strict() {
+        let mut decoder = StringDecoder::default();
+        let result = decoder.decode(vec![0x61, 0xFF, 0x62]);
+
+        assert!(matches!(result, Err(TdmsReadError::Utf8Error(_))));
+        assert!(decoder.take_warnings().is_empty());
+    }
 }
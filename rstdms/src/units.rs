@@ -0,0 +1,213 @@
+use crate::channel_data::ChannelData;
+use crate::error::{Result, TdmsReadError};
+use crate::properties::TdmsValue;
+use crate::property_names::UNIT_STRING;
+use crate::Channel;
+use std::io::{Read, Seek};
+use uom::si::f64::{
+    ElectricCurrent, ElectricPotential, ElectricalResistance, Force, Frequency, Length, Mass,
+    Power, Pressure, ThermodynamicTemperature, Time,
+};
+use uom::si::{
+    electric_current, electric_potential, electrical_resistance, force, frequency, length, mass,
+    power, pressure, thermodynamic_temperature, time,
+};
+
+/// An SI unit that can be parsed from a unit_string property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseUnit {
+    Volt,
+    Ampere,
+    Ohm,
+    Watt,
+    Hertz,
+    Second,
+    Metre,
+    Gram,
+    Newton,
+    Pascal,
+    Kelvin,
+    DegreeCelsius,
+}
+
+/// A unit parsed from a unit_string property, as a multiple of a base unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    pub base: BaseUnit,
+    /// Multiplier from an SI prefix, eg. 1e-3 for "mV"
+    pub multiplier: f64,
+}
+
+const BASE_UNIT_SYMBOLS: &[(&str, BaseUnit)] = &[
+    ("V", BaseUnit::Volt),
+    ("A", BaseUnit::Ampere),
+    ("Ohm", BaseUnit::Ohm),
+    ("Ohms", BaseUnit::Ohm),
+    ("\u{3A9}", BaseUnit::Ohm),
+    ("W", BaseUnit::Watt),
+    ("Hz", BaseUnit::Hertz),
+    ("s", BaseUnit::Second),
+    ("m", BaseUnit::Metre),
+    ("g", BaseUnit::Gram),
+    ("N", BaseUnit::Newton),
+    ("Pa", BaseUnit::Pascal),
+    ("K", BaseUnit::Kelvin),
+];
+
+const CELSIUS_SYMBOLS: &[&str] = &["\u{B0}C", "degC", "Deg C", "deg C"];
+
+const PREFIXES: &[(char, f64)] = &[
+    ('p', 1e-12),
+    ('n', 1e-9),
+    ('u', 1e-6),
+    ('\u{B5}', 1e-6),
+    ('m', 1e-3),
+    ('c', 1e-2),
+    ('k', 1e3),
+    ('M', 1e6),
+    ('G', 1e9),
+];
+
+impl Unit {
+    /// Parse a unit string such as "V", "mA" or "kPa".
+    /// Returns `None` if the unit is not recognised.
+    pub fn parse(unit_string: &str) -> Option<Unit> {
+        let unit_string = unit_string.trim();
+        if CELSIUS_SYMBOLS.contains(&unit_string) {
+            return Some(Unit {
+                base: BaseUnit::DegreeCelsius,
+                multiplier: 1.0,
+            });
+        }
+        if let Some(base) = base_unit(unit_string) {
+            return Some(Unit {
+                base,
+                multiplier: 1.0,
+            });
+        }
+        let mut chars = unit_string.chars();
+        let prefix = chars.next()?;
+        let (_, multiplier) = PREFIXES.iter().find(|(symbol, _)| *symbol == prefix)?;
+        base_unit(chars.as_str()).map(|base| Unit {
+            base,
+            multiplier: *multiplier,
+        })
+    }
+}
+
+fn base_unit(symbol: &str) -> Option<BaseUnit> {
+    BASE_UNIT_SYMBOLS
+        .iter()
+        .find(|(unit_symbol, _)| *unit_symbol == symbol)
+        .map(|(_, base)| *base)
+}
+
+/// A uom quantity that channel values can be converted to
+pub trait UnitQuantity: Sized {
+    /// Name of the kind of quantity, used in error messages
+    const KIND: &'static str;
+
+    /// Create a quantity from a value in the given unit,
+    /// or `None` if the unit has the wrong dimension
+    fn from_value(value: f64, unit: &Unit) -> Option<Self>;
+}
+
+macro_rules! impl_unit_quantity {
+    ($quantity:ty, $kind:expr, $($base:ident => $unit:ty),+) => {
+        impl UnitQuantity for $quantity {
+            const KIND: &'static str = $kind;
+
+            fn from_value(value: f64, unit: &Unit) -> Option<Self> {
+                match unit.base {
+                    $(BaseUnit::$base => Some(<$quantity>::new::<$unit>(value * unit.multiplier)),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+impl_unit_quantity!(ElectricPotential, "electric potential", Volt => electric_potential::volt);
+impl_unit_quantity!(ElectricCurrent, "electric current", Ampere => electric_current::ampere);
+impl_unit_quantity!(ElectricalResistance, "electrical resistance", Ohm => electrical_resistance::ohm);
+impl_unit_quantity!(Power, "power", Watt => power::watt);
+impl_unit_quantity!(Frequency, "frequency", Hertz => frequency::hertz);
+impl_unit_quantity!(Time, "time", Second => time::second);
+impl_unit_quantity!(Length, "length", Metre => length::meter);
+impl_unit_quantity!(Mass, "mass", Gram => mass::gram);
+impl_unit_quantity!(Force, "force", Newton => force::newton);
+impl_unit_quantity!(Pressure, "pressure", Pascal => pressure::pascal);
+impl_unit_quantity!(
+    ThermodynamicTemperature,
+    "temperature",
+    Kelvin => thermodynamic_temperature::kelvin,
+    DegreeCelsius => thermodynamic_temperature::degree_celsius
+);
+
+impl<'a, R: Read + Seek> Channel<'a, R> {
+    /// Get the unit of the channel values parsed from the unit_string property
+    pub fn unit(&self) -> Option<Unit> {
+        self.unit_string().and_then(Unit::parse)
+    }
+
+    /// Read all data for this numeric channel as quantities with the unit given by
+    /// the unit_string property, eg. `channel.read_quantities::<ElectricPotential>()`
+    pub fn read_quantities<Q: UnitQuantity>(&self) -> Result<Vec<Q>> {
+        let unit_string = match self.property(UNIT_STRING) {
+            Some(TdmsValue::String(unit_string)) => unit_string,
+            _ => {
+                return Err(TdmsReadError::MissingProperty {
+                    path: self.path(),
+                    name: UNIT_STRING,
+                })
+            }
+        };
+        let unit = Unit::parse(unit_string)
+            .filter(|unit| Q::from_value(0.0, unit).is_some())
+            .ok_or_else(|| TdmsReadError::IncompatibleUnit {
+                path: self.path(),
+                unit: unit_string.clone(),
+                quantity: Q::KIND,
+            })?;
+        let values = match self.read_data()? {
+            ChannelData::I8(values) => values.into_iter().map(f64::from).collect(),
+            ChannelData::I16(values) => values.into_iter().map(f64::from).collect(),
+            ChannelData::I32(values) => values.into_iter().map(f64::from).collect(),
+            ChannelData::I64(values) => values.into_iter().map(|v| v as f64).collect(),
+            ChannelData::U8(values) => values.into_iter().map(f64::from).collect(),
+            ChannelData::U16(values) => values.into_iter().map(f64::from).collect(),
+            ChannelData::U32(values) => values.into_iter().map(f64::from).collect(),
+            ChannelData::U64(values) => values.into_iter().map(|v| v as f64).collect(),
+            ChannelData::F32(values) => values.into_iter().map(f64::from).collect(),
+            ChannelData::F64(values) => values,
+            ChannelData::String(_) | ChannelData::Timestamp(_) => {
+                return Err(TdmsReadError::UnsupportedDataType(self.data_type()))
+            }
+        };
+        Ok(values
+            .into_iter()
+            .map(|value| Q::from_value(value, &unit).expect("Unit has been checked"))
+            .collect::<Vec<Q>>())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn parse_units() {
+        let parse = |unit_string| Unit::parse(unit_string).map(|unit| (unit.base, unit.multiplier));
+        assert_eq!(parse("V"), Some((BaseUnit::Volt, 1.0)));
+        assert_eq!(parse("mV"), Some((BaseUnit::Volt, 1e-3)));
+        assert_eq!(parse("kPa"), Some((BaseUnit::Pascal, 1e3)));
+        assert_eq!(parse("m"), Some((BaseUnit::Metre, 1.0)));
+        assert_eq!(parse("mm"), Some((BaseUnit::Metre, 1e-3)));
+        assert_eq!(parse("ms"), Some((BaseUnit::Second, 1e-3)));
+        assert_eq!(parse("\u{B5}A"), Some((BaseUnit::Ampere, 1e-6)));
+        assert_eq!(parse("Deg C"), Some((BaseUnit::DegreeCelsius, 1.0)));
+        assert_eq!(parse(" Ohms "), Some((BaseUnit::Ohm, 1.0)));
+        assert_eq!(parse("furlong"), None);
+        assert_eq!(parse(""), None);
+    }
+}
@@ -0,0 +1,69 @@
+use crate::error::Result;
+use crate::object_path::ObjectPathId;
+use crate::segment::DataChunk;
+use crate::types::NativeType;
+use crate::TdmsFile;
+use std::io::{Read, Seek};
+
+/// An iterator over the values of a channel, created by `Channel::iter_values`.
+///
+/// Data is read one block at a time as the iterator is advanced.
+/// If reading a block fails, the error is returned and iteration ends.
+pub struct ChannelValues<'a, R: Read + Seek, T> {
+    file: &'a TdmsFile<R>,
+    object_id: ObjectPathId,
+    blocks: std::vec::IntoIter<DataChunk>,
+    values: std::vec::IntoIter<T>,
+}
+
+impl<'a, R: Read + Seek, T: NativeType + Default + Clone> ChannelValues<'a, R, T> {
+    pub(crate) fn new(
+        file: &'a TdmsFile<R>,
+        object_id: ObjectPathId,
+        blocks: Vec<DataChunk>,
+    ) -> ChannelValues<'a, R, T> {
+        ChannelValues {
+            file,
+            object_id,
+            blocks: blocks.into_iter(),
+            values: Vec::new().into_iter(),
+        }
+    }
+
+    fn read_block(&self, block: &DataChunk) -> Result<Vec<T>> {
+        self.file
+            .tdms_reader
+            .check_values_memory_limit(self.object_id, block.number_of_values)?;
+        let mut values = vec![T::default(); block.number_of_values as usize];
+        let mut file_reader = self.file.file_reader.borrow_mut();
+        self.file.tdms_reader.chunk_decoded();
+        let result = if self.file.unbuffered_data_reads {
+            block.read_values(file_reader.get_mut(), &mut values)
+        } else {
+            block.read_values(&mut *file_reader, &mut values)
+        };
+        result
+            .map_err(|e| e.in_object(self.file.tdms_reader.object_path_string(self.object_id)))?;
+        Ok(values)
+    }
+}
+
+impl<'a, R: Read + Seek, T: NativeType + Default + Clone> Iterator for ChannelValues<'a, R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Result<T>> {
+        loop {
+            if let Some(value) = self.values.next() {
+                return Some(Ok(value));
+            }
+            let block = self.blocks.next()?;
+            match self.read_block(&block) {
+                Ok(values) => self.values = values.into_iter(),
+                Err(e) => {
+                    self.blocks = Vec::new().into_iter();
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::timestamp::Timestamp;
+use std::ops::Range;
+
+/// Tolerance in samples used when converting times to sample indexes,
+/// to allow for floating point error
+const TIME_TOLERANCE: f64 = 1e-6;
+
+/// The timing of regularly sampled waveform data
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaveformTiming {
+    /// Time of the first sample
+    pub start_time: Timestamp,
+    /// Time between samples in seconds
+    pub increment: f64,
+    /// Number of samples
+    pub len: u64,
+}
+
+impl WaveformTiming {
+    /// Get the index of the first sample at or after the given time,
+    /// clamped to the range of samples
+    pub fn index_at(&self, time: &Timestamp) -> u64 {
+        let offset =
+            (time.seconds_since(&self.start_time) / self.increment - TIME_TOLERANCE).ceil();
+        offset.clamp(0.0, self.len as f64) as u64
+    }
+
+    /// Get the time of the sample at the given index
+    pub fn time_at(&self, index: u64) -> Timestamp {
+        self.start_time.add_seconds(index as f64 * self.increment)
+    }
+
+    /// Get the time just after the last sample
+    pub fn end_time(&self) -> Timestamp {
+        self.time_at(self.len)
+    }
+}
+
+/// The overlapping time range of multiple waveforms
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaveformAlignment {
+    /// Start of the overlapping time range
+    pub start_time: Timestamp,
+    /// Duration of the overlapping time range in seconds
+    pub duration: f64,
+    /// For each waveform, the range of sample indexes within the overlapping time range.
+    /// The start of each range is the index offset of that waveform relative to the others.
+    pub ranges: Vec<Range<u64>>,
+}
+
+/// Align multiple waveforms onto a common time base, finding the time range where they
+/// all have data and the range of samples of each waveform within it.
+/// Returns None if there are no waveforms or they do not overlap.
+pub fn align_waveforms(timings: &[WaveformTiming]) -> Option<WaveformAlignment> {
+    let start_time = timings.iter().map(|t| t.start_time).max()?;
+    let end_time = timings.iter().map(|t| t.end_time()).min()?;
+    if end_time <= start_time {
+        return None;
+    }
+    let ranges = timings
+        .iter()
+        .map(|timing| timing.index_at(&start_time)..timing.index_at(&end_time))
+        .collect();
+    Some(WaveformAlignment {
+        start_time,
+        duration: end_time.seconds_since(&start_time),
+        ranges,
+    })
+}
@@ -0,0 +1,340 @@
+use crate::channel_data::ChannelData;
+use crate::error::Result;
+use crate::object_path::{path_from_channel, path_from_group};
+use crate::properties::{TdmsProperty, TdmsValue};
+use crate::tdms_reader::RAW_DATA_INDEX_NO_DATA;
+use crate::timestamp::Timestamp;
+use crate::toc::{TocFlag, TDMS_VERSION_2_0};
+use crate::types::TdsType;
+use crate::TdmsFile;
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::{Read, Seek, Write};
+
+/// An object to write in a segment, with its properties and any channel data
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteObject {
+    path: String,
+    properties: Vec<TdmsProperty>,
+    data: Option<ChannelData>,
+}
+
+impl WriteObject {
+    /// Create the root object of a file
+    pub fn root(properties: Vec<TdmsProperty>) -> WriteObject {
+        WriteObject {
+            path: "/".to_owned(),
+            properties,
+            data: None,
+        }
+    }
+
+    /// Create a group object
+    pub fn group(group_name: &str, properties: Vec<TdmsProperty>) -> WriteObject {
+        WriteObject {
+            path: path_from_group(group_name),
+            properties,
+            data: None,
+        }
+    }
+
+    /// Create a channel object with optional data to append to the channel
+    pub fn channel(
+        group_name: &str,
+        channel_name: &str,
+        properties: Vec<TdmsProperty>,
+        data: Option<ChannelData>,
+    ) -> WriteObject {
+        WriteObject {
+            path: path_from_channel(group_name, channel_name),
+            properties,
+            data,
+        }
+    }
+}
+
+/// Writes TDMS files one segment at a time.
+/// Data is written in little endian byte order and is not interleaved.
+pub struct TdmsWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> TdmsWriter<W> {
+    pub fn new(writer: W) -> TdmsWriter<W> {
+        TdmsWriter { writer }
+    }
+
+    /// Write a segment containing the given objects. Properties set in the segment
+    /// replace any previous values, and channel data is appended to any data written
+    /// in previous segments.
+    pub fn write_segment(&mut self, objects: &[WriteObject]) -> Result<()> {
+        let mut metadata = Vec::new();
+        let mut raw_data = Vec::new();
+        metadata.write_u32::<LittleEndian>(objects.len() as u32)?;
+        for object in objects {
+            write_string(&mut metadata, &object.path)?;
+            match &object.data {
+                Some(data) if !data.is_empty() => {
+                    let data_start = raw_data.len();
+                    write_channel_data(&mut raw_data, data)?;
+                    write_raw_data_index(
+                        &mut metadata,
+                        data,
+                        (raw_data.len() - data_start) as u64,
+                    )?;
+                }
+                _ => metadata.write_u32::<LittleEndian>(RAW_DATA_INDEX_NO_DATA)?,
+            }
+            write_properties(&mut metadata, &object.properties)?;
+        }
+
+        let mut toc_mask: u32 = TocFlag::MetaData.into();
+        toc_mask |= u32::from(TocFlag::NewObjList);
+        if !raw_data.is_empty() {
+            toc_mask |= u32::from(TocFlag::RawData);
+        }
+        self.write_segment_contents(toc_mask, &metadata, &raw_data)
+    }
+
+    /// Write a segment containing only the properties of the given objects, which are
+    /// merged with the objects of previous segments rather than replacing them. This allows
+    /// properties of an existing file to be changed by appending a segment, without
+    /// rewriting the file's data. Any channel data of the objects is not written.
+    pub fn write_properties(&mut self, objects: &[WriteObject]) -> Result<()> {
+        let mut metadata = Vec::new();
+        metadata.write_u32::<LittleEndian>(objects.len() as u32)?;
+        for object in objects {
+            write_string(&mut metadata, &object.path)?;
+            metadata.write_u32::<LittleEndian>(RAW_DATA_INDEX_NO_DATA)?;
+            write_properties(&mut metadata, &object.properties)?;
+        }
+        self.write_segment_contents(TocFlag::MetaData.into(), &metadata, &[])
+    }
+
+    fn write_segment_contents(
+        &mut self,
+        toc_mask: u32,
+        metadata: &[u8],
+        raw_data: &[u8],
+    ) -> Result<()> {
+        self.writer.write_all(b"TDSm")?;
+        self.writer.write_u32::<LittleEndian>(toc_mask)?;
+        self.writer.write_u32::<LittleEndian>(TDMS_VERSION_2_0)?;
+        self.writer
+            .write_u64::<LittleEndian>((metadata.len() + raw_data.len()) as u64)?;
+        self.writer
+            .write_u64::<LittleEndian>(metadata.len() as u64)?;
+        self.writer.write_all(metadata)?;
+        self.writer.write_all(raw_data)?;
+        Ok(())
+    }
+
+    /// Flush and return the underlying writer
+    pub fn into_inner(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<R: Read + Seek> TdmsFile<R> {
+    /// Copy the file to a new TDMS file, allowing the properties of each object to be
+    /// modified. `map_properties` is called with the path of each object, eg. `/'Group'`,
+    /// and its properties. Each group is written as one segment with all of its channel data.
+    pub fn rewrite<W, F>(&self, writer: W, map_properties: F) -> Result<W>
+    where
+        W: Write,
+        F: FnMut(&str, &mut Vec<TdmsProperty>),
+    {
+        self.rewrite_objects(
+            writer,
+            |group_name| group_name.to_owned(),
+            |_, channel_name| channel_name.to_owned(),
+            map_properties,
+        )
+    }
+
+    /// Copy the file to a new TDMS file with groups and channels renamed, preserving all
+    /// data and properties. `rename_group` is called with the name of each group and
+    /// `rename_channel` with the original group name and the name of each channel,
+    /// and each returns the name to write. Groups or channels given the same name as another
+    /// are merged, so callers should check that new names are unique.
+    pub fn rewrite_renamed<W, G, C>(
+        &self,
+        writer: W,
+        rename_group: G,
+        rename_channel: C,
+    ) -> Result<W>
+    where
+        W: Write,
+        G: FnMut(&str) -> String,
+        C: FnMut(&str, &str) -> String,
+    {
+        self.rewrite_objects(writer, rename_group, rename_channel, |_, _| {})
+    }
+
+    fn rewrite_objects<W, G, C, F>(
+        &self,
+        writer: W,
+        mut rename_group: G,
+        mut rename_channel: C,
+        mut map_properties: F,
+    ) -> Result<W>
+    where
+        W: Write,
+        G: FnMut(&str) -> String,
+        C: FnMut(&str, &str) -> String,
+        F: FnMut(&str, &mut Vec<TdmsProperty>),
+    {
+        let mut writer = TdmsWriter::new(writer);
+        let mut root = WriteObject::root(self.properties().clone());
+        map_properties(&root.path, &mut root.properties);
+        writer.write_segment(&[root])?;
+        for group in self.groups() {
+            let group_name = rename_group(group.name());
+            let mut objects = vec![WriteObject::group(&group_name, group.properties().clone())];
+            for channel in group.channels() {
+                let data = if channel.is_empty() {
+                    None
+                } else {
+                    Some(channel.read_data()?)
+                };
+                objects.push(WriteObject::channel(
+                    &group_name,
+                    &rename_channel(group.name(), channel.name()),
+                    channel.properties().clone(),
+                    data,
+                ));
+            }
+            for object in &mut objects {
+                map_properties(&object.path, &mut object.properties);
+            }
+            writer.write_segment(&objects)?;
+        }
+        writer.into_inner()
+    }
+}
+
+fn write_raw_data_index(bytes: &mut Vec<u8>, data: &ChannelData, data_size: u64) -> Result<()> {
+    let data_type = channel_data_type(data);
+    let is_string = matches!(data_type, TdsType::String);
+    // Length of the index information, including this length field
+    bytes.write_u32::<LittleEndian>(if is_string { 28 } else { 20 })?;
+    bytes.write_u32::<LittleEndian>(data_type as u32)?;
+    // Dimension is always 1
+    bytes.write_u32::<LittleEndian>(1)?;
+    bytes.write_u64::<LittleEndian>(data.len() as u64)?;
+    if is_string {
+        bytes.write_u64::<LittleEndian>(data_size)?;
+    }
+    Ok(())
+}
+
+fn channel_data_type(data: &ChannelData) -> TdsType {
+    match data {
+        ChannelData::I8(_) => TdsType::I8,
+        ChannelData::I16(_) => TdsType::I16,
+        ChannelData::I32(_) => TdsType::I32,
+        ChannelData::I64(_) => TdsType::I64,
+        ChannelData::U8(_) => TdsType::U8,
+        ChannelData::U16(_) => TdsType::U16,
+        ChannelData::U32(_) => TdsType::U32,
+        ChannelData::U64(_) => TdsType::U64,
+        ChannelData::F32(_) => TdsType::SingleFloat,
+        ChannelData::F64(_) => TdsType::DoubleFloat,
+        ChannelData::String(_) => TdsType::String,
+        ChannelData::Timestamp(_) => TdsType::TimeStamp,
+    }
+}
+
+pub(crate) fn write_channel_data(bytes: &mut Vec<u8>, data: &ChannelData) -> Result<()> {
+    match data {
+        ChannelData::I8(values) => values.iter().try_for_each(|v| bytes.write_i8(*v))?,
+        ChannelData::I16(values) => values
+            .iter()
+            .try_for_each(|v| bytes.write_i16::<LittleEndian>(*v))?,
+        ChannelData::I32(values) => values
+            .iter()
+            .try_for_each(|v| bytes.write_i32::<LittleEndian>(*v))?,
+        ChannelData::I64(values) => values
+            .iter()
+            .try_for_each(|v| bytes.write_i64::<LittleEndian>(*v))?,
+        ChannelData::U8(values) => bytes.extend_from_slice(values),
+        ChannelData::U16(values) => values
+            .iter()
+            .try_for_each(|v| bytes.write_u16::<LittleEndian>(*v))?,
+        ChannelData::U32(values) => values
+            .iter()
+            .try_for_each(|v| bytes.write_u32::<LittleEndian>(*v))?,
+        ChannelData::U64(values) => values
+            .iter()
+            .try_for_each(|v| bytes.write_u64::<LittleEndian>(*v))?,
+        ChannelData::F32(values) => values
+            .iter()
+            .try_for_each(|v| bytes.write_f32::<LittleEndian>(*v))?,
+        ChannelData::F64(values) => values
+            .iter()
+            .try_for_each(|v| bytes.write_f64::<LittleEndian>(*v))?,
+        ChannelData::String(values) => {
+            // The offset of the end of each string, relative to the start of
+            // the string contents, is written before the string contents
+            let mut end_offset = 0_u32;
+            for value in values {
+                end_offset = u32::try_from(value.len())
+                    .ok()
+                    .and_then(|len| end_offset.checked_add(len))
+                    .ok_or_else(|| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            "String data is too large to write in one segment",
+                        )
+                    })?;
+                bytes.write_u32::<LittleEndian>(end_offset)?;
+            }
+            for value in values {
+                bytes.extend_from_slice(value.as_bytes());
+            }
+        }
+        ChannelData::Timestamp(values) => {
+            values.iter().try_for_each(|v| write_timestamp(bytes, v))?
+        }
+    }
+    Ok(())
+}
+
+fn write_properties(bytes: &mut Vec<u8>, properties: &[TdmsProperty]) -> Result<()> {
+    bytes.write_u32::<LittleEndian>(properties.len() as u32)?;
+    for property in properties {
+        write_string(bytes, &property.name)?;
+        bytes.write_u32::<LittleEndian>(property.value.data_type() as u32)?;
+        write_value(bytes, &property.value)?;
+    }
+    Ok(())
+}
+
+fn write_value(bytes: &mut Vec<u8>, value: &TdmsValue) -> Result<()> {
+    match value {
+        TdmsValue::Int8(v) => bytes.write_i8(*v)?,
+        TdmsValue::Int16(v) => bytes.write_i16::<LittleEndian>(*v)?,
+        TdmsValue::Int32(v) => bytes.write_i32::<LittleEndian>(*v)?,
+        TdmsValue::Int64(v) => bytes.write_i64::<LittleEndian>(*v)?,
+        TdmsValue::Uint8(v) => bytes.write_u8(*v)?,
+        TdmsValue::Uint16(v) => bytes.write_u16::<LittleEndian>(*v)?,
+        TdmsValue::Uint32(v) => bytes.write_u32::<LittleEndian>(*v)?,
+        TdmsValue::Uint64(v) => bytes.write_u64::<LittleEndian>(*v)?,
+        TdmsValue::Float32(v) => bytes.write_f32::<LittleEndian>(*v)?,
+        TdmsValue::Float64(v) => bytes.write_f64::<LittleEndian>(*v)?,
+        TdmsValue::String(v) => write_string(bytes, v)?,
+        TdmsValue::Timestamp(v) => write_timestamp(bytes, v)?,
+    }
+    Ok(())
+}
+
+fn write_string(bytes: &mut Vec<u8>, value: &str) -> std::io::Result<()> {
+    bytes.write_u32::<LittleEndian>(value.len() as u32)?;
+    bytes.extend_from_slice(value.as_bytes());
+    Ok(())
+}
+
+fn write_timestamp(bytes: &mut Vec<u8>, value: &Timestamp) -> std::io::Result<()> {
+    bytes.write_u64::<LittleEndian>(value.second_fractions)?;
+    bytes.write_i64::<LittleEndian>(value.seconds)
+}
@@ -0,0 +1,152 @@
+#![allow(dead_code)]
+
+extern crate hex_literal;
+
+use hex_literal::hex;
+use std::io::Cursor;
+
+pub struct TestFile {
+    bytes: Vec<u8>,
+    version: u32,
+}
+
+pub const TOC_METADATA: u32 = 1 << 1;
+pub const TOC_NEW_OBJ_LIST: u32 = 1 << 2;
+pub const TOC_RAW_DATA: u32 = 1 << 3;
+pub const TOC_INTERLEAVED_DATA: u32 = 1 << 5;
+
+impl TestFile {
+    pub fn new() -> TestFile {
+        TestFile::with_version(4713)
+    }
+
+    pub fn with_version(version: u32) -> TestFile {
+        TestFile {
+            bytes: Vec::new(),
+            version,
+        }
+    }
+
+    pub fn add_segment(&mut self, toc_mask: u32, metadata_bytes: &Vec<u8>, data_bytes: &Vec<u8>) {
+        let next_segment_offset = metadata_bytes.len() + data_bytes.len();
+        self.add_segment_with_offset(
+            toc_mask,
+            next_segment_offset as u64,
+            metadata_bytes,
+            data_bytes,
+        );
+    }
+
+    /// Add a segment that was not finalized, so has an unknown next segment offset
+    pub fn add_incomplete_segment(
+        &mut self,
+        toc_mask: u32,
+        metadata_bytes: &Vec<u8>,
+        data_bytes: &Vec<u8>,
+    ) {
+        self.add_segment_with_offset(toc_mask, u64::MAX, metadata_bytes, data_bytes);
+    }
+
+    pub fn add_segment_with_offset(
+        &mut self,
+        toc_mask: u32,
+        next_segment_offset: u64,
+        metadata_bytes: &Vec<u8>,
+        data_bytes: &Vec<u8>,
+    ) {
+        // TDSm tag
+        self.bytes.extend(&hex!("54 44 53 6D"));
+
+        // ToC mask
+        self.bytes.extend(&toc_mask.to_le_bytes());
+
+        // Version number
+        self.bytes.extend(&self.version.to_le_bytes());
+
+        // Offsets
+        let raw_data_offset = metadata_bytes.len();
+        self.bytes.extend(&next_segment_offset.to_le_bytes());
+        self.bytes.extend(&(raw_data_offset as u64).to_le_bytes());
+
+        self.bytes.extend(metadata_bytes);
+        self.bytes.extend(data_bytes);
+    }
+
+    pub fn into_cursor(self) -> Cursor<Vec<u8>> {
+        Cursor::new(self.bytes)
+    }
+}
+
+pub fn object_metadata(
+    path: &'static str,
+    raw_data_index: &[u8],
+    properties: Vec<(&'static str, u32, &[u8])>,
+) -> Vec<u8> {
+    let mut metadata_bytes = Vec::new();
+    write_string(path, &mut metadata_bytes);
+    metadata_bytes.extend(raw_data_index);
+    metadata_bytes.extend(&((properties.len() as u32).to_le_bytes()));
+    for (name, type_id, val) in properties {
+        write_string(name, &mut metadata_bytes);
+        metadata_bytes.extend(&(type_id.to_le_bytes()));
+        metadata_bytes.extend(val);
+    }
+    metadata_bytes
+}
+
+pub fn raw_data_index(data_type: u32, num_values: u64) -> Vec<u8> {
+    raw_data_index_with_byte_len(data_type, num_values, None)
+}
+
+pub fn raw_data_index_with_byte_len(
+    data_type: u32,
+    num_values: u64,
+    num_bytes: Option<u64>,
+) -> Vec<u8> {
+    raw_data_index_with_dimension(data_type, 1, num_values, num_bytes)
+}
+
+pub fn raw_data_index_with_dimension(
+    data_type: u32,
+    dimension: u32,
+    num_values: u64,
+    num_bytes: Option<u64>,
+) -> Vec<u8> {
+    let mut index_bytes = Vec::new();
+    index_bytes.extend(&(20_u32.to_le_bytes())); // Raw data index length
+    index_bytes.extend(&(data_type.to_le_bytes())); // Data type
+    index_bytes.extend(&(dimension.to_le_bytes())); // Dimension
+    index_bytes.extend(&(num_values.to_le_bytes())); // Number of values
+    if let Some(num_bytes) = num_bytes {
+        index_bytes.extend(&(num_bytes.to_le_bytes())); // Number of values
+    }
+    index_bytes
+}
+
+pub fn metadata(objects: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut metadata_bytes = Vec::new();
+    metadata_bytes.extend(&((objects.len() as u32).to_le_bytes()));
+    for object in objects {
+        metadata_bytes.extend(object);
+    }
+    metadata_bytes
+}
+
+pub fn data_bytes_i32(data: Vec<i32>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for val in data {
+        bytes.extend(&(val.to_le_bytes()));
+    }
+    bytes
+}
+
+pub fn timestamp_bytes(seconds: i64) -> Vec<u8> {
+    let mut bytes = 0_u64.to_le_bytes().to_vec();
+    bytes.extend(seconds.to_le_bytes());
+    bytes
+}
+
+pub fn write_string(string: &str, bytes: &mut Vec<u8>) {
+    bytes.extend(&(string.len() as u32).to_le_bytes());
+    bytes.extend(string.bytes());
+}
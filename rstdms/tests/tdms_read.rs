@@ -1,108 +1,18 @@
 extern crate hex_literal;
 
-use hex_literal::hex;
-use std::io::Cursor;
-
-use rstdms::TdmsFile;
-
-struct TestFile {
-    bytes: Vec<u8>,
-}
-
-const TOC_METADATA: u32 = 1 << 1;
-const TOC_NEW_OBJ_LIST: u32 = 1 << 2;
-const TOC_RAW_DATA: u32 = 1 << 3;
-const TOC_INTERLEAVED_DATA: u32 = 1 << 5;
-
-impl TestFile {
-    fn new() -> TestFile {
-        TestFile { bytes: Vec::new() }
-    }
-
-    fn add_segment(&mut self, toc_mask: u32, metadata_bytes: &Vec<u8>, data_bytes: &Vec<u8>) {
-        // TDSm tag
-        self.bytes.extend(&hex!("54 44 53 6D"));
-
-        // ToC mask
-        self.bytes.extend(&toc_mask.to_le_bytes());
-
-        // Version number
-        self.bytes.extend(&hex!("69 12 00 00"));
-
-        // Offsets
-        let raw_data_offset = metadata_bytes.len();
-        let next_segment_offset = raw_data_offset + data_bytes.len();
-        self.bytes
-            .extend(&(next_segment_offset as u64).to_le_bytes());
-        self.bytes.extend(&(raw_data_offset as u64).to_le_bytes());
-
-        self.bytes.extend(metadata_bytes);
-        self.bytes.extend(data_bytes);
-    }
-
-    fn to_cursor(self) -> Cursor<Vec<u8>> {
-        Cursor::new(self.bytes)
-    }
-}
+mod common;
 
-fn object_metadata(
-    path: &'static str,
-    raw_data_index: &[u8],
-    properties: Vec<(&'static str, u32, &[u8])>,
-) -> Vec<u8> {
-    let mut metadata_bytes = Vec::new();
-    write_string(path, &mut metadata_bytes);
-    metadata_bytes.extend(raw_data_index);
-    metadata_bytes.extend(&((properties.len() as u32).to_le_bytes()));
-    for (name, type_id, val) in properties {
-        write_string(name, &mut metadata_bytes);
-        metadata_bytes.extend(&(type_id.to_le_bytes()));
-        metadata_bytes.extend(val);
-    }
-    metadata_bytes
-}
-
-fn raw_data_index(data_type: u32, num_values: u64) -> Vec<u8> {
-    raw_data_index_with_byte_len(data_type, num_values, None)
-}
-
-fn raw_data_index_with_byte_len(
-    data_type: u32,
-    num_values: u64,
-    num_bytes: Option<u64>,
-) -> Vec<u8> {
-    let mut index_bytes = Vec::new();
-    index_bytes.extend(&(20_u32.to_le_bytes())); // Raw data index length
-    index_bytes.extend(&(data_type.to_le_bytes())); // Data type
-    index_bytes.extend(&(1_u32.to_le_bytes())); // Dimension
-    index_bytes.extend(&(num_values.to_le_bytes())); // Number of values
-    if let Some(num_bytes) = num_bytes {
-        index_bytes.extend(&(num_bytes.to_le_bytes())); // Number of values
-    }
-    index_bytes
-}
-
-fn metadata(objects: Vec<Vec<u8>>) -> Vec<u8> {
-    let mut metadata_bytes = Vec::new();
-    metadata_bytes.extend(&((objects.len() as u32).to_le_bytes()));
-    for object in objects {
-        metadata_bytes.extend(object);
-    }
-    metadata_bytes
-}
-
-fn data_bytes_i32(data: Vec<i32>) -> Vec<u8> {
-    let mut bytes = Vec::new();
-    for val in data {
-        bytes.extend(&(val.to_le_bytes()));
-    }
-    bytes
-}
+use common::*;
+use hex_literal::hex;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
-fn write_string(string: &str, bytes: &mut Vec<u8>) {
-    bytes.extend(&(string.len() as u32).to_le_bytes());
-    bytes.extend(string.bytes());
-}
+use rstdms::{
+    align_waveforms, read_concatenated, write_csv, Channel, ChannelData, CsvOptions, NativeTypeId,
+    PropertyRevision, QualityOptions, ReadOptions, ReadProgress, TdmsFile, TdmsObject,
+    TdmsProperty, TdmsReadError, TdmsValue, TdsType, Timestamp, TDMS_VERSION_1_0, TDMS_VERSION_2_0,
+};
 
 #[test]
 fn read_metadata() {
@@ -119,7 +29,7 @@ fn read_metadata() {
     let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
     test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
 
-    let tdms_file = TdmsFile::new(test_file.to_cursor());
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
 
     assert!(tdms_file.is_ok(), "Got error: {:?}", tdms_file.unwrap_err());
 
@@ -150,7 +60,7 @@ fn read_metadata_with_repeated_raw_data_index() {
     )]);
     test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
 
-    let tdms_file = TdmsFile::new(test_file.to_cursor());
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
 
     assert!(tdms_file.is_ok(), "Got error: {:?}", tdms_file.unwrap_err());
 
@@ -175,16 +85,16 @@ fn multiple_channels() {
     let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
     test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
 
-    let tdms_file = TdmsFile::new(test_file.to_cursor());
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
 
     assert!(tdms_file.is_ok(), "Got error: {:?}", tdms_file.unwrap_err());
 
     let tdms_file = tdms_file.unwrap();
     let group = tdms_file.group("Group").unwrap();
 
-    let expected_data = vec![vec![1, 2], vec![3, 4, 5], vec![6, 7, 8, 9]];
+    let expected_data = [vec![1, 2], vec![3, 4, 5], vec![6, 7, 8, 9]];
 
-    for (i, channel_name) in vec!["Channel1", "Channel2", "Channel3"].iter().enumerate() {
+    for (i, channel_name) in ["Channel1", "Channel2", "Channel3"].iter().enumerate() {
         let channel = group.channel(channel_name).unwrap();
         let mut data: Vec<i32> = vec![0; channel.len() as usize];
         channel.read_all_data(&mut data[..]).unwrap();
@@ -205,16 +115,16 @@ fn interleaved_data() {
     let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_INTERLEAVED_DATA;
     test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
 
-    let tdms_file = TdmsFile::new(test_file.to_cursor());
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
 
     assert!(tdms_file.is_ok(), "Got error: {:?}", tdms_file.unwrap_err());
 
     let tdms_file = tdms_file.unwrap();
     let group = tdms_file.group("Group").unwrap();
 
-    let expected_data = vec![vec![1, 4, 7, 10], vec![2, 5, 8, 11], vec![3, 6, 9, 12]];
+    let expected_data = [vec![1, 4, 7, 10], vec![2, 5, 8, 11], vec![3, 6, 9, 12]];
 
-    for (i, channel_name) in vec!["Channel1", "Channel2", "Channel3"].iter().enumerate() {
+    for (i, channel_name) in ["Channel1", "Channel2", "Channel3"].iter().enumerate() {
         let channel = group.channel(channel_name).unwrap();
         let mut data: Vec<i32> = vec![0; channel.len() as usize];
         channel.read_all_data(&mut data[..]).unwrap();
@@ -238,13 +148,13 @@ fn iterate_over_objects() {
     let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST;
     test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
 
-    let tdms_file = TdmsFile::new(test_file.to_cursor());
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
 
     assert!(tdms_file.is_ok(), "Got error: {:?}", tdms_file.unwrap_err());
 
     let tdms_file = tdms_file.unwrap();
-    let expected_groups = vec!["Group1", "Group2"];
-    let expected_channels = vec![
+    let expected_groups = ["Group1", "Group2"];
+    let expected_channels = [
         vec!["Channel1_1", "Channel1_2"],
         vec!["Channel2_1", "Channel2_2"],
     ];
@@ -259,11 +169,13 @@ fn iterate_over_objects() {
 #[test]
 fn read_string_data() {
     let strings = vec!["Hello", "World!"];
-    // Total data size is the size of each string plus 4 bytes per string for the lengths
+    // Total data size is the size of each string plus 4 bytes per string for the end offsets
     let byte_len = strings.iter().map(|s| s.len() as u64 + 4).sum();
     let mut data_bytes = Vec::new();
+    let mut end_offset = 0_u32;
     for s in &strings {
-        data_bytes.extend((s.len() as u32).to_le_bytes());
+        end_offset += s.len() as u32;
+        data_bytes.extend(end_offset.to_le_bytes());
     }
     for s in &strings {
         data_bytes.extend(s.as_bytes());
@@ -279,7 +191,7 @@ fn read_string_data() {
     let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
     test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
 
-    let tdms_file = TdmsFile::new(test_file.to_cursor());
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
 
     assert!(tdms_file.is_ok(), "Got error: {:?}", tdms_file.unwrap_err());
 
@@ -292,6 +204,96 @@ fn read_string_data() {
     assert_eq!(data, vec!["Hello".to_string(), "World!".to_string()]);
 }
 
+#[test]
+fn read_string_data_in_ni_layout() {
+    // A complete segment laid out as described by the NI TDMS file format documentation,
+    // where string data starts with the offset of the end of each string
+    let bytes = hex!(
+        "54 44 53 6D 0E 00 00 00 69 12 00 00"
+        "7A 00 00 00 00 00 00 00 61 00 00 00 00 00 00 00"
+        "03 00 00 00"
+        "01 00 00 00 2F FF FF FF FF 00 00 00 00"
+        "08 00 00 00 2F 27 47 72 6F 75 70 27 FF FF FF FF 00 00 00 00"
+        "18 00 00 00 2F 27 47 72 6F 75 70 27 2F 27 53 74 72 69 6E 67 43 68 61 6E 6E 65 6C 27"
+        "1C 00 00 00 20 00 00 00 01 00 00 00 02 00 00 00 00 00 00 00 19 00 00 00 00 00 00 00"
+        "00 00 00 00"
+        "07 00 00 00 11 00 00 00"
+        "61 62 63 64 65 66 67 71 77 65 72 74 79 75 69 6F 70"
+    );
+
+    let tdms_file = TdmsFile::new(Cursor::new(bytes.to_vec())).unwrap();
+    let channel = tdms_file
+        .group("Group")
+        .unwrap()
+        .channel("StringChannel")
+        .unwrap();
+    let mut data = vec![String::new(); channel.len() as usize];
+    channel.read_all_data(&mut data[..]).unwrap();
+
+    assert_eq!(data, vec!["abcdefg".to_string(), "qwertyuiop".to_string()]);
+}
+
+#[test]
+fn read_string_data_with_decreasing_offset() {
+    let mut data_bytes = Vec::new();
+    data_bytes.extend(5_u32.to_le_bytes());
+    data_bytes.extend(3_u32.to_le_bytes());
+    data_bytes.extend(b"hello");
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index_with_byte_len(0x20, 2, Some(13)),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let channel = tdms_file
+        .group("Group")
+        .unwrap()
+        .channel("Channel1")
+        .unwrap();
+    let mut data = vec![String::new(); 2];
+    let err = channel.read_all_data(&mut data[..]).unwrap_err();
+
+    assert!(matches!(
+        err.without_context(),
+        TdmsReadError::InvalidStringOffset {
+            offset: 3,
+            previous: 5
+        }
+    ));
+}
+
+#[test]
+fn read_data_with_dimension_greater_than_one() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index_with_dimension(3, 2, 3, None),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![1, 2, 3, 4, 5, 6]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    assert_eq!(channel.dimension(), 2);
+    assert_eq!(channel.len(), 6);
+    assert_eq!(channel.shape(), vec![3, 2]);
+    assert_eq!(
+        channel.read_data().unwrap(),
+        ChannelData::I32(vec![1, 2, 3, 4, 5, 6])
+    );
+}
+
 #[test]
 fn repeated_segment_data() {
     let mut test_file = TestFile::new();
@@ -304,19 +306,20 @@ fn repeated_segment_data() {
     let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
     test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
 
-    let tdms_file = TdmsFile::new(test_file.to_cursor());
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
 
     assert!(tdms_file.is_ok(), "Got error: {:?}", tdms_file.unwrap_err());
 
     let tdms_file = tdms_file.unwrap();
     let group = tdms_file.group("Group").unwrap();
 
-    let expected_data = vec![
+    let expected_data = [
         vec![1, 2, 10, 11, 19, 20],
         vec![3, 4, 5, 12, 13, 14, 21, 22, 23],
-        vec![6, 7, 8, 9, 15, 16, 17, 18, 24, 25, 26, 27]];
+        vec![6, 7, 8, 9, 15, 16, 17, 18, 24, 25, 26, 27],
+    ];
 
-    for (i, channel_name) in vec!["Channel1", "Channel2", "Channel3"].iter().enumerate() {
+    for (i, channel_name) in ["Channel1", "Channel2", "Channel3"].iter().enumerate() {
         let channel = group.channel(channel_name).unwrap();
         let mut data: Vec<i32> = vec![0; channel.len() as usize];
         channel.read_all_data(&mut data[..]).unwrap();
@@ -336,22 +339,1810 @@ fn repeated_interleaved_segment_data() {
     let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_INTERLEAVED_DATA;
     test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
 
-    let tdms_file = TdmsFile::new(test_file.to_cursor());
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
 
     assert!(tdms_file.is_ok(), "Got error: {:?}", tdms_file.unwrap_err());
 
     let tdms_file = tdms_file.unwrap();
     let group = tdms_file.group("Group").unwrap();
 
-    let expected_data = vec![
+    let expected_data = [
         vec![0, 3, 6, 9, 12, 15],
         vec![1, 4, 7, 10, 13, 16],
-        vec![2, 5, 8, 11, 14, 17]];
+        vec![2, 5, 8, 11, 14, 17],
+    ];
 
-    for (i, channel_name) in vec!["Channel1", "Channel2", "Channel3"].iter().enumerate() {
+    for (i, channel_name) in ["Channel1", "Channel2", "Channel3"].iter().enumerate() {
         let channel = group.channel(channel_name).unwrap();
         let mut data: Vec<i32> = vec![0; channel.len() as usize];
         channel.read_all_data(&mut data[..]).unwrap();
         assert_eq!(data, expected_data[i]);
     }
 }
+
+#[test]
+fn read_all_channels_in_group() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(10, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel3'", &raw_data_index(3, 1), Vec::new()),
+        object_metadata("/'Other'/'Channel1'", &raw_data_index(3, 1), Vec::new()),
+    ]);
+    let mut data_bytes = data_bytes_i32(vec![1, 2]);
+    data_bytes.extend(1.5_f64.to_le_bytes());
+    data_bytes.extend(2.5_f64.to_le_bytes());
+    data_bytes.extend(data_bytes_i32(vec![3, 4]));
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel3'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_INTERLEAVED_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![10, 20, 11, 21]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+
+    let channel_data = group.read_all_channels().unwrap();
+
+    assert_eq!(channel_data.len(), 3);
+    assert_eq!(
+        channel_data["Channel1"],
+        ChannelData::I32(vec![1, 2, 10, 11])
+    );
+    assert_eq!(channel_data["Channel2"], ChannelData::F64(vec![1.5, 2.5]));
+    assert_eq!(channel_data["Channel3"], ChannelData::I32(vec![3, 20, 21]));
+}
+
+#[test]
+fn read_data_with_positioned_reads() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(
+        TOC_RAW_DATA | TOC_INTERLEAVED_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![5, 6, 7, 8]),
+    );
+    let bytes = test_file.into_cursor().into_inner();
+
+    let tdms_file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel2").unwrap();
+    let mut data: Vec<i32> = vec![0; channel.len() as usize];
+    channel.read_all_data_at(&bytes, &mut data[..]).unwrap();
+
+    assert_eq!(data, vec![3, 4, 6, 8]);
+}
+
+#[test]
+fn read_from_bytes() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![5, 6, 7, 8]));
+    let bytes = test_file.into_cursor().into_inner();
+
+    let borrowed_file = TdmsFile::from_slice(&bytes).unwrap();
+    let channel_len = borrowed_file
+        .group("Group")
+        .and_then(|g| g.channel("Channel2").map(|c| c.len()));
+    assert_eq!(channel_len, Some(4));
+
+    let tdms_file = TdmsFile::from_bytes(bytes).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel2").unwrap();
+    let mut data: Vec<i32> = vec![0; channel.len() as usize];
+    channel.read_all_data(&mut data[..]).unwrap();
+
+    assert_eq!(data, vec![3, 4, 7, 8]);
+}
+
+#[test]
+fn read_incomplete_final_segment() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    // Final segment has one complete chunk of data followed by a partially written chunk
+    test_file.add_incomplete_segment(
+        TOC_RAW_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![5, 6, 7, 8, 9, 10]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel2").unwrap();
+    let mut data: Vec<i32> = vec![0; channel.len() as usize];
+    channel.read_all_data(&mut data[..]).unwrap();
+
+    assert_eq!(data, vec![3, 4, 7, 8]);
+}
+
+#[test]
+fn error_for_corrupt_number_of_objects() {
+    let mut test_file = TestFile::new();
+    let mut metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    metadata_bytes[0..4].copy_from_slice(&0xFFFFFFF0_u32.to_le_bytes());
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
+
+    assert!(tdms_file.is_err());
+}
+
+#[test]
+fn error_for_corrupt_string_length() {
+    let mut test_file = TestFile::new();
+    let mut metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    // Length of the object path
+    metadata_bytes[4..8].copy_from_slice(&0xFFFFFFF0_u32.to_le_bytes());
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
+
+    assert!(tdms_file.is_err());
+}
+
+#[test]
+fn error_for_corrupt_number_of_values() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 0x0FFFFFFFFFFFFFFF),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor());
+
+    assert!(tdms_file.is_err());
+}
+
+#[test]
+fn error_for_next_segment_offset_that_does_not_advance() {
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    let data_bytes = data_bytes_i32(vec![1, 2]);
+
+    // Offsets that would overflow back to the segment start, or point before the raw data
+    for next_segment_offset in [u64::MAX - 27, 0, 4] {
+        let mut test_file = TestFile::new();
+        test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
+        test_file.add_segment_with_offset(
+            toc_mask,
+            next_segment_offset,
+            &metadata_bytes,
+            &data_bytes,
+        );
+
+        let err = TdmsFile::new(test_file.into_cursor()).unwrap_err();
+
+        assert!(
+            matches!(
+                err.without_context(),
+                TdmsReadError::InvalidSegmentOffset { offset, .. } if *offset == next_segment_offset
+            ),
+            "Unexpected error for offset {}: {}",
+            next_segment_offset,
+            err
+        );
+    }
+}
+
+#[test]
+fn read_invalid_utf8_property_with_lossy_decoding() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/'Group'",
+            &hex!("FF FF FF FF"),
+            vec![("description", 0x20, &hex!("03 00 00 00 61 FF 62"))],
+        ),
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    let bytes = test_file.into_cursor().into_inner();
+
+    assert!(TdmsFile::new(Cursor::new(bytes.clone())).is_err());
+
+    let options = ReadOptions::new().lossy_utf8(true);
+    let tdms_file = TdmsFile::with_options(Cursor::new(bytes), &options).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+
+    assert_eq!(
+        group.properties()[0].value,
+        TdmsValue::String("a\u{FFFD}b".to_string())
+    );
+    assert_eq!(tdms_file.warnings().len(), 1);
+}
+
+#[test]
+fn read_with_buffering_options() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![5, 6, 7, 8]));
+    let bytes = test_file.into_cursor().into_inner();
+
+    for unbuffered_data_reads in [false, true] {
+        let options = ReadOptions::new()
+            .buffer_capacity(16)
+            .unbuffered_data_reads(unbuffered_data_reads);
+        let tdms_file = TdmsFile::with_options(Cursor::new(bytes.clone()), &options).unwrap();
+        let group = tdms_file.group("Group").unwrap();
+
+        let mut data = vec![0_i32; 4];
+        group
+            .channel("Channel2")
+            .unwrap()
+            .read_all_data(&mut data)
+            .unwrap();
+        assert_eq!(data, vec![3, 4, 7, 8]);
+
+        let all_data = group.read_all_channels().unwrap();
+        assert_eq!(all_data["Channel1"], ChannelData::I32(vec![1, 2, 5, 6]));
+    }
+}
+
+#[test]
+fn read_with_progress_callback() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![3, 4]));
+    let bytes = test_file.into_cursor().into_inner();
+    let file_length = bytes.len() as u64;
+
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let callback_progress = progress.clone();
+    let options = ReadOptions::new().progress_callback(move |p: &ReadProgress| {
+        callback_progress.lock().unwrap().push(p.clone())
+    });
+    TdmsFile::with_options(Cursor::new(bytes), &options).unwrap();
+
+    let progress = progress.lock().unwrap();
+    assert_eq!(progress.len(), 2);
+    assert_eq!(progress[0].segments_read, 1);
+    assert!(progress[0].position < file_length);
+    assert_eq!(progress[1].segments_read, 2);
+    assert_eq!(progress[1].position, file_length);
+    assert_eq!(progress[1].bytes_processed, file_length);
+    assert_eq!(progress[1].file_length, file_length);
+}
+
+#[test]
+fn read_ranges_within_memory_limit() {
+    let mut test_file = TestFile::new();
+    let start_time = timestamp_bytes(1000);
+    let increment = 1.0_f64.to_le_bytes();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 4),
+        vec![
+            ("wf_start_time", 0x44, &start_time),
+            ("wf_increment", 10, &increment),
+        ],
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![0, 1, 2, 3]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![4, 5, 6, 7]));
+
+    // The channel requires 32 bytes to read in full
+    let options = ReadOptions::new().memory_limit(24);
+    let tdms_file = TdmsFile::with_options(test_file.into_cursor(), &options).unwrap();
+    let channel = tdms_file
+        .group("Group")
+        .unwrap()
+        .channel("Channel1")
+        .unwrap();
+    let exceeds_limit = |err: TdmsReadError| {
+        matches!(
+            err,
+            TdmsReadError::MemoryLimitExceeded {
+                required: 28..,
+                limit: 24
+            }
+        )
+    };
+
+    assert_eq!(
+        channel.read_data_slice(1, 6).unwrap(),
+        ChannelData::I32(vec![1, 2, 3, 4, 5, 6])
+    );
+    assert!(exceeds_limit(channel.read_data_slice(0, 8).unwrap_err()));
+
+    let typed = channel.typed::<i32>().unwrap();
+    assert!(exceeds_limit(typed.read_all().unwrap_err()));
+    assert_eq!(typed.read_range(2, 6).unwrap(), vec![2, 3, 4, 5, 6, 7]);
+    assert!(exceeds_limit(typed.read_range(0, 7).unwrap_err()));
+
+    // Decimated reads also need a buffer for each chunk
+    assert_eq!(channel.read_decimated::<i32>(4).unwrap(), vec![0, 4]);
+    assert!(exceeds_limit(channel.read_decimated::<i32>(1).unwrap_err()));
+
+    let data: Vec<i32> = channel
+        .read_between(Timestamp::new(1001, 0), Timestamp::new(1007, 0))
+        .unwrap();
+    assert_eq!(data, vec![1, 2, 3, 4, 5, 6]);
+    assert!(exceeds_limit(
+        channel
+            .read_between::<i32>(Timestamp::new(1000, 0), Timestamp::new(1008, 0))
+            .unwrap_err()
+    ));
+}
+
+#[test]
+fn read_channel_without_data() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/'Group'/'Channel1'",
+            &hex!("FF FF FF FF"),
+            vec![("prop", 3, &hex!("01 00 00 00"))],
+        ),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    assert!(channel.is_empty());
+    assert_eq!(channel.data_type(), TdsType::Void);
+    assert_eq!(channel.read_data().unwrap(), ChannelData::F64(Vec::new()));
+    assert_eq!(
+        channel.read_data_slice(0, 0).unwrap(),
+        ChannelData::F64(Vec::new())
+    );
+}
+
+#[test]
+fn read_data_within_memory_limit() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    let bytes = test_file.into_cursor().into_inner();
+
+    let options = ReadOptions::new().memory_limit(12);
+    let tdms_file = TdmsFile::with_options(Cursor::new(bytes), &options).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    assert_eq!(channel.memory_size(), 8);
+    assert_eq!(channel.read_data().unwrap(), ChannelData::I32(vec![1, 2]));
+
+    let result = group.read_all_channels();
+    assert!(matches!(
+        result,
+        Err(TdmsReadError::MemoryLimitExceeded {
+            required: 16,
+            limit: 12
+        })
+    ));
+}
+
+/// Wraps a reader to count the number of bytes read
+struct CountingReader<R> {
+    inner: R,
+    bytes_read: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        self.bytes_read
+            .fetch_add(bytes_read as u64, Ordering::Relaxed);
+        Ok(bytes_read)
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn read_channel_data_reads_only_channel_chunks() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 1000), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    // The segment data is repeated, so has two chunks for each channel
+    let mut data = vec![1, 2];
+    data.extend(0..1000);
+    data.extend([3, 4]);
+    data.extend(0..1000);
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(data));
+    let bytes_read = Arc::new(AtomicU64::new(0));
+    let reader = CountingReader {
+        inner: test_file.into_cursor(),
+        bytes_read: bytes_read.clone(),
+    };
+
+    let options = ReadOptions::new().unbuffered_data_reads(true);
+    let tdms_file = TdmsFile::with_options(reader, &options).unwrap();
+    let channel = tdms_file
+        .group("Group")
+        .unwrap()
+        .channel("Channel1")
+        .unwrap();
+    bytes_read.store(0, Ordering::Relaxed);
+
+    assert_eq!(
+        channel.read_data().unwrap(),
+        ChannelData::I32(vec![1, 2, 3, 4])
+    );
+    assert_eq!(bytes_read.load(Ordering::Relaxed), 16);
+}
+
+#[test]
+fn read_interleaved_data_within_memory_limit() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_INTERLEAVED_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 3, 2, 4]));
+    let bytes = test_file.into_cursor().into_inner();
+
+    // Reading interleaved data requires a buffer for the raw bytes of all channels
+    // in addition to the channel data
+    let options = ReadOptions::new().memory_limit(24);
+    let tdms_file = TdmsFile::with_options(Cursor::new(bytes.clone()), &options).unwrap();
+    let channel = tdms_file
+        .group("Group")
+        .unwrap()
+        .channel("Channel1")
+        .unwrap();
+    assert_eq!(channel.read_data().unwrap(), ChannelData::I32(vec![1, 2]));
+
+    let options = ReadOptions::new().memory_limit(20);
+    let tdms_file = TdmsFile::with_options(Cursor::new(bytes), &options).unwrap();
+    let channel = tdms_file
+        .group("Group")
+        .unwrap()
+        .channel("Channel1")
+        .unwrap();
+    assert!(matches!(
+        channel.read_data(),
+        Err(TdmsReadError::MemoryLimitExceeded {
+            required: 24,
+            limit: 20
+        })
+    ));
+}
+
+#[test]
+fn read_decimated_data() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 1500),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32((0..1500).collect()),
+    );
+    test_file.add_segment(
+        TOC_RAW_DATA,
+        &Vec::new(),
+        &data_bytes_i32((1500..3000).collect()),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    let data: Vec<i32> = channel.read_decimated(7).unwrap();
+    assert_eq!(data, (0..3000).step_by(7).collect::<Vec<_>>());
+
+    // Large enough stride that values are read individually
+    let data: Vec<i32> = channel.read_decimated(1024).unwrap();
+    assert_eq!(data, vec![0, 1024, 2048]);
+
+    let result = channel.read_decimated::<i32>(0);
+    assert!(matches!(result, Err(TdmsReadError::InvalidStride(0))));
+}
+
+#[test]
+fn read_decimated_interleaved_data() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2000), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2000), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_INTERLEAVED_DATA;
+    let values = (0..2000).flat_map(|i| [i, -i]).collect();
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(values));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel2").unwrap();
+
+    let data: Vec<i32> = channel.read_decimated(3).unwrap();
+    assert_eq!(data, (0..2000).step_by(3).map(|i| -i).collect::<Vec<_>>());
+
+    let data: Vec<i32> = channel.read_decimated(600).unwrap();
+    assert_eq!(data, vec![0, -600, -1200, -1800]);
+}
+
+#[test]
+fn read_min_max_windows() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 5),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![5, 1, 9, 3, 2]),
+    );
+    test_file.add_segment(
+        TOC_RAW_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![8, 4, 7, 0, 6]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    let windows: Vec<(i32, i32)> = channel.read_min_max(3).unwrap();
+    assert_eq!(windows, vec![(1, 9), (2, 8), (0, 7)]);
+
+    let windows: Vec<(i32, i32)> = channel.read_min_max(20).unwrap();
+    assert_eq!(windows.len(), 10);
+    assert_eq!(windows[2], (9, 9));
+}
+
+#[test]
+fn read_single_values() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 1), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    // Segment data is repeated twice
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![1, 2, 10, 3, 4, 11]),
+    );
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 3),
+        Vec::new(),
+    )]);
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![5, 6, 7]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    let values: Vec<i32> = (0..7)
+        .map(|i| channel.value_at(i).unwrap().unwrap())
+        .collect();
+    assert_eq!(values, vec![1, 2, 3, 4, 5, 6, 7]);
+    assert_eq!(channel.value_at::<i32>(7).unwrap(), None);
+    assert!(channel.value_at::<f64>(0).is_err());
+
+    let channel = group.channel("Channel2").unwrap();
+    assert_eq!(channel.value_at::<i32>(1).unwrap(), Some(11));
+}
+
+#[test]
+fn iterate_over_values() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(
+        TOC_RAW_DATA | TOC_INTERLEAVED_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![5, 7, 6, 8]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel1 = group.channel("Channel1").unwrap();
+    let channel2 = group.channel("Channel2").unwrap();
+
+    let values: Vec<i32> = channel1
+        .iter_values()
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(values, vec![1, 2, 5, 6]);
+
+    let pairs: Vec<(i32, i32)> = channel1
+        .iter_values()
+        .unwrap()
+        .zip(channel2.iter_values().unwrap())
+        .step_by(2)
+        .map(|(a, b)| (a.unwrap(), b.unwrap()))
+        .collect();
+    assert_eq!(pairs, vec![(1, 3), (5, 7)]);
+
+    assert!(channel1.iter_values::<f64>().is_err());
+}
+
+#[test]
+fn read_channels_concatenated_across_files() {
+    let mut first_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    first_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+
+    let mut second_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel0'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    second_file.add_segment(
+        toc_mask | TOC_INTERLEAVED_DATA,
+        &metadata_bytes,
+        &data_bytes_i32(vec![0, 3, 0, 4]),
+    );
+
+    let first_file = TdmsFile::new(first_file.into_cursor()).unwrap();
+    let second_file = TdmsFile::new(second_file.into_cursor()).unwrap();
+    let first_group = first_file.group("Group").unwrap();
+    let second_group = second_file.group("Group").unwrap();
+    let first_channel = first_group.channel("Channel1").unwrap();
+    let second_channel = second_group.channel("Channel1").unwrap();
+
+    let mut data = vec![0_i32; 4];
+    read_concatenated(&first_channel, &second_channel, &mut data).unwrap();
+    assert_eq!(data, vec![1, 2, 3, 4]);
+
+    let other_channel = second_group.channel("Channel0").unwrap();
+    let result = read_concatenated(&first_channel, &other_channel, &mut data);
+    assert!(matches!(
+        result,
+        Err(TdmsReadError::IncompatibleChannels { .. })
+    ));
+}
+
+#[test]
+fn read_data_range() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![0, 1, 2, 3]));
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![4, 5, 6, 7]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    let mut data = vec![0_i32; 5];
+    channel.read_data_range(1, &mut data).unwrap();
+    assert_eq!(data, vec![1, 2, 3, 4, 5]);
+
+    let result = channel.read_data_range(4, &mut data);
+    assert!(matches!(
+        result,
+        Err(TdmsReadError::RangeOutOfBounds {
+            start: 4,
+            end: 9,
+            length: 8
+        })
+    ));
+
+    assert_eq!(
+        channel.read_data_slice(3, 2).unwrap(),
+        ChannelData::I32(vec![3, 4])
+    );
+
+    // Invalid ranges are rejected before allocating any data
+    let result = channel.read_data_slice(6, usize::MAX);
+    assert!(matches!(
+        result,
+        Err(TdmsReadError::RangeOutOfBounds {
+            start: 6,
+            end: u64::MAX,
+            length: 8
+        })
+    ));
+    let result = channel.read_data_slice(7, 1 << 40);
+    assert!(matches!(
+        result,
+        Err(TdmsReadError::RangeOutOfBounds { start: 7, .. })
+    ));
+}
+
+#[test]
+fn read_data_between_times() {
+    let mut test_file = TestFile::new();
+    let start_time = timestamp_bytes(1000);
+    let increment = 1.0_f64.to_le_bytes();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 4),
+        vec![
+            ("wf_start_time", 0x44, &start_time),
+            ("wf_increment", 10, &increment),
+        ],
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![0, 1, 2, 3]));
+    // Acquisition is restarted with a new start time
+    let restart_time = timestamp_bytes(1100);
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 4),
+        vec![("wf_start_time", 0x44, &restart_time)],
+    )]);
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![10, 11, 12, 13]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    let data: Vec<i32> = channel
+        .read_between(Timestamp::new(1001, 0), Timestamp::new(1003, 0))
+        .unwrap();
+    assert_eq!(data, vec![1, 2]);
+
+    let data: Vec<i32> = channel
+        .read_between(Timestamp::new(1002, 0), Timestamp::new(1102, 0))
+        .unwrap();
+    assert_eq!(data, vec![2, 3, 10, 11]);
+
+    let data: Vec<i32> = channel
+        .read_between(Timestamp::new(2000, 0), Timestamp::new(3000, 0))
+        .unwrap();
+    assert!(data.is_empty());
+}
+
+#[test]
+fn find_index_of_time() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Time'",
+        &raw_data_index(0x44, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    let times = [10, 12, 20, 21, 30];
+    let data_bytes = times
+        .iter()
+        .flat_map(|&seconds| timestamp_bytes(seconds))
+        .collect::<Vec<u8>>();
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes[..32].to_vec());
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes[32..64].to_vec());
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Time'",
+        &raw_data_index(0x44, 1),
+        Vec::new(),
+    )]);
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes[64..].to_vec());
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Time").unwrap();
+
+    let find = |seconds| {
+        channel
+            .find_time_index(Timestamp::new(seconds, 0))
+            .unwrap()
+            .unwrap()
+    };
+    assert_eq!(find(0), 0);
+    assert_eq!(find(12), 1);
+    assert_eq!(find(17), 2);
+    assert_eq!(find(15), 1);
+    assert_eq!(find(26), 4);
+    assert_eq!(find(100), 4);
+}
+
+#[test]
+fn align_waveform_channels() {
+    let mut test_file = TestFile::new();
+    let start_time_1 = timestamp_bytes(100);
+    let start_time_2 = timestamp_bytes(102);
+    let increment_1 = 1.0_f64.to_le_bytes();
+    let increment_2 = 0.5_f64.to_le_bytes();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/'Group'/'Channel1'",
+            &raw_data_index(3, 10),
+            vec![
+                ("wf_start_time", 0x44, &start_time_1),
+                ("wf_increment", 10, &increment_1),
+            ],
+        ),
+        object_metadata(
+            "/'Group'/'Channel2'",
+            &raw_data_index(3, 10),
+            vec![
+                ("wf_start_time", 0x44, &start_time_2),
+                ("wf_increment", 10, &increment_2),
+            ],
+        ),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![0; 20]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let timings = group
+        .channels()
+        .map(|channel| channel.waveform_timing())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let alignment = align_waveforms(&timings).unwrap();
+    assert_eq!(alignment.start_time, Timestamp::new(102, 0));
+    assert!((alignment.duration - 5.0).abs() < 1e-9);
+    assert_eq!(alignment.ranges, vec![2..7, 0..10]);
+}
+
+#[test]
+fn read_digital_port_lines() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Port0'",
+        &raw_data_index(5, 4),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &vec![0b01, 0b10, 0b11, 0x80]);
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Port0").unwrap();
+
+    assert_eq!(channel.digital_line_count().unwrap(), 8);
+    let lines = channel.read_digital_lines().unwrap();
+    assert_eq!(lines.len(), 8);
+    assert_eq!(lines[0], vec![true, false, true, false]);
+    assert_eq!(lines[1], vec![false, true, true, false]);
+    assert_eq!(lines[7], vec![false, false, false, true]);
+    assert_eq!(
+        channel.read_digital_line(1).unwrap(),
+        vec![false, true, true, false]
+    );
+    assert!(channel.read_digital_line(8).is_err());
+}
+
+#[test]
+fn read_typed_channel() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![3, 4]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    assert!(matches!(
+        channel.typed::<f64>(),
+        Err(TdmsReadError::BufferTypeMismatch { .. })
+    ));
+
+    let typed_channel = channel.typed::<i32>().unwrap();
+    assert_eq!(typed_channel.len(), 4);
+    assert_eq!(typed_channel.read_all().unwrap(), vec![1, 2, 3, 4]);
+    assert_eq!(typed_channel.read_range(1, 2).unwrap(), vec![2, 3]);
+    let values = typed_channel.iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(values, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn group_channel_names_and_count() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'", &hex!("FF FF FF FF"), Vec::new()),
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 1), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 1), Vec::new()),
+        object_metadata("/'Other'/'Channel3'", &raw_data_index(3, 1), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+
+    assert_eq!(group.channel_count(), 2);
+    assert_eq!(group.channel_names(), vec!["Channel1", "Channel2"]);
+    assert!(group.contains_channel("Channel2"));
+    assert!(!group.contains_channel("Channel3"));
+}
+
+#[test]
+fn debug_and_display_describe_objects() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'", &hex!("FF FF FF FF"), Vec::new()),
+        object_metadata(
+            "/'Group'/'Channel1'",
+            &raw_data_index(3, 2),
+            vec![("prop", 3, &hex!("01 00 00 00"))],
+        ),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    assert_eq!(
+        format!("{}", tdms_file),
+        "TDMS file with 1 groups and 0 properties"
+    );
+    assert_eq!(
+        format!("{:?}", tdms_file),
+        "TdmsFile { group_count: 1, property_count: 0 }"
+    );
+    assert_eq!(
+        format!("{}", group),
+        "Group 'Group' with 1 channels and 0 properties"
+    );
+    assert_eq!(
+        format!("{:?}", group),
+        "Group { name: \"Group\", channel_count: 1, property_count: 0 }"
+    );
+    assert_eq!(
+        format!("{}", channel),
+        "Channel /'Group'/'Channel1' with 2 values of type I32 and 1 properties"
+    );
+    assert_eq!(
+        format!("{:?}", channel),
+        "Channel { path: \"/'Group'/'Channel1'\", data_type: I32, len: 2, property_count: 1 }"
+    );
+}
+
+#[test]
+fn file_metadata_summary() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/",
+            &hex!("FF FF FF FF"),
+            vec![("name", 0x20, &hex!("04 00 00 00 74 65 73 74"))],
+        ),
+        object_metadata("/'Group'", &hex!("FF FF FF FF"), Vec::new()),
+        object_metadata(
+            "/'Group'/'Channel1'",
+            &raw_data_index(3, 2),
+            vec![("prop", 3, &hex!("01 00 00 00"))],
+        ),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let file_metadata = tdms_file.metadata();
+
+    assert_eq!(
+        file_metadata.properties,
+        vec![TdmsProperty {
+            name: "name".to_owned(),
+            value: TdmsValue::String("test".to_owned()),
+        }]
+    );
+    assert_eq!(file_metadata.groups.len(), 1);
+    let group = &file_metadata.groups[0];
+    assert_eq!(group.name, "Group");
+    assert_eq!(group.channels.len(), 1);
+    let channel = &group.channels[0];
+    assert_eq!(channel.name, "Channel1");
+    assert_eq!(channel.data_type, TdsType::I32);
+    assert_eq!(channel.len, 2);
+    assert_eq!(channel.properties[0].value, TdmsValue::Int32(1));
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&file_metadata).unwrap();
+        let deserialized: rstdms::FileMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, file_metadata);
+    }
+}
+
+#[test]
+fn export_channels_to_csv() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/'Group'/'Channel1'",
+            &raw_data_index(3, 3),
+            vec![
+                ("wf_increment", 10, &0.5_f64.to_le_bytes()),
+                ("unit_string", 0x20, &hex!("01 00 00 00 56")),
+            ],
+        ),
+        object_metadata("/'Group'/'Channel, 2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![1, 2, 3, 11, 12]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channels: Vec<_> = group.channels().collect();
+
+    let mut output = Vec::new();
+    write_csv(&channels, &mut output, &CsvOptions::new()).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Channel1,\"Channel, 2\"\n1,11\n2,12\n3,\n"
+    );
+
+    let mut output = Vec::new();
+    let options = CsvOptions::new()
+        .delimiter(';')
+        .time_column(true)
+        .units_header(true);
+    write_csv(&channels, &mut output, &options).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Time;Channel1;Channel, 2\ns;V;\n0;1;11\n0.5;2;12\n1;3;\n"
+    );
+}
+
+#[test]
+fn export_channels_to_csv_with_empty_channel() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/'Group'/'Empty'",
+            &hex!("FF FF FF FF"),
+            vec![("prop", 3, &hex!("01 00 00 00"))],
+        ),
+        object_metadata("/'Group'/'Values'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channels: Vec<_> = group.channels().collect();
+
+    let mut output = Vec::new();
+    write_csv(&channels, &mut output, &CsvOptions::new()).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Empty,Values
+,1
+,2
+"
+    );
+}
+
+#[test]
+fn export_string_channel_to_csv_decodes_chunks_once() {
+    // More values than are exported in one block, in a single chunk
+    let num_values = 150_000_u32;
+    let mut data_bytes = Vec::new();
+    for offset in 1..=num_values {
+        data_bytes.extend(offset.to_le_bytes());
+    }
+    data_bytes.extend(vec![b'a'; num_values as usize]);
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Strings'",
+        &raw_data_index_with_byte_len(0x20, num_values as u64, Some(data_bytes.len() as u64)),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channels: Vec<_> = group.channels().collect();
+
+    let mut output = Vec::new();
+    write_csv(&channels, &mut output, &CsvOptions::new()).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.lines().count(), num_values as usize + 1);
+    assert!(output.lines().skip(1).all(|line| line == "a"));
+    assert_eq!(tdms_file.metrics().chunks_decoded, 1);
+}
+
+#[test]
+fn io_metrics() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![3, 4]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let metrics = tdms_file.metrics();
+    assert_eq!(metrics.segments_parsed, 2);
+    assert_eq!(metrics.chunks_decoded, 0);
+    assert!(metrics.bytes_read > 0);
+
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+    let mut data = vec![0i32; 4];
+    channel.read_all_data(&mut data).unwrap();
+
+    let read_metrics = tdms_file.metrics();
+    assert_eq!(read_metrics.segments_parsed, 2);
+    assert_eq!(read_metrics.chunks_decoded, 2);
+    assert_eq!(read_metrics.seeks, metrics.seeks + 2);
+    assert!(read_metrics.bytes_read >= metrics.bytes_read + 16);
+}
+
+#[test]
+fn redefined_properties_use_latest_value() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        vec![
+            ("prop1", 3, &hex!("01 00 00 00")),
+            ("prop2", 3, &hex!("02 00 00 00")),
+        ],
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &hex!("00 00 00 00"),
+        vec![("prop1", 3, &hex!("0A 00 00 00"))],
+    )]);
+    test_file.add_segment(
+        TOC_METADATA | TOC_RAW_DATA,
+        &metadata_bytes,
+        &data_bytes_i32(vec![3, 4]),
+    );
+
+    let bytes = test_file.into_cursor().into_inner();
+    let options = ReadOptions::new().property_history(true);
+    let tdms_file = TdmsFile::with_options(Cursor::new(bytes.clone()), &options).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    let properties = channel.properties();
+    assert_eq!(properties.len(), 2);
+    assert_eq!(properties[0].name, "prop1");
+    assert_eq!(properties[0].value, TdmsValue::Int32(10));
+    assert_eq!(properties[1].name, "prop2");
+
+    let history = channel.property_history("prop1");
+    assert_eq!(
+        history,
+        vec![
+            PropertyRevision {
+                segment_index: 0,
+                value: TdmsValue::Int32(1),
+            },
+            PropertyRevision {
+                segment_index: 1,
+                value: TdmsValue::Int32(10),
+            },
+        ]
+    );
+    assert_eq!(channel.property_history("prop2").len(), 1);
+
+    assert_eq!(
+        channel.property("prop1").and_then(TdmsValue::as_i64),
+        Some(10)
+    );
+    assert_eq!(channel.unit_string(), None);
+
+    let properties_map = channel.properties_map();
+    assert_eq!(
+        properties_map.keys().copied().collect::<Vec<_>>(),
+        vec!["prop1", "prop2"]
+    );
+    assert_eq!(properties_map["prop1"], &TdmsValue::Int32(10));
+    assert!(tdms_file.properties_map().is_empty());
+    assert!(channel.property_history("missing").is_empty());
+    assert!(tdms_file.property_history("prop1").is_empty());
+
+    // History is only kept when enabled
+    let tdms_file = TdmsFile::new(Cursor::new(bytes)).unwrap();
+    let channel = tdms_file
+        .group("Group")
+        .unwrap()
+        .channel("Channel1")
+        .unwrap();
+    assert_eq!(
+        channel.property("prop1").and_then(TdmsValue::as_i64),
+        Some(10)
+    );
+    assert!(channel.property_history("prop1").is_empty());
+}
+
+#[test]
+fn read_standard_root_properties() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/",
+        &hex!("FF FF FF FF"),
+        vec![
+            ("name", 0x20, &hex!("04 00 00 00 74 65 73 74")),
+            ("author", 0x20, &hex!("03 00 00 00 41 6E 6E")),
+            (
+                "datetime",
+                0x44,
+                &hex!("00 00 00 00 00 00 00 80 7B E2 15 D2 00 00 00 00"),
+            ),
+        ],
+    )]);
+    test_file.add_segment(
+        TOC_METADATA | TOC_NEW_OBJ_LIST,
+        &metadata_bytes,
+        &Vec::new(),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    assert_eq!(tdms_file.name(), Some("test"));
+    assert_eq!(tdms_file.author(), Some("Ann"));
+    assert_eq!(tdms_file.title(), None);
+    assert_eq!(tdms_file.description(), None);
+    assert_eq!(
+        tdms_file.datetime(),
+        Some(Timestamp::new(3524649595, 1 << 63))
+    );
+}
+
+#[test]
+fn find_objects_by_property() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/'Group'",
+            &hex!("FF FF FF FF"),
+            vec![("unit_string", 0x20, &hex!("01 00 00 00 56"))],
+        ),
+        object_metadata(
+            "/'Group'/'Channel1'",
+            &raw_data_index(3, 2),
+            vec![("unit_string", 0x20, &hex!("01 00 00 00 56"))],
+        ),
+        object_metadata(
+            "/'Group'/'Channel2'",
+            &raw_data_index(3, 2),
+            vec![("unit_string", 0x20, &hex!("01 00 00 00 41"))],
+        ),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let paths = |objects: Vec<TdmsObject<_>>| {
+        objects
+            .iter()
+            .map(|object| object.path())
+            .collect::<Vec<_>>()
+    };
+
+    let objects = tdms_file.find_by_property("unit_string", &TdmsValue::String("V".to_owned()));
+    assert_eq!(paths(objects), vec!["/'Group'", "/'Group'/'Channel1'"]);
+
+    let objects = tdms_file.find_objects(|object| matches!(object, TdmsObject::Channel(_)));
+    assert_eq!(
+        paths(objects),
+        vec!["/'Group'/'Channel1'", "/'Group'/'Channel2'"]
+    );
+
+    assert!(tdms_file
+        .find_by_property("unit_string", &TdmsValue::Int32(1))
+        .is_empty());
+}
+
+#[test]
+fn filter_channels_in_group() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata(
+            "/'Group'/'Channel2'",
+            &raw_data_index_with_byte_len(0x20, 2, Some(11)),
+            Vec::new(),
+        ),
+        object_metadata("/'Group'/'Channel3'", &raw_data_index(10, 1), Vec::new()),
+    ]);
+    let mut data_bytes = data_bytes_i32(vec![1, 2]);
+    data_bytes.extend(1_u32.to_le_bytes());
+    data_bytes.extend(3_u32.to_le_bytes());
+    data_bytes.extend(b"abc");
+    data_bytes.extend(1.5_f64.to_le_bytes());
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let names = |channels: Vec<Channel<_>>| {
+        channels
+            .iter()
+            .map(|channel| channel.name().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let channel = group.channel("Channel2").unwrap();
+    assert_eq!(channel.raw_data_size(), 11);
+    let channel = group.channel("Channel3").unwrap();
+    assert_eq!(channel.raw_data_size(), 8);
+
+    assert_eq!(
+        names(group.numeric_channels()),
+        vec!["Channel1", "Channel3"]
+    );
+    assert_eq!(
+        names(group.channels_of_type(TdsType::String)),
+        vec!["Channel2"]
+    );
+    assert_eq!(
+        names(group.channels_where(|channel| channel.len() == 1)),
+        vec!["Channel3"]
+    );
+}
+
+#[test]
+fn scan_channel_data_quality() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(10, 10),
+        Vec::new(),
+    )]);
+    let values = [
+        1.0,
+        f64::NAN,
+        2.0,
+        2.0,
+        2.0,
+        -9999.0,
+        f64::INFINITY,
+        3.0,
+        3.0,
+        f64::NAN,
+    ];
+    let data_bytes = values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect::<Vec<_>>();
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    let options = QualityOptions::new().sentinel(-9999.0).min_constant_run(2);
+    let report = channel.scan_quality(&options).unwrap();
+    assert_eq!(report.len, 10);
+    assert_eq!(report.nan_count, 2);
+    assert_eq!(report.infinite_count, 1);
+    assert_eq!(report.sentinel_count, 1);
+    assert_eq!(report.longest_constant_run, 3);
+    assert_eq!(report.constant_runs, vec![2..5, 7..9]);
+    assert!(report.has_issues());
+
+    let report = channel.scan_quality(&QualityOptions::new()).unwrap();
+    assert_eq!(report.sentinel_count, 0);
+    assert!(report.constant_runs.is_empty());
+}
+
+#[test]
+fn error_includes_segment_and_object_context() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel2'",
+        &raw_data_index(0x7FFF, 2),
+        Vec::new(),
+    )]);
+    test_file.add_segment(
+        TOC_METADATA | TOC_RAW_DATA,
+        &metadata_bytes,
+        &data_bytes_i32(vec![3, 4]),
+    );
+
+    let error = match TdmsFile::new(test_file.into_cursor()) {
+        Ok(_) => panic!("Expected an error"),
+        Err(error) => error,
+    };
+    let context = error.context().unwrap();
+
+    assert_eq!(context.segment_index, Some(1));
+    assert_eq!(context.segment_position, Some(87));
+    assert_eq!(context.object_path.as_deref(), Some("/'Group'/'Channel2'"));
+}
+
+#[test]
+fn error_for_invalid_segment_header() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    let mut bytes = test_file.into_cursor().into_inner();
+    bytes[0] = b'X';
+
+    let error = match TdmsFile::from_bytes(bytes) {
+        Ok(_) => panic!("Expected an error"),
+        Err(error) => error,
+    };
+
+    assert!(matches!(
+        error,
+        TdmsReadError::InvalidSegmentHeader {
+            position: 0,
+            header: [b'X', b'D', b'S', b'm']
+        }
+    ));
+}
+
+#[test]
+fn error_for_buffer_type_mismatch() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+    let mut data: Vec<f64> = vec![0.0; channel.len() as usize];
+    let error = channel.read_all_data(&mut data[..]).unwrap_err();
+
+    match error {
+        TdmsReadError::BufferTypeMismatch {
+            channel_path,
+            data_type,
+            requested,
+            expected,
+        } => {
+            assert_eq!(channel_path, "/'Group'/'Channel1'");
+            assert_eq!(data_type, TdsType::I32);
+            assert_eq!(requested, NativeTypeId::F64);
+            assert_eq!(expected, NativeTypeId::I32);
+        }
+        _ => panic!("Unexpected error variant: {:?}", error),
+    }
+}
+
+#[test]
+fn file_stats() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(
+        TOC_RAW_DATA | TOC_INTERLEAVED_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![5, 6, 7, 8, 9, 10, 11, 12]),
+    );
+    let metadata_length = metadata_bytes.len() as u64;
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let stats = tdms_file.stats();
+
+    assert_eq!(stats.segment_count, 2);
+    assert_eq!(stats.contiguous_segment_count, 1);
+    assert_eq!(stats.interleaved_segment_count, 1);
+    assert_eq!(stats.metadata_bytes, 2 * 28 + metadata_length);
+    assert_eq!(stats.data_bytes, 12 * 4);
+    assert_eq!(stats.channels.len(), 2);
+    for channel_stats in stats.channels.iter() {
+        assert_eq!(channel_stats.chunk_count, 3);
+        assert_eq!(channel_stats.number_of_values, 6);
+    }
+    assert!((stats.fragmentation - 2.0 / 3.0).abs() < 1e-10);
+}
+
+#[test]
+fn file_segments() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(
+        TOC_RAW_DATA | TOC_INTERLEAVED_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![5, 6, 7, 8, 9, 10, 11, 12]),
+    );
+    let first_data_position = 28 + metadata_bytes.len() as u64;
+    let second_position = first_data_position + 16;
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let segments = tdms_file.segments();
+
+    assert_eq!(segments.len(), 2);
+    assert_eq!(segments[0].position, 0);
+    assert_eq!(segments[0].data_position, first_data_position);
+    assert_eq!(segments[0].next_segment_position, second_position);
+    assert_eq!(segments[0].version, 4713);
+    assert_eq!(segments[0].toc_flags, toc_mask);
+    assert_eq!(segments[0].object_count, 2);
+    assert_eq!(segments[0].repetitions, 1);
+    assert_eq!(segments[1].position, second_position);
+    assert_eq!(segments[1].data_position, second_position + 28);
+    assert_eq!(segments[1].next_segment_position, second_position + 28 + 32);
+    assert_eq!(segments[1].toc_flags, TOC_RAW_DATA | TOC_INTERLEAVED_DATA);
+    assert_eq!(segments[1].object_count, 2);
+    assert_eq!(segments[1].repetitions, 2);
+}
+
+#[test]
+fn channel_segment_chunks() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(
+        TOC_RAW_DATA | TOC_INTERLEAVED_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![5, 6, 7, 8, 9, 10, 11, 12]),
+    );
+    let first_data_position = 28 + metadata_bytes.len() as u64;
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let chunks = group.channel("Channel2").unwrap().chunks();
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].segment_index, 0);
+    assert_eq!(chunks[0].segment_position, 0);
+    assert_eq!(chunks[0].data_position, first_data_position + 8);
+    assert_eq!(chunks[0].number_of_values, 2);
+    assert_eq!(chunks[0].repetitions, 1);
+    assert!(!chunks[0].interleaved);
+    assert!(!chunks[0].big_endian);
+    assert_eq!(chunks[1].segment_index, 1);
+    assert_eq!(chunks[1].segment_position, first_data_position + 16);
+    assert_eq!(chunks[1].repetitions, 2);
+    assert!(chunks[1].interleaved);
+}
+
+#[test]
+fn channel_byte_ranges() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![1, 2, 3, 4, 5, 6, 7, 8]),
+    );
+    let metadata_bytes_2 = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes_2,
+        &data_bytes_i32(vec![9, 10, 11, 12]),
+    );
+    let data_start = 28 + metadata_bytes.len() as u64;
+    let second_data_start = data_start + 32 + 28 + metadata_bytes_2.len() as u64;
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+
+    assert_eq!(
+        group.channel("Channel1").unwrap().byte_ranges().unwrap(),
+        vec![
+            data_start..data_start + 8,
+            data_start + 16..data_start + 24,
+            second_data_start..second_data_start + 16,
+        ]
+    );
+}
+
+#[test]
+fn write_channel_raw_data() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(
+        TOC_RAW_DATA | TOC_INTERLEAVED_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![5, 6, 7, 8, 9, 10, 11, 12]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel2").unwrap();
+    let mut raw_data = Vec::new();
+    let bytes_written = channel.write_raw_data(&mut raw_data).unwrap();
+
+    assert_eq!(bytes_written, 24);
+    assert_eq!(raw_data, data_bytes_i32(vec![3, 4, 6, 8, 10, 12]));
+    assert_eq!(bytes_written, channel.raw_data_size());
+}
+
+#[test]
+fn file_format_info() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(
+        TOC_RAW_DATA | TOC_INTERLEAVED_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![5, 6, 7, 8]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let format_info = tdms_file.format_info();
+
+    assert_eq!(format_info.versions, vec![TDMS_VERSION_2_0]);
+    assert!(format_info.interleaved);
+    assert!(!format_info.big_endian);
+    assert!(!format_info.daqmx);
+}
+
+#[test]
+fn read_tdms_1_0_file_with_new_object_list() {
+    let mut test_file = TestFile::with_version(TDMS_VERSION_1_0);
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    // A new object list with only the second channel, so the first channel has no data
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel2'",
+        &raw_data_index(3, 3),
+        Vec::new(),
+    )]);
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![5, 6, 7]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let data = group.read_all_channels().unwrap();
+
+    assert_eq!(data["Channel1"], ChannelData::I32(vec![1, 2]));
+    assert_eq!(data["Channel2"], ChannelData::I32(vec![3, 4, 5, 6, 7]));
+    assert_eq!(tdms_file.format_info().versions, vec![TDMS_VERSION_1_0]);
+}
+
+#[test]
+fn read_tdms_1_0_file_merging_object_lists() {
+    let mut test_file = TestFile::with_version(TDMS_VERSION_1_0);
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    // Only the changed channel is written, even though the new object list flag is set
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel2'",
+        &raw_data_index(3, 1),
+        Vec::new(),
+    )]);
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![5, 6, 7]));
+
+    let options = ReadOptions::new().merge_tdms_1_0_object_lists(true);
+    let tdms_file = TdmsFile::with_options(test_file.into_cursor(), &options).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let data = group.read_all_channels().unwrap();
+
+    assert_eq!(data["Channel1"], ChannelData::I32(vec![1, 2, 5, 6]));
+    assert_eq!(data["Channel2"], ChannelData::I32(vec![3, 4, 7]));
+    assert_eq!(tdms_file.format_info().versions, vec![TDMS_VERSION_1_0]);
+    assert!(tdms_file.warnings().is_empty());
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "arrow")]
+
+extern crate hex_literal;
+
+mod common;
+
+use arrow2::array::{Int32Array, Utf8Array};
+use arrow2::datatypes::DataType;
+use common::*;
+
+use rstdms::TdmsFile;
+
+#[test]
+fn read_group_as_arrow_chunks() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 3), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![1, 2, 3, 11, 12]),
+    );
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    test_file.add_segment(
+        TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA,
+        &metadata_bytes,
+        &data_bytes_i32(vec![4, 5]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let reader = group.arrow_chunks(2).unwrap();
+
+    let fields = reader.fields();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0].name, "Channel1");
+    assert_eq!(fields[1].name, "Channel2");
+    assert_eq!(fields[0].data_type, DataType::Int32);
+
+    let chunks = reader.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(
+        chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+
+    let column = |chunk: usize, channel: usize| {
+        chunks[chunk].arrays()[channel]
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap()
+            .iter()
+            .map(|value| value.copied())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(column(0, 0), vec![Some(1), Some(2)]);
+    assert_eq!(column(1, 0), vec![Some(3), Some(4)]);
+    assert_eq!(column(2, 0), vec![Some(5)]);
+    assert_eq!(column(0, 1), vec![Some(11), Some(12)]);
+    assert_eq!(column(1, 1), vec![None, None]);
+    assert_eq!(column(2, 1), vec![None]);
+}
+
+#[test]
+fn read_string_channel_as_arrow_chunks() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index_with_byte_len(0x20, 2, Some(19)),
+        Vec::new(),
+    )]);
+    let mut data_bytes = Vec::new();
+    // String data starts with the offset of the end of each string
+    data_bytes.extend(5_u32.to_le_bytes());
+    data_bytes.extend(11_u32.to_le_bytes());
+    data_bytes.extend(b"hello");
+    data_bytes.extend(b"world!");
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let chunks = group
+        .arrow_chunks(1)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(chunks.len(), 2);
+    let strings = chunks
+        .iter()
+        .flat_map(|chunk| {
+            let array = chunk.arrays()[0]
+                .as_any()
+                .downcast_ref::<Utf8Array<i32>>()
+                .unwrap();
+            array
+                .iter()
+                .map(|value| value.map(|s| s.to_owned()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        strings,
+        vec![Some("hello".to_owned()), Some("world!".to_owned())]
+    );
+}
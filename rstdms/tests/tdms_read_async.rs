@@ -0,0 +1,87 @@
+#![cfg(feature = "tokio")]
+
+extern crate hex_literal;
+
+mod common;
+
+use common::*;
+use hex_literal::hex;
+
+use rstdms::{TdmsFileAsync, TdmsValue};
+
+#[tokio::test]
+async fn read_metadata_async() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/",
+            &hex!("FF FF FF FF"),
+            vec![("test_property", 3, &10_i32.to_le_bytes())],
+        ),
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 3), Vec::new()),
+    ]);
+    let data_bytes = data_bytes_i32(vec![1, 2, 3]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
+
+    let tdms_file = TdmsFileAsync::new(test_file.into_cursor()).await;
+
+    assert!(tdms_file.is_ok(), "Got error: {:?}", tdms_file.unwrap_err());
+
+    let tdms_file = tdms_file.unwrap();
+    assert_eq!(tdms_file.properties().len(), 1);
+    assert_eq!(tdms_file.properties()[0].value, TdmsValue::Int32(10));
+
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+    let mut data: Vec<i32> = vec![0; channel.len() as usize];
+    channel.read_all_data(&mut data[..]).await.unwrap();
+
+    assert_eq!(data, vec![1, 2, 3]);
+}
+
+#[tokio::test]
+async fn read_multiple_segments_async() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![5, 6, 7, 8]));
+
+    let tdms_file = TdmsFileAsync::new(test_file.into_cursor()).await.unwrap();
+
+    let group = tdms_file.group("Group").unwrap();
+    let channel_names: Vec<&str> = group.channels().map(|c| c.name()).collect();
+    assert_eq!(channel_names, vec!["Channel1", "Channel2"]);
+
+    let expected_data = vec![vec![1, 2, 5, 6], vec![3, 4, 7, 8]];
+    for (channel, expected) in group.channels().zip(expected_data) {
+        let mut data: Vec<i32> = vec![0; channel.len() as usize];
+        channel.read_all_data(&mut data[..]).await.unwrap();
+        assert_eq!(data, expected);
+    }
+}
+
+#[tokio::test]
+async fn interleaved_data_async() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 3), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 3), Vec::new()),
+    ]);
+    let data_bytes = data_bytes_i32(vec![1, 2, 3, 4, 5, 6]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_INTERLEAVED_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes);
+
+    let tdms_file = TdmsFileAsync::new(test_file.into_cursor()).await.unwrap();
+
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel2").unwrap();
+    let mut data: Vec<i32> = vec![0; channel.len() as usize];
+    channel.read_all_data(&mut data[..]).await.unwrap();
+
+    assert_eq!(data, vec![2, 4, 6]);
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "ndarray")]
+
+extern crate hex_literal;
+
+mod common;
+
+use common::*;
+use ndarray::array;
+
+use rstdms::{TdmsFile, TdmsReadError};
+
+#[test]
+fn read_channel_into_array() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![3, 4]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    assert_eq!(channel.read_array::<i32>().unwrap(), array![1, 2, 3, 4]);
+}
+
+#[test]
+fn read_group_into_array2() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![1, 2, 11, 12]),
+    );
+    test_file.add_segment(
+        TOC_RAW_DATA,
+        &Vec::new(),
+        &data_bytes_i32(vec![3, 4, 13, 14]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+
+    assert_eq!(
+        group.read_array2::<i32>().unwrap(),
+        array![[1, 11], [2, 12], [3, 13], [4, 14]]
+    );
+}
+
+#[test]
+fn read_group_with_different_lengths_into_array2() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 1), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 11]));
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+
+    match group.read_array2::<i32>() {
+        Err(TdmsReadError::ChannelLengthMismatch {
+            length, expected, ..
+        }) => {
+            assert_eq!(length, 1);
+            assert_eq!(expected, 2);
+        }
+        other => panic!("Unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,103 @@
+#![cfg(feature = "npz")]
+
+use rstdms::{write_npz, ChannelData, TdmsFile, TdmsWriter, WriteObject};
+use std::io::{Cursor, Read};
+
+/// Read an array from an archive, returning the `.npy` header and data
+fn read_array(archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>, name: &str) -> (String, Vec<u8>) {
+    let mut bytes = Vec::new();
+    archive
+        .by_name(name)
+        .unwrap()
+        .read_to_end(&mut bytes)
+        .unwrap();
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let header_length = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    let data_start = 10 + header_length;
+    assert_eq!(data_start % 64, 0);
+    let header = String::from_utf8(bytes[10..data_start].to_vec()).unwrap();
+    (header.trim_end().to_owned(), bytes[data_start..].to_vec())
+}
+
+#[test]
+fn write_channels_as_npz() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[
+            WriteObject::channel(
+                "Group",
+                "Ints",
+                Vec::new(),
+                Some(ChannelData::I32(vec![1, -2])),
+            ),
+            WriteObject::channel(
+                "Group",
+                "Strings",
+                Vec::new(),
+                Some(ChannelData::String(vec!["a".to_owned(), "bé".to_owned()])),
+            ),
+        ])
+        .unwrap();
+    let tdms_file = TdmsFile::from_bytes(writer.into_inner().unwrap()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channels = group.channels().collect::<Vec<_>>();
+
+    let bytes = write_npz(&channels, Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+    assert_eq!(archive.len(), 2);
+
+    let (header, data) = read_array(&mut archive, "Group/Ints.npy");
+    assert_eq!(
+        header,
+        "{'descr': '<i4', 'fortran_order': False, 'shape': (2,), }"
+    );
+    assert_eq!(data, [1i32.to_le_bytes(), (-2i32).to_le_bytes()].concat());
+
+    let (header, data) = read_array(&mut archive, "Group/Strings.npy");
+    assert_eq!(
+        header,
+        "{'descr': '<U2', 'fortran_order': False, 'shape': (2,), }"
+    );
+    let code_points = data
+        .chunks(4)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect::<Vec<_>>();
+    assert_eq!(code_points, vec!['a' as u32, 0, 'b' as u32, 'é' as u32]);
+}
+
+#[test]
+fn write_empty_channel_as_npz() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[
+            WriteObject::channel("Group", "Empty", Vec::new(), None),
+            WriteObject::channel(
+                "Group",
+                "Values",
+                Vec::new(),
+                Some(ChannelData::F64(vec![1.5])),
+            ),
+        ])
+        .unwrap();
+    let tdms_file = TdmsFile::from_bytes(writer.into_inner().unwrap()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channels = group.channels().collect::<Vec<_>>();
+
+    let bytes = write_npz(&channels, Cursor::new(Vec::new()))
+        .unwrap()
+        .into_inner();
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+    assert_eq!(archive.len(), 2);
+
+    let (header, data) = read_array(&mut archive, "Group/Empty.npy");
+    assert_eq!(
+        header,
+        "{'descr': '<f8', 'fortran_order': False, 'shape': (0,), }"
+    );
+    assert!(data.is_empty());
+
+    let (_, data) = read_array(&mut archive, "Group/Values.npy");
+    assert_eq!(data, 1.5f64.to_le_bytes());
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "object_store")]
+
+extern crate hex_literal;
+
+mod common;
+
+use common::*;
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::{ObjectStore, PutPayload};
+use std::sync::Arc;
+
+use rstdms::{ObjectStoreReader, TdmsFileAsync};
+
+async fn store_test_file(test_file: TestFile) -> (Arc<dyn ObjectStore>, Path) {
+    let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+    let location = Path::from("data/test.tdms");
+    let bytes = test_file.into_cursor().into_inner();
+    store.put(&location, PutPayload::from(bytes)).await.unwrap();
+    (store, location)
+}
+
+fn multi_segment_test_file() -> TestFile {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 3), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 3), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32((1..7).collect()));
+    test_file.add_segment(
+        TOC_RAW_DATA,
+        &Vec::new(),
+        &data_bytes_i32((11..17).collect()),
+    );
+    test_file
+}
+
+#[tokio::test]
+async fn read_from_object_store() {
+    let (store, location) = store_test_file(multi_segment_test_file()).await;
+
+    let reader = ObjectStoreReader::new(store, location).await.unwrap();
+    let tdms_file = TdmsFileAsync::new(reader).await.unwrap();
+
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel2").unwrap();
+    let mut data: Vec<i32> = vec![0; channel.len() as usize];
+    channel.read_all_data(&mut data[..]).await.unwrap();
+
+    assert_eq!(data, vec![4, 5, 6, 14, 15, 16]);
+}
+
+#[tokio::test]
+async fn read_from_object_store_with_small_blocks() {
+    let (store, location) = store_test_file(multi_segment_test_file()).await;
+
+    let reader = ObjectStoreReader::new(store, location)
+        .await
+        .unwrap()
+        .block_size(5);
+    let tdms_file = TdmsFileAsync::new(reader).await.unwrap();
+
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+    let mut data: Vec<i32> = vec![0; channel.len() as usize];
+    channel.read_all_data(&mut data[..]).await.unwrap();
+
+    assert_eq!(data, vec![1, 2, 3, 11, 12, 13]);
+}
+
+#[tokio::test]
+async fn missing_object() {
+    let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+
+    let result = ObjectStoreReader::new(store, Path::from("missing.tdms")).await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "rayon")]
+
+extern crate hex_literal;
+
+mod common;
+
+use common::*;
+use std::io::Cursor;
+
+use rstdms::{Channel, TdmsFile};
+
+#[test]
+fn read_channels_in_parallel() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 3), Vec::new()),
+        object_metadata("/'Group'/'Channel3'", &raw_data_index(3, 4), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32((1..10).collect()),
+    );
+    test_file.add_segment(
+        TOC_RAW_DATA,
+        &Vec::new(),
+        &data_bytes_i32((11..20).collect()),
+    );
+    let bytes = test_file.into_cursor().into_inner();
+
+    let tdms_file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channels: Vec<Channel<_>> = group.channels().collect();
+    let mut data: Vec<Vec<i32>> = channels
+        .iter()
+        .map(|channel| vec![0; channel.len() as usize])
+        .collect();
+    let mut buffers: Vec<&mut [i32]> = data.iter_mut().map(|d| &mut d[..]).collect();
+
+    tdms_file
+        .read_channels_parallel(&channels, &mut buffers, || Ok(Cursor::new(bytes.clone())))
+        .unwrap();
+
+    assert_eq!(
+        data,
+        vec![
+            vec![1, 2, 11, 12],
+            vec![3, 4, 5, 13, 14, 15],
+            vec![6, 7, 8, 9, 16, 17, 18, 19],
+        ]
+    );
+}
+
+#[test]
+fn read_channels_in_parallel_with_wrong_type() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    let bytes = test_file.into_cursor().into_inner();
+
+    let tdms_file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channels: Vec<Channel<_>> = group.channels().collect();
+    let mut data = [0.0f64; 2];
+
+    let result = tdms_file.read_channels_parallel(&channels, &mut [&mut data[..]], || {
+        Ok(Cursor::new(bytes.clone()))
+    });
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn read_channels_in_parallel_with_positioned_reads() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA | TOC_INTERLEAVED_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3, 4]));
+    let bytes = test_file.into_cursor().into_inner();
+
+    let tdms_file = TdmsFile::new(Cursor::new(bytes.clone())).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channels: Vec<Channel<_>> = group.channels().collect();
+    let mut data = vec![vec![0i32; 2], vec![0i32; 2]];
+    let mut buffers: Vec<&mut [i32]> = data.iter_mut().map(|d| &mut d[..]).collect();
+
+    tdms_file
+        .read_channels_parallel_at(&channels, &mut buffers, &bytes)
+        .unwrap();
+
+    assert_eq!(data, vec![vec![1, 3], vec![2, 4]]);
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "parquet")]
+
+extern crate hex_literal;
+
+mod common;
+
+use common::*;
+use hex_literal::hex;
+use polars::prelude::{ParquetReader, SerReader};
+use std::io::Cursor;
+
+use rstdms::TdmsFile;
+
+#[test]
+fn write_group_as_parquet() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/'Group'",
+            &hex!("FF FF FF FF"),
+            vec![("description", 0x20, &hex!("03 00 00 00 61 62 63"))],
+        ),
+        object_metadata(
+            "/'Group'/'Channel1'",
+            &raw_data_index(3, 3),
+            vec![("unit_string", 0x20, &hex!("01 00 00 00 56"))],
+        ),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![1, 2, 3, 11, 12]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let mut bytes = Vec::new();
+    group.write_parquet(&mut bytes, false).unwrap();
+
+    let mut reader = ParquetReader::new(Cursor::new(bytes));
+    let metadata = reader
+        .get_metadata()
+        .unwrap()
+        .key_value_metadata()
+        .clone()
+        .unwrap()
+        .into_iter()
+        .filter(|kv| kv.key.starts_with('/'))
+        .map(|kv| (kv.key, kv.value.unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        metadata,
+        vec![
+            ("/'Group':description".to_owned(), "abc".to_owned()),
+            ("/'Group'/'Channel1':unit_string".to_owned(), "V".to_owned()),
+        ]
+    );
+
+    let df = reader.finish().unwrap();
+    assert_eq!(df.shape(), (3, 2));
+    assert_eq!(df.get_column_names(), vec!["Channel1", "Channel2"]);
+    assert_eq!(
+        df.column("Channel2")
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![Some(11), Some(12), None]
+    );
+}
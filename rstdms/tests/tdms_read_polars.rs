@@ -0,0 +1,59 @@
+#![cfg(feature = "polars")]
+
+extern crate hex_literal;
+
+mod common;
+
+use common::*;
+use polars::prelude::DataType;
+
+use rstdms::TdmsFile;
+
+#[test]
+fn read_group_into_dataframe() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/'Group'/'Channel1'",
+            &raw_data_index(3, 3),
+            vec![("wf_increment", 10, &0.5_f64.to_le_bytes())],
+        ),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![1, 2, 3, 11, 12]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+
+    let df = group.to_polars(false).unwrap();
+    assert_eq!(df.shape(), (3, 2));
+    assert_eq!(df.get_column_names(), vec!["Channel1", "Channel2"]);
+    let channel1 = df.column("Channel1").unwrap();
+    assert_eq!(channel1.dtype(), &DataType::Int32);
+    assert_eq!(
+        channel1.i32().unwrap().into_iter().collect::<Vec<_>>(),
+        vec![Some(1), Some(2), Some(3)]
+    );
+    let channel2 = df.column("Channel2").unwrap();
+    assert_eq!(
+        channel2.i32().unwrap().into_iter().collect::<Vec<_>>(),
+        vec![Some(11), Some(12), None]
+    );
+
+    let df = group.to_polars(true).unwrap();
+    assert_eq!(df.get_column_names(), vec!["Time", "Channel1", "Channel2"]);
+    assert_eq!(
+        df.column("Time")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec![Some(0.0), Some(0.5), Some(1.0)]
+    );
+}
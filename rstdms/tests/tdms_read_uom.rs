@@ -0,0 +1,65 @@
+#![cfg(feature = "uom")]
+
+extern crate hex_literal;
+
+mod common;
+
+use common::*;
+use hex_literal::hex;
+use uom::si::electric_potential::volt;
+use uom::si::f64::{ElectricPotential, Length};
+
+use rstdms::{BaseUnit, TdmsFile, TdmsReadError};
+
+#[test]
+fn read_channel_as_quantities() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/'Group'/'Channel1'",
+            &raw_data_index(3, 2),
+            vec![("unit_string", 0x20, &hex!("02 00 00 00 6D 56"))],
+        ),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 2), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(
+        toc_mask,
+        &metadata_bytes,
+        &data_bytes_i32(vec![1500, -250, 1, 2]),
+    );
+
+    let tdms_file = TdmsFile::new(test_file.into_cursor()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Channel1").unwrap();
+
+    let unit = channel.unit().unwrap();
+    assert_eq!(unit.base, BaseUnit::Volt);
+    assert_eq!(unit.multiplier, 1e-3);
+
+    let values = channel.read_quantities::<ElectricPotential>().unwrap();
+    assert_eq!(
+        values.iter().map(|v| v.get::<volt>()).collect::<Vec<_>>(),
+        vec![1.5, -0.25]
+    );
+
+    let result = channel.read_quantities::<Length>();
+    assert!(matches!(
+        result,
+        Err(TdmsReadError::IncompatibleUnit {
+            quantity: "length",
+            ..
+        })
+    ));
+
+    let channel = group.channel("Channel2").unwrap();
+    assert_eq!(channel.unit(), None);
+    let result = channel.read_quantities::<ElectricPotential>();
+    assert!(matches!(
+        result,
+        Err(TdmsReadError::MissingProperty {
+            name: "unit_string",
+            ..
+        })
+    ));
+}
@@ -0,0 +1,149 @@
+extern crate hex_literal;
+
+mod common;
+
+use common::*;
+use hex_literal::hex;
+
+use rstdms::{ChannelData, ReadOptions, TdmsReadError, TdmsStreamReader, TdmsValue};
+
+#[test]
+fn stream_segments_without_seeking() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![
+        object_metadata(
+            "/",
+            &hex!("FF FF FF FF"),
+            vec![("test_property", 3, &10_i32.to_le_bytes())],
+        ),
+        object_metadata("/'Group'/'Channel1'", &raw_data_index(3, 2), Vec::new()),
+        object_metadata("/'Group'/'Channel2'", &raw_data_index(3, 1), Vec::new()),
+    ]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![4, 5, 6]));
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel2'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    test_file.add_segment(
+        TOC_METADATA | TOC_RAW_DATA | TOC_INTERLEAVED_DATA,
+        &metadata_bytes,
+        &data_bytes_i32(vec![7, 8, 9, 10]),
+    );
+    let bytes = test_file.into_cursor().into_inner();
+
+    // A byte slice reader does not implement Seek
+    let mut stream_reader = TdmsStreamReader::new(&bytes[..]);
+    let chunks: Vec<(String, ChannelData)> = stream_reader
+        .by_ref()
+        .map(|chunk| {
+            let chunk = chunk.unwrap();
+            assert_eq!(chunk.group_name, "Group");
+            (chunk.channel_name, chunk.data)
+        })
+        .collect();
+
+    assert_eq!(
+        chunks,
+        vec![
+            ("Channel1".to_string(), ChannelData::I32(vec![1, 2])),
+            ("Channel2".to_string(), ChannelData::I32(vec![3])),
+            ("Channel1".to_string(), ChannelData::I32(vec![4, 5])),
+            ("Channel2".to_string(), ChannelData::I32(vec![6])),
+            ("Channel1".to_string(), ChannelData::I32(vec![7, 9])),
+            ("Channel2".to_string(), ChannelData::I32(vec![8, 10])),
+        ]
+    );
+    assert_eq!(stream_reader.properties()[0].value, TdmsValue::Int32(10));
+    assert_eq!(
+        stream_reader
+            .channel_properties("Group", "Channel1")
+            .map(|p| p.len()),
+        Some(0)
+    );
+}
+
+#[test]
+fn stream_truncated_data() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    let mut bytes = test_file.into_cursor().into_inner();
+    bytes.truncate(bytes.len() - 2);
+
+    let results: Vec<_> = TdmsStreamReader::new(&bytes[..]).collect();
+
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
+
+#[test]
+fn stream_incomplete_final_segment() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_incomplete_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2, 3]));
+    let bytes = test_file.into_cursor().into_inner();
+
+    let chunks: Vec<ChannelData> = TdmsStreamReader::new(&bytes[..])
+        .map(|chunk| chunk.unwrap().data)
+        .collect();
+
+    assert_eq!(chunks, vec![ChannelData::I32(vec![1, 2])]);
+}
+
+#[test]
+fn stream_segment_exceeding_memory_limit() {
+    let mut test_file = TestFile::new();
+    let metadata_bytes = metadata(vec![object_metadata(
+        "/'Group'/'Channel1'",
+        &raw_data_index(3, 2),
+        Vec::new(),
+    )]);
+    let toc_mask = TOC_METADATA | TOC_NEW_OBJ_LIST | TOC_RAW_DATA;
+    test_file.add_segment(toc_mask, &metadata_bytes, &data_bytes_i32(vec![1, 2]));
+    test_file.add_segment(TOC_RAW_DATA, &Vec::new(), &data_bytes_i32(vec![3, 4, 5, 6]));
+    let bytes = test_file.into_cursor().into_inner();
+
+    let options = ReadOptions::new().memory_limit(8);
+    let mut stream_reader = TdmsStreamReader::with_options(&bytes[..], &options);
+
+    let chunk = stream_reader.next().unwrap().unwrap();
+    assert_eq!(chunk.data, ChannelData::I32(vec![1, 2]));
+    let result = stream_reader.next().unwrap();
+    assert!(matches!(
+        result,
+        Err(TdmsReadError::MemoryLimitExceeded {
+            required: 16,
+            limit: 8
+        })
+    ));
+}
+
+#[test]
+fn stream_segment_with_invalid_metadata_size() {
+    // The lead in declares an exabyte of metadata, but the stream ends after a few bytes
+    let mut bytes = hex!("54 44 53 6D 0E 00 00 00 69 12 00 00").to_vec();
+    bytes.extend(u64::MAX.to_le_bytes());
+    bytes.extend((1_u64 << 60).to_le_bytes());
+    bytes.extend(hex!("01 00 00 00"));
+
+    let results: Vec<_> = TdmsStreamReader::new(&bytes[..]).collect();
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(
+        results[0].as_ref().map_err(|err| err.without_context()),
+        Err(TdmsReadError::IoError(_))
+    ));
+}
@@ -0,0 +1,544 @@
+use rstdms::{
+    write_index, write_line_protocol, write_ndjson, ChannelData, ReadOptions, TdmsFile,
+    TdmsProperty, TdmsReadError, TdmsValue, TdmsWriter, Timestamp, WriteObject,
+};
+use std::fs::{File, OpenOptions};
+use std::io::{Cursor, Write};
+
+fn property(name: &str, value: TdmsValue) -> TdmsProperty {
+    TdmsProperty {
+        name: name.to_owned(),
+        value,
+    }
+}
+
+#[test]
+fn write_and_read_file() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[
+            WriteObject::root(vec![property("name", TdmsValue::String("test".to_owned()))]),
+            WriteObject::group("Group", vec![property("count", TdmsValue::Int32(2))]),
+            WriteObject::channel(
+                "Group",
+                "Floats",
+                vec![property("unit_string", TdmsValue::String("V".to_owned()))],
+                Some(ChannelData::F64(vec![1.0, 2.5])),
+            ),
+            WriteObject::channel(
+                "Group",
+                "Strings",
+                Vec::new(),
+                Some(ChannelData::String(vec!["a".to_owned(), "bcd".to_owned()])),
+            ),
+        ])
+        .unwrap();
+    writer
+        .write_segment(&[
+            WriteObject::channel(
+                "Group",
+                "Floats",
+                Vec::new(),
+                Some(ChannelData::F64(vec![3.0])),
+            ),
+            WriteObject::channel(
+                "Group",
+                "Times",
+                Vec::new(),
+                Some(ChannelData::Timestamp(vec![Timestamp::new(10, 1 << 63)])),
+            ),
+        ])
+        .unwrap();
+    let bytes = writer.into_inner().unwrap();
+
+    let tdms_file = TdmsFile::from_bytes(bytes).unwrap();
+    assert_eq!(tdms_file.name(), Some("test"));
+    let group = tdms_file.group("Group").unwrap();
+    assert_eq!(group.property("count"), Some(&TdmsValue::Int32(2)));
+    assert_eq!(group.channel_names(), vec!["Floats", "Strings", "Times"]);
+
+    let channel = group.channel("Floats").unwrap();
+    assert_eq!(channel.unit_string(), Some("V"));
+    assert_eq!(
+        channel.read_data().unwrap(),
+        ChannelData::F64(vec![1.0, 2.5, 3.0])
+    );
+    assert_eq!(
+        group.channel("Strings").unwrap().read_data().unwrap(),
+        ChannelData::String(vec!["a".to_owned(), "bcd".to_owned()])
+    );
+    assert_eq!(
+        group.channel("Times").unwrap().read_data().unwrap(),
+        ChannelData::Timestamp(vec![Timestamp::new(10, 1 << 63)])
+    );
+}
+
+#[test]
+fn write_string_data_with_end_offsets() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    for strings in [vec!["ab", "", "cde"], vec!["fghi"]] {
+        let strings = strings.into_iter().map(|s| s.to_owned()).collect();
+        writer
+            .write_segment(&[WriteObject::channel(
+                "Group",
+                "Strings",
+                Vec::new(),
+                Some(ChannelData::String(strings)),
+            )])
+            .unwrap();
+    }
+    let bytes = writer.into_inner().unwrap();
+
+    let tdms_file = TdmsFile::from_bytes(bytes).unwrap();
+    let channel = tdms_file
+        .group("Group")
+        .unwrap()
+        .channel("Strings")
+        .unwrap();
+    let mut raw_data = Vec::new();
+    channel.write_raw_data(&mut raw_data).unwrap();
+
+    let mut expected = Vec::new();
+    for offset in [2_u32, 2, 5] {
+        expected.extend(offset.to_le_bytes());
+    }
+    expected.extend(b"abcde");
+    expected.extend(4_u32.to_le_bytes());
+    expected.extend(b"fghi");
+    assert_eq!(raw_data, expected);
+}
+
+#[test]
+fn rewrite_file_properties() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[
+            WriteObject::root(vec![
+                property("name", TdmsValue::String("test".to_owned())),
+                property("operator", TdmsValue::String("someone".to_owned())),
+            ]),
+            WriteObject::group("Group", vec![property("operator", TdmsValue::Int32(2))]),
+            WriteObject::channel(
+                "Group",
+                "Floats",
+                vec![property("unit_string", TdmsValue::String("V".to_owned()))],
+                Some(ChannelData::F64(vec![1.0, 2.5])),
+            ),
+        ])
+        .unwrap();
+    let tdms_file = TdmsFile::from_bytes(writer.into_inner().unwrap()).unwrap();
+
+    let mut paths = Vec::new();
+    let bytes = tdms_file
+        .rewrite(Vec::new(), |path, properties| {
+            paths.push(path.to_owned());
+            properties.retain(|property| property.name != "operator");
+        })
+        .unwrap();
+    assert_eq!(paths, vec!["/", "/'Group'", "/'Group'/'Floats'"]);
+
+    let tdms_file = TdmsFile::from_bytes(bytes).unwrap();
+    assert_eq!(tdms_file.name(), Some("test"));
+    assert_eq!(tdms_file.property("operator"), None);
+    let group = tdms_file.group("Group").unwrap();
+    assert!(group.properties().is_empty());
+    let channel = group.channel("Floats").unwrap();
+    assert_eq!(channel.unit_string(), Some("V"));
+    assert_eq!(
+        channel.read_data().unwrap(),
+        ChannelData::F64(vec![1.0, 2.5])
+    );
+}
+
+#[test]
+fn rewrite_file_with_renamed_objects() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[
+            WriteObject::root(vec![property("name", TdmsValue::String("test".to_owned()))]),
+            WriteObject::group("Grop", vec![property("count", TdmsValue::Int32(2))]),
+            WriteObject::channel(
+                "Grop",
+                "Voltag",
+                vec![property("unit_string", TdmsValue::String("V".to_owned()))],
+                Some(ChannelData::F64(vec![1.0, 2.5])),
+            ),
+            WriteObject::channel(
+                "Grop",
+                "Current",
+                Vec::new(),
+                Some(ChannelData::F64(vec![0.5, 0.25])),
+            ),
+        ])
+        .unwrap();
+    let tdms_file = TdmsFile::from_bytes(writer.into_inner().unwrap()).unwrap();
+
+    let bytes = tdms_file
+        .rewrite_renamed(
+            Vec::new(),
+            |group_name| group_name.replace("Grop", "Group"),
+            |group_name, channel_name| match (group_name, channel_name) {
+                ("Grop", "Voltag") => "Voltage".to_owned(),
+                _ => channel_name.to_owned(),
+            },
+        )
+        .unwrap();
+
+    let tdms_file = TdmsFile::from_bytes(bytes).unwrap();
+    assert_eq!(tdms_file.name(), Some("test"));
+    assert!(tdms_file.group("Grop").is_none());
+    let group = tdms_file.group("Group").unwrap();
+    assert_eq!(group.property("count"), Some(&TdmsValue::Int32(2)));
+    assert_eq!(group.channel_names(), vec!["Voltage", "Current"]);
+    let channel = group.channel("Voltage").unwrap();
+    assert_eq!(channel.unit_string(), Some("V"));
+    assert_eq!(
+        channel.read_data().unwrap(),
+        ChannelData::F64(vec![1.0, 2.5])
+    );
+    assert_eq!(
+        group.channel("Current").unwrap().read_data().unwrap(),
+        ChannelData::F64(vec![0.5, 0.25])
+    );
+}
+
+#[test]
+fn append_properties_segment() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[
+            WriteObject::root(vec![property("name", TdmsValue::String("test".to_owned()))]),
+            WriteObject::group("Group", Vec::new()),
+            WriteObject::channel(
+                "Group",
+                "Floats",
+                vec![
+                    property("unit_string", TdmsValue::String("v".to_owned())),
+                    property("gain", TdmsValue::Float64(2.0)),
+                ],
+                Some(ChannelData::F64(vec![1.0, 2.5])),
+            ),
+            WriteObject::channel(
+                "Group",
+                "Ints",
+                Vec::new(),
+                Some(ChannelData::I32(vec![1, 2])),
+            ),
+        ])
+        .unwrap();
+    writer
+        .write_properties(&[
+            WriteObject::root(vec![property(
+                "operator",
+                TdmsValue::String("someone".to_owned()),
+            )]),
+            WriteObject::channel(
+                "Group",
+                "Floats",
+                vec![property("unit_string", TdmsValue::String("V".to_owned()))],
+                None,
+            ),
+        ])
+        .unwrap();
+    let tdms_file = TdmsFile::from_bytes(writer.into_inner().unwrap()).unwrap();
+
+    assert_eq!(tdms_file.name(), Some("test"));
+    assert_eq!(
+        tdms_file.property("operator"),
+        Some(&TdmsValue::String("someone".to_owned()))
+    );
+    let group = tdms_file.group("Group").unwrap();
+    let channel = group.channel("Floats").unwrap();
+    assert_eq!(channel.unit_string(), Some("V"));
+    assert_eq!(channel.property("gain"), Some(&TdmsValue::Float64(2.0)));
+    assert_eq!(
+        channel.read_data().unwrap(),
+        ChannelData::F64(vec![1.0, 2.5])
+    );
+    assert_eq!(
+        group.channel("Ints").unwrap().read_data().unwrap(),
+        ChannelData::I32(vec![1, 2])
+    );
+}
+
+#[test]
+fn write_index_file() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    let objects = [WriteObject::channel(
+        "Group",
+        "Ints",
+        Vec::new(),
+        Some(ChannelData::I32(vec![1, 2, 3])),
+    )];
+    writer.write_segment(&objects).unwrap();
+    writer.write_segment(&objects).unwrap();
+    let bytes = writer.into_inner().unwrap();
+    // Each segment has 12 bytes of raw data
+    let segment_length = bytes.len() / 2;
+    let metadata_length = segment_length - 28 - 12;
+
+    let index = write_index(&mut Cursor::new(&bytes), Vec::new()).unwrap();
+    assert_eq!(index.len(), 2 * (28 + metadata_length));
+    for segment_index in 0..2 {
+        let index_segment = &index[segment_index * (28 + metadata_length)..];
+        let file_segment = &bytes[segment_index * segment_length..];
+        assert_eq!(&index_segment[..4], b"TDSh");
+        assert_eq!(
+            &index_segment[4..28 + metadata_length],
+            &file_segment[4..28 + metadata_length]
+        );
+    }
+}
+
+#[test]
+fn read_metadata_from_index_file() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[WriteObject::channel(
+            "Group",
+            "Ints",
+            vec![property("unit_string", TdmsValue::String("V".to_owned()))],
+            Some(ChannelData::I32(vec![1, 2, 3])),
+        )])
+        .unwrap();
+    writer
+        .write_segment(&[
+            WriteObject::channel("Group", "Ints", Vec::new(), Some(ChannelData::I32(vec![4]))),
+            WriteObject::channel(
+                "Group",
+                "Floats",
+                Vec::new(),
+                Some(ChannelData::F64(vec![0.5, 1.5])),
+            ),
+        ])
+        .unwrap();
+    let bytes = writer.into_inner().unwrap();
+    let index = write_index(&mut Cursor::new(&bytes), Vec::new()).unwrap();
+
+    let tdms_file = TdmsFile::new(Cursor::new(&bytes)).unwrap();
+    let indexed_file = TdmsFile::with_index(
+        Cursor::new(&bytes),
+        Cursor::new(&index),
+        &ReadOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(indexed_file.metadata(), tdms_file.metadata());
+    let group = indexed_file.group("Group").unwrap();
+    assert_eq!(
+        group.channel("Ints").unwrap().read_data().unwrap(),
+        ChannelData::I32(vec![1, 2, 3, 4])
+    );
+    assert_eq!(
+        group.channel("Floats").unwrap().read_data().unwrap(),
+        ChannelData::F64(vec![0.5, 1.5])
+    );
+
+    // A data file is not a valid index
+    let result = TdmsFile::with_index(
+        Cursor::new(&bytes),
+        Cursor::new(&bytes),
+        &ReadOptions::default(),
+    );
+    assert!(matches!(
+        result,
+        Err(TdmsReadError::InvalidSegmentHeader { .. })
+    ));
+}
+
+#[test]
+fn refresh_appended_segments() {
+    let path = std::env::temp_dir().join(format!("rstdms_refresh_{}.tdms", std::process::id()));
+    let objects = [WriteObject::channel(
+        "Group",
+        "Ints",
+        Vec::new(),
+        Some(ChannelData::I32(vec![1, 2, 3])),
+    )];
+    let mut writer = TdmsWriter::new(File::create(&path).unwrap());
+    writer.write_segment(&objects).unwrap();
+    writer.into_inner().unwrap();
+
+    let mut tdms_file = TdmsFile::new(File::open(&path).unwrap()).unwrap();
+    assert_eq!(tdms_file.refresh().unwrap(), 0);
+
+    let mut segment = TdmsWriter::new(Vec::new());
+    segment.write_segment(&objects).unwrap();
+    let segment = segment.into_inner().unwrap();
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(&segment).unwrap();
+    // Write a partial segment that should not be read until it is complete
+    file.write_all(&segment[..segment.len() - 4]).unwrap();
+
+    assert_eq!(tdms_file.refresh().unwrap(), 1);
+    let channel_len = |tdms_file: &TdmsFile<File>| {
+        tdms_file
+            .group("Group")
+            .unwrap()
+            .channel("Ints")
+            .unwrap()
+            .len()
+    };
+    assert_eq!(channel_len(&tdms_file), 6);
+
+    file.write_all(&segment[segment.len() - 4..]).unwrap();
+    assert_eq!(tdms_file.refresh().unwrap(), 1);
+    assert_eq!(channel_len(&tdms_file), 9);
+    assert_eq!(
+        tdms_file
+            .group("Group")
+            .unwrap()
+            .channel("Ints")
+            .unwrap()
+            .read_data()
+            .unwrap(),
+        ChannelData::I32(vec![1, 2, 3, 1, 2, 3, 1, 2, 3])
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn write_channels_as_line_protocol() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[
+            WriteObject::channel(
+                "Group 1",
+                "Time",
+                Vec::new(),
+                Some(ChannelData::Timestamp(vec![
+                    Timestamp::from_unix_nanos(1_000),
+                    Timestamp::from_unix_nanos(2_000),
+                    Timestamp::from_unix_nanos(3_000),
+                ])),
+            ),
+            WriteObject::channel(
+                "Group 1",
+                "Value,1",
+                Vec::new(),
+                Some(ChannelData::F64(vec![1.5, f64::NAN, f64::NAN])),
+            ),
+            WriteObject::channel(
+                "Group 1",
+                "Label",
+                Vec::new(),
+                Some(ChannelData::String(vec!["a \"b\"".to_owned()])),
+            ),
+            WriteObject::channel(
+                "Waveform",
+                "Count",
+                vec![
+                    property(
+                        "wf_start_time",
+                        TdmsValue::Timestamp(Timestamp::from_unix_nanos(0)),
+                    ),
+                    property("wf_increment", TdmsValue::Float64(0.5)),
+                ],
+                Some(ChannelData::U64(vec![1, u64::MAX])),
+            ),
+        ])
+        .unwrap();
+    let tdms_file = TdmsFile::from_bytes(writer.into_inner().unwrap()).unwrap();
+    let groups = tdms_file.groups().collect::<Vec<_>>();
+    let channels = groups
+        .iter()
+        .flat_map(|group| group.channels())
+        .collect::<Vec<_>>();
+
+    let mut output = Vec::new();
+    write_line_protocol(&channels, &mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Group\\ 1 Value\\,1=1.5,Label=\"a \\\"b\\\"\" 1000\n\
+         Waveform Count=1i 0\n\
+         Waveform Count=18446744073709551615u 500000000\n"
+    );
+
+    let group = tdms_file.group("Group 1").unwrap();
+    let channels = group.channels().skip(1).collect::<Vec<_>>();
+    let result = write_line_protocol(&channels, &mut Vec::new());
+    assert!(matches!(result, Err(TdmsReadError::MissingProperty { .. })));
+}
+
+#[test]
+fn write_line_protocol_with_empty_channel() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[
+            WriteObject::channel("Waveform", "Empty", Vec::new(), None),
+            WriteObject::channel(
+                "Waveform",
+                "Count",
+                vec![
+                    property(
+                        "wf_start_time",
+                        TdmsValue::Timestamp(Timestamp::from_unix_nanos(0)),
+                    ),
+                    property("wf_increment", TdmsValue::Float64(0.5)),
+                ],
+                Some(ChannelData::I32(vec![1, 2])),
+            ),
+            WriteObject::channel("Empty group", "Empty", Vec::new(), None),
+        ])
+        .unwrap();
+    let tdms_file = TdmsFile::from_bytes(writer.into_inner().unwrap()).unwrap();
+    let groups = tdms_file.groups().collect::<Vec<_>>();
+    let channels = groups
+        .iter()
+        .flat_map(|group| group.channels())
+        .collect::<Vec<_>>();
+
+    let mut output = Vec::new();
+    write_line_protocol(&channels, &mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Waveform Count=1i 0
+Waveform Count=2i 500000000
+"
+    );
+}
+
+#[test]
+fn write_channels_as_ndjson() {
+    let mut writer = TdmsWriter::new(Vec::new());
+    writer
+        .write_segment(&[
+            WriteObject::channel(
+                "Group",
+                "Values",
+                vec![
+                    property(
+                        "wf_start_time",
+                        TdmsValue::Timestamp(Timestamp::from_unix_nanos(0)),
+                    ),
+                    property("wf_increment", TdmsValue::Float64(0.5)),
+                ],
+                Some(ChannelData::F64(vec![1.5, f64::NAN])),
+            ),
+            WriteObject::channel(
+                "Group",
+                "Labels",
+                Vec::new(),
+                Some(ChannelData::String(vec!["a \"b\"\n".to_owned()])),
+            ),
+        ])
+        .unwrap();
+    let tdms_file = TdmsFile::from_bytes(writer.into_inner().unwrap()).unwrap();
+    let group = tdms_file.group("Group").unwrap();
+    let channels = group.channels().collect::<Vec<_>>();
+
+    let mut output = Vec::new();
+    write_ndjson(&channels, &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(
+        output,
+        "{\"channel\":\"/'Group'/'Values'\",\"index\":0,\
+         \"time\":\"1970-01-01T00:00:00+00:00\",\"value\":1.5}\n\
+         {\"channel\":\"/'Group'/'Values'\",\"index\":1,\
+         \"time\":\"1970-01-01T00:00:00.500+00:00\",\"value\":null}\n\
+         {\"channel\":\"/'Group'/'Labels'\",\"index\":0,\"time\":null,\
+         \"value\":\"a \\\"b\\\"\\n\"}\n"
+    );
+    for line in output.lines() {
+        serde_json::from_str::<serde_json::Value>(line).unwrap();
+    }
+}